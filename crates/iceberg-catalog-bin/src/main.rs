@@ -5,6 +5,7 @@ use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::EnvFilter;
 
 mod healthcheck;
+mod policy;
 mod serve;
 mod wait_for_db;
 
@@ -19,6 +20,15 @@ struct Cli {
 enum Commands {
     /// Migrate the database
     Migrate {},
+    /// Re-encrypt all secrets stored in postgres with the active encryption key
+    RotateSecrets {
+        #[clap(
+            default_value_t = 100,
+            long,
+            help = "Number of secrets to re-encrypt per transaction."
+        )]
+        batch_size: u32,
+    },
     /// Wait for the database to be up and migrated
     WaitForDB {
         #[clap(
@@ -77,6 +87,11 @@ enum Commands {
     Version {},
     /// Get the OpenAPI specification of the Management API as yaml
     ManagementOpenapi {},
+    /// Work with the Cedar policies of the cedar authorization backend
+    Policy {
+        #[command(subcommand)]
+        command: policy::PolicyCommand,
+    },
 }
 
 #[tokio::main]
@@ -123,6 +138,28 @@ async fn main() -> anyhow::Result<()> {
             iceberg_catalog::implementations::postgres::migrate(&write_pool).await?;
             println!("Database migration complete.");
         }
+        Some(Commands::RotateSecrets { batch_size }) => {
+            print_info();
+            println!(
+                "Re-encrypting secrets with key '{}'...",
+                CONFIG.pg_encryption_key_id
+            );
+            let read_pool =
+                iceberg_catalog::implementations::postgres::get_reader_pool(CONFIG.to_pool_opts())
+                    .await?;
+            let write_pool =
+                iceberg_catalog::implementations::postgres::get_writer_pool(CONFIG.to_pool_opts())
+                    .await?;
+            let stats = iceberg_catalog::implementations::postgres::SecretsStore::from_pools(
+                read_pool, write_pool,
+            )?
+            .rotate_encryption_key(batch_size)
+            .await?;
+            println!(
+                "Secret rotation complete. Re-encrypted {} secrets and {} secret versions.",
+                stats.secrets, stats.secret_versions
+            );
+        }
         Some(Commands::Serve {}) => {
             print_info();
             tracing::info!("Starting server on 0.0.0.0:{}...", CONFIG.listen_port);
@@ -145,6 +182,11 @@ async fn main() -> anyhow::Result<()> {
             use utoipa::OpenApi;
             println!("{}", ManagementApiDoc::openapi().to_yaml()?)
         }
+        Some(Commands::Policy { command }) => {
+            if !policy::run(command).await? {
+                std::process::exit(1);
+            }
+        }
         None => {
             // Error out if no subcommand is provided.
             eprintln!("No subcommand provided. Use --help for more information.");
@@ -0,0 +1,93 @@
+use anyhow::anyhow;
+use clap::Subcommand;
+use iceberg_catalog::implementations::cedar::{
+    Action, PolicyEngine, PolicyPrincipal, PolicyResource,
+};
+use iceberg_catalog::CONFIG;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Subcommand)]
+pub(crate) enum PolicyCommand {
+    /// Evaluate a request against the Cedar policies
+    Test {
+        #[clap(
+            long,
+            help = "File containing the policies. Defaults to the configured policy file."
+        )]
+        policy_file: Option<PathBuf>,
+        #[clap(long, help = "Subject of the principal.")]
+        principal: String,
+        #[clap(long, help = "Group of the principal, can be repeated.")]
+        group: Vec<String>,
+        #[clap(long, help = "Additional claims of the token as JSON object.")]
+        claims: Option<String>,
+        #[clap(long, help = "Action to evaluate, i.e. LoadTable.")]
+        action: Action,
+        #[clap(long)]
+        project: Option<Uuid>,
+        #[clap(long)]
+        warehouse: Option<Uuid>,
+        #[clap(
+            long,
+            requires("warehouse"),
+            help = "Namespace with parts separated by '.'."
+        )]
+        namespace: Option<String>,
+        #[clap(long, requires("warehouse"), conflicts_with("view"))]
+        table: Option<Uuid>,
+        #[clap(long, requires("warehouse"))]
+        view: Option<Uuid>,
+    },
+}
+
+/// Runs the command, returns whether the request was allowed.
+pub(crate) async fn run(command: PolicyCommand) -> anyhow::Result<bool> {
+    match command {
+        PolicyCommand::Test {
+            policy_file,
+            principal,
+            group,
+            claims,
+            action,
+            project,
+            warehouse,
+            namespace,
+            table,
+            view,
+        } => {
+            let policy_file = policy_file
+                .or_else(|| CONFIG.cedar.as_ref().map(|c| c.policy_file.clone()))
+                .ok_or_else(|| anyhow!("No policy file provided or configured"))?;
+            let groups_claim = CONFIG
+                .cedar
+                .as_ref()
+                .map_or("groups", |c| c.groups_claim.as_str());
+            let claims = match claims {
+                Some(claims) => serde_json::from_str(&claims)?,
+                None => serde_json::json!({}),
+            };
+
+            let engine = PolicyEngine::from_file(&policy_file).await?;
+            let mut principal = PolicyPrincipal::new(&principal, &claims, groups_claim);
+            principal.groups.extend(group);
+            let resource = PolicyResource {
+                project_id: project,
+                warehouse_id: warehouse,
+                namespace: namespace.map(|ns| ns.split('.').map(str::to_string).collect()),
+                table_id: table,
+                view_id: view,
+            };
+
+            let decision = engine.evaluate(&principal, action, &resource)?;
+            println!("{}", if decision.allowed { "ALLOW" } else { "DENY" });
+            for reason in &decision.reasons {
+                println!("  determined by: {reason}");
+            }
+            for error in &decision.errors {
+                println!("  error: {error}");
+            }
+            Ok(decision.allowed)
+        }
+    }
+}
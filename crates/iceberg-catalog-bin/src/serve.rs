@@ -1,16 +1,23 @@
 use anyhow::{anyhow, Error};
 use iceberg_catalog::api::router::{new_full_router, serve as service_serve};
-use iceberg_catalog::implementations::postgres::{Catalog, CatalogState};
+use iceberg_catalog::implementations::cedar::{CedarAuthZHandler, CedarAuthZState};
+use iceberg_catalog::implementations::openfga::{OpenFGAAuthZHandler, OpenFGAState};
+use iceberg_catalog::implementations::postgres::{
+    Catalog, CatalogState, PostgresAuthZHandler, PostgresAuthZState,
+};
 use iceberg_catalog::implementations::{AllowAllAuthState, AllowAllAuthZHandler};
+use iceberg_catalog::service::auth::{AuthConfigHandler, AuthZHandler};
 use iceberg_catalog::service::contract_verification::ContractVerifiers;
 use iceberg_catalog::service::event_publisher::{
-    CloudEventBackend, CloudEventsPublisher, CloudEventsPublisherBackgroundTask, Message,
-    NatsBackend,
+    CloudEventBackend, CloudEventsPublisher, KafkaBackend, NatsBackend, OutboxRelay, WebhookBackend,
 };
-use iceberg_catalog::service::health::ServiceHealthProvider;
-use iceberg_catalog::service::secrets::Secrets;
+use iceberg_catalog::service::health::{HealthExt, ServiceHealthProvider};
+use iceberg_catalog::service::kubernetes_auth::KubernetesAuthenticator;
+use iceberg_catalog::service::secrets::{CachedSecretStore, Secrets};
+use iceberg_catalog::service::token_introspection::Introspector;
+use iceberg_catalog::service::token_issuer::TokenIssuer;
 use iceberg_catalog::service::token_verification::Verifier;
-use iceberg_catalog::{SecretBackend, CONFIG};
+use iceberg_catalog::{AuthZBackend, SecretBackend, CONFIG};
 use reqwest::Url;
 
 use std::sync::Arc;
@@ -31,15 +38,106 @@ pub(crate) async fn serve(bind_addr: std::net::SocketAddr) -> Result<(), anyhow:
         )
         .await?
         .into(),
+        SecretBackend::File => iceberg_catalog::implementations::file::SecretsState::from_config(
+            CONFIG
+                .file_secrets
+                .as_ref()
+                .ok_or_else(|| anyhow!("Need file secrets config to use files as backend"))?,
+        )
+        .await?
+        .into(),
         SecretBackend::Postgres => {
             iceberg_catalog::implementations::postgres::SecretsState::from_pools(
-                read_pool, write_pool,
-            )
+                read_pool.clone(),
+                write_pool.clone(),
+            )?
             .into()
         }
     };
-    let auth_state = AllowAllAuthState;
+    let secrets_state = CachedSecretStore::new(
+        secrets_state,
+        std::time::Duration::from_secs(CONFIG.secret_cache_ttl_seconds),
+        CONFIG.secret_cache_max_entries,
+    );
+
+    match CONFIG.authz_backend {
+        AuthZBackend::AllowAll => {
+            serve_with_authz::<AllowAllAuthZHandler>(
+                bind_addr,
+                AllowAllAuthState,
+                catalog_state,
+                secrets_state,
+            )
+            .await
+        }
+        AuthZBackend::OpenFGA => {
+            if !CONFIG.authentication_enabled() {
+                return Err(anyhow!(
+                    "The openfga authorization backend requires authentication"
+                ));
+            }
+            serve_with_authz::<OpenFGAAuthZHandler>(
+                bind_addr,
+                OpenFGAState::from_config(
+                    CONFIG
+                        .openfga
+                        .as_ref()
+                        .ok_or_else(|| anyhow!("Need openfga config to use openfga as backend"))?,
+                    read_pool,
+                )?,
+                catalog_state,
+                secrets_state,
+            )
+            .await
+        }
+        AuthZBackend::Cedar => {
+            if !CONFIG.authentication_enabled() {
+                return Err(anyhow!(
+                    "The cedar authorization backend requires authentication"
+                ));
+            }
+            serve_with_authz::<CedarAuthZHandler>(
+                bind_addr,
+                CedarAuthZState::from_config(
+                    CONFIG
+                        .cedar
+                        .as_ref()
+                        .ok_or_else(|| anyhow!("Need cedar config to use cedar as backend"))?,
+                    read_pool,
+                )
+                .await?,
+                catalog_state,
+                secrets_state,
+            )
+            .await
+        }
+        AuthZBackend::Postgres => {
+            if !CONFIG.authentication_enabled() {
+                return Err(anyhow!(
+                    "The postgres authorization backend requires authentication"
+                ));
+            }
+            serve_with_authz::<PostgresAuthZHandler>(
+                bind_addr,
+                PostgresAuthZState::from_pools(read_pool, write_pool),
+                catalog_state,
+                secrets_state,
+            )
+            .await
+        }
+    }
+}
 
+async fn serve_with_authz<A>(
+    bind_addr: std::net::SocketAddr,
+    auth_state: A::State,
+    catalog_state: CatalogState,
+    secrets_state: CachedSecretStore<Secrets>,
+) -> Result<(), anyhow::Error>
+where
+    A: AuthZHandler + AuthConfigHandler<A>,
+    A::State: HealthExt,
+{
     let health_provider = ServiceHealthProvider::new(
         vec![
             ("catalog", Arc::new(catalog_state.clone())),
@@ -57,49 +155,63 @@ pub(crate) async fn serve(bind_addr: std::net::SocketAddr) -> Result<(), anyhow:
         let nats_publisher = build_nats_client(nat_addr).await?;
         cloud_event_sinks
             .push(Arc::new(nats_publisher) as Arc<dyn CloudEventBackend + Sync + Send>);
-    } else {
-        tracing::info!("Running without publisher.");
-    };
+    }
 
-    let (tx, rx) = tokio::sync::mpsc::channel(1000);
+    if let Some(kafka_config) = &CONFIG.kafka {
+        tracing::info!(
+            "Running with kafka publisher, connecting to: {}",
+            kafka_config.brokers
+        );
+        let kafka_publisher = KafkaBackend::try_new(kafka_config)?;
+        cloud_event_sinks
+            .push(Arc::new(kafka_publisher) as Arc<dyn CloudEventBackend + Sync + Send>);
+    }
 
-    let x = CloudEventsPublisherBackgroundTask {
-        source: rx,
-        sinks: cloud_event_sinks,
-    };
+    let mut webhooks = CONFIG.webhooks.iter().collect::<Vec<_>>();
+    webhooks.sort_by_key(|(name, _)| name.as_str());
+    for (name, webhook_config) in webhooks {
+        tracing::info!(
+            "Running with webhook publisher '{name}', posting to: {}",
+            webhook_config.url
+        );
+        let webhook_publisher = WebhookBackend::try_new(name, webhook_config.clone())?;
+        cloud_event_sinks
+            .push(Arc::new(webhook_publisher) as Arc<dyn CloudEventBackend + Sync + Send>);
+    }
+
+    if cloud_event_sinks.is_empty() {
+        tracing::info!("Running without publisher.");
+    }
+
+    let publisher = CloudEventsPublisher::new();
+    let relay =
+        OutboxRelay::<Catalog>::new(catalog_state.clone(), publisher.clone(), cloud_event_sinks);
     let listener = tokio::net::TcpListener::bind(bind_addr).await?;
 
     let metrics_layer =
         iceberg_catalog::metrics::get_axum_layer_and_install_recorder(CONFIG.metrics_port)?;
 
-    let router =
-        new_full_router::<Catalog, Catalog, AllowAllAuthZHandler, AllowAllAuthZHandler, Secrets>(
-            auth_state,
-            catalog_state,
-            secrets_state,
-            CloudEventsPublisher::new(tx.clone()),
-            ContractVerifiers::new(vec![]),
-            if let Some(uri) = CONFIG.openid_provider_uri.clone() {
-                Some(Verifier::new(uri).await?)
-            } else {
-                None
-            },
-            health_provider,
-            Some(metrics_layer),
-        );
+    let router = new_full_router::<Catalog, Catalog, A, A, CachedSecretStore<Secrets>>(
+        auth_state,
+        catalog_state,
+        secrets_state,
+        publisher,
+        ContractVerifiers::new(vec![]),
+        verifier().await?,
+        health_provider,
+        Some(metrics_layer),
+    );
 
-    let publisher_handle = tokio::task::spawn(async move {
-        match x.publish().await {
-            Ok(_) => tracing::info!("Exiting publisher task"),
-            Err(e) => tracing::error!("Publisher task failed: {e}"),
-        };
-    });
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let relay_handle = tokio::task::spawn(relay.run(async move {
+        let _ = shutdown_rx.await;
+    }));
 
     service_serve(listener, router).await?;
 
-    tracing::debug!("Sending shutdown signal to event publisher.");
-    tx.send(Message::Shutdown).await?;
-    publisher_handle.await?;
+    tracing::debug!("Sending shutdown signal to event relay.");
+    let _ = shutdown_tx.send(());
+    relay_handle.await?;
 
     Ok(())
 }
@@ -135,3 +247,24 @@ async fn build_nats_client(nat_addr: &Url) -> Result<NatsBackend, Error> {
     };
     Ok(nats_publisher)
 }
+
+async fn verifier() -> anyhow::Result<Option<Verifier>> {
+    if !CONFIG.authentication_enabled() {
+        if CONFIG.token_issuer.is_some() {
+            tracing::warn!("Token issuer is configured but ignored, it requires authentication.");
+        }
+        return Ok(None);
+    }
+
+    let mut verifier = Verifier::from_providers(CONFIG.openid_providers()).await?;
+    if let Some(config) = &CONFIG.token_introspection {
+        verifier = verifier.with_introspector(Introspector::new(config));
+    }
+    if let Some(config) = &CONFIG.kubernetes_auth {
+        verifier = verifier.with_kubernetes(KubernetesAuthenticator::from_config(config)?);
+    }
+    if let Some(config) = &CONFIG.token_issuer {
+        verifier = verifier.with_token_issuer(TokenIssuer::from_config(config)?);
+    }
+    Ok(Some(verifier))
+}
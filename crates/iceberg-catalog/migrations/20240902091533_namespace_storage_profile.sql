@@ -0,0 +1,5 @@
+-- Optional storage profile override for all tabulars created below a namespace.
+-- If storage_profile is null, the storage profile of the warehouse is used.
+alter table namespace
+    add column storage_profile jsonb,
+    add column storage_secret_id uuid;
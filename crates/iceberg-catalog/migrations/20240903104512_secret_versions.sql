@@ -0,0 +1,15 @@
+alter table secret
+    add column version bigint not null default 1;
+
+create table secret_version (
+    secret_id uuid not null references secret (secret_id) on delete cascade,
+    version bigint not null,
+    "secret" bytea not null,
+    created_at timestamptz not null default now(),
+    primary key (secret_id, version)
+);
+
+-- Existing secrets become their first version
+insert into secret_version (secret_id, version, "secret", created_at)
+select secret_id, 1, "secret", coalesce(updated_at, created_at)
+from secret;
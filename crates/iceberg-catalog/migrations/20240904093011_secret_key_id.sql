@@ -0,0 +1,13 @@
+-- Existing secrets are encrypted with the single `pg_encryption_key`,
+-- which is part of the keyring as key "default".
+alter table secret
+    add column key_id text not null default 'default';
+
+alter table secret_version
+    add column key_id text not null default 'default';
+
+alter table secret
+    alter column key_id drop default;
+
+alter table secret_version
+    alter column key_id drop default;
@@ -0,0 +1,36 @@
+-- Order matters: privileges are compared, every privilege includes the ones before it.
+create type privilege as enum ('read', 'write', 'manage');
+create type grant_object_type as enum ('project', 'warehouse', 'namespace', 'tabular');
+create type role_member_type as enum ('user', 'group');
+
+create table "role" (
+    role_id uuid primary key default uuid_generate_v1mc(),
+    role_name text collate "case_insensitive" not null unique,
+    description text,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz
+);
+
+SELECT trigger_updated_at('"role"');
+
+create table role_member (
+    role_id uuid not null references "role" (role_id) on delete cascade,
+    member_type role_member_type not null,
+    member text not null,
+    primary key (role_id, member_type, member)
+);
+
+create index role_member_member_idx on role_member (member_type, member);
+
+-- Grants are not tied to the granted object by a foreign key, as objects of different
+-- types live in different tables.
+create table role_grant (
+    role_id uuid not null references "role" (role_id) on delete cascade,
+    object_type grant_object_type not null,
+    object_id uuid not null,
+    privilege privilege not null,
+    created_at timestamptz not null default now(),
+    primary key (role_id, object_type, object_id)
+);
+
+create index role_grant_object_idx on role_grant (object_type, object_id);
@@ -0,0 +1,16 @@
+-- Only the SHA-256 hash of a key is stored, the key itself is shown once on creation.
+create table api_key (
+    api_key_id uuid primary key default uuid_generate_v1mc(),
+    name text not null,
+    owner text not null,
+    warehouse_id uuid references warehouse (warehouse_id) on delete cascade,
+    key_hash bytea not null unique,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz,
+    expires_at timestamptz,
+    revoked_at timestamptz
+);
+
+SELECT trigger_updated_at('api_key');
+
+create index api_key_owner_idx on api_key (owner);
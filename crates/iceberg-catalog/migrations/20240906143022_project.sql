@@ -0,0 +1,26 @@
+create type project_status as enum ('active', 'inactive');
+
+create table project (
+    project_id uuid primary key default uuid_generate_v1mc(),
+    project_name text collate "case_insensitive" not null,
+    description text,
+    "status" project_status not null default 'active',
+    created_at timestamptz not null default now(),
+    updated_at timestamptz,
+    CONSTRAINT unique_project_name UNIQUE (project_name)
+);
+
+SELECT trigger_updated_at('project');
+
+-- Projects used to exist only implicitly as `project_id` of warehouses.
+insert into project (project_id, project_name)
+values ('00000000-0000-0000-0000-000000000000', 'Default Project');
+
+insert into project (project_id, project_name)
+select distinct project_id, project_id::text from warehouse
+on conflict do nothing;
+
+-- Projects can only be deleted once all their warehouses are deleted.
+alter table warehouse
+    add constraint warehouse_project_id_fk foreign key (project_id)
+        references project (project_id) on delete restrict;
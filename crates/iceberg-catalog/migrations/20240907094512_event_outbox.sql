@@ -0,0 +1,23 @@
+-- Events are written in the transaction of the change they describe
+-- and published by the relay afterwards.
+create table event_outbox (
+    sequence_id bigserial primary key,
+    event_id uuid not null unique,
+    event_type text not null,
+    -- Events with the same ordering key are published in order of their sequence_id.
+    ordering_key uuid not null,
+    data jsonb not null,
+    metadata jsonb not null,
+    created_at timestamptz not null default now(),
+    attempts integer not null default 0,
+    next_attempt_at timestamptz not null default now(),
+    last_error text,
+    -- Names of the backends the event was published to.
+    sent_to text[] not null default '{}',
+    sent_at timestamptz,
+    -- Set if publishing was given up. Such events don't block later events.
+    failed_at timestamptz
+);
+
+create index event_outbox_pending_idx on event_outbox (sequence_id) where sent_at is null and failed_at is null;
+create index event_outbox_sent_at_idx on event_outbox (sent_at) where sent_at is not null;
@@ -1,4 +1,6 @@
 pub mod v1 {
+    pub mod api_key;
+    pub mod role;
     pub mod warehouse;
     use axum::{Extension, Json, Router};
     use utoipa::OpenApi;
@@ -10,13 +12,18 @@ pub mod v1 {
 
     use crate::service::{Catalog, SecretStore, State};
     use axum::extract::{Path, Query, State as AxumState};
-    use axum::routing::{get, post};
+    use axum::routing::{delete, get, post};
     use warehouse::{
-        AzCredential, AzdlsProfile, CreateWarehouseRequest, CreateWarehouseResponse,
-        GetWarehouseResponse, ListProjectsResponse, ListWarehousesRequest, ListWarehousesResponse,
-        ProjectResponse, RenameWarehouseRequest, S3Credential, S3Profile, Service,
-        StorageCredential, StorageProfile, UpdateWarehouseCredentialRequest,
-        UpdateWarehouseStorageRequest, WarehouseStatus,
+        AzCredential, AzdlsProfile, CreateProjectRequest, CreateProjectResponse,
+        CreateWarehouseRequest, CreateWarehouseResponse, GetWarehouseResponse,
+        ListProjectsResponse, ListStorageCredentialVersionsResponse, ListWarehousesRequest,
+        ListWarehousesResponse, ProjectResponse, ProjectStatus, RenameProjectRequest,
+        RenameWarehouseRequest, RollbackStorageCredentialRequest, S3Credential, S3Profile,
+        S3ServerSideEncryption, SecretVersion, Service, StorageCredential, StorageProfile,
+        StorageValidationCheck, StorageValidationStatus, StorageValidationStep,
+        UpdateNamespaceStorageRequest, UpdateWarehouseCredentialRequest,
+        UpdateWarehouseStorageRequest, ValidateStorageRequest, ValidateStorageResponse,
+        WarehouseStatus,
     };
 
     #[derive(Debug, OpenApi)]
@@ -25,34 +32,86 @@ pub mod v1 {
             (name = "management", description = "Warehouse management operations")
         ),
         paths(
+            activate_project,
             activate_warehouse,
+            api_key::create_api_key,
+            api_key::list_api_keys,
+            api_key::revoke_api_key,
+            api_key::set_api_key_expiry,
+            create_project,
             create_warehouse,
+            role::create_role,
+            role::delete_role,
+            role::get_role,
+            role::grant,
+            role::list_grants,
+            role::list_roles,
+            role::revoke,
+            role::update_role_members,
+            deactivate_project,
             deactivate_warehouse,
+            delete_project,
             delete_warehouse,
             get_warehouse,
             list_projects,
+            list_storage_credential_versions,
             list_warehouses,
+            rename_project,
             rename_warehouse,
+            rollback_storage_credential,
+            update_namespace_storage,
             update_storage_credential,
-            update_storage_profile
+            update_storage_profile,
+            validate_storage
         ),
         components(schemas(
+            api_key::ApiKey,
+            api_key::CreateApiKeyRequest,
+            api_key::CreateApiKeyResponse,
+            api_key::ListApiKeysResponse,
+            api_key::SetApiKeyExpiryRequest,
             AzCredential,
             AzdlsProfile,
+            role::CreateRoleRequest,
+            role::Grant,
+            role::GrantObjectType,
+            role::GrantRequest,
+            role::ListGrantsResponse,
+            role::ListRolesResponse,
+            role::Privilege,
+            role::RevokeRequest,
+            role::Role,
+            role::RoleMember,
+            role::RoleMemberType,
+            role::UpdateRoleMembersRequest,
+            CreateProjectRequest,
+            CreateProjectResponse,
             CreateWarehouseRequest,
             CreateWarehouseResponse,
             GetWarehouseResponse,
             ListProjectsResponse,
+            ListStorageCredentialVersionsResponse,
             ListWarehousesRequest,
             ListWarehousesResponse,
             ProjectResponse,
+            ProjectStatus,
+            RenameProjectRequest,
             RenameWarehouseRequest,
+            RollbackStorageCredentialRequest,
             S3Credential,
             S3Profile,
+            S3ServerSideEncryption,
+            SecretVersion,
             StorageCredential,
             StorageProfile,
+            UpdateNamespaceStorageRequest,
             UpdateWarehouseCredentialRequest,
+            StorageValidationCheck,
+            StorageValidationStatus,
+            StorageValidationStep,
             UpdateWarehouseStorageRequest,
+            ValidateStorageRequest,
+            ValidateStorageResponse,
             WarehouseStatus
         ))
     )]
@@ -88,6 +147,123 @@ pub mod v1 {
         ApiServer::<C, A, S>::create_warehouse(request, api_context, metadata).await
     }
 
+    /// Validate a storage profile without creating a warehouse
+    ///
+    /// Runs the same checks as warehouse creation - normalization, writing,
+    /// reading and deleting a test file, and vended-credentials - without
+    /// persisting anything. The response reports the outcome of each step.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/storage/validate",
+        request_body = ValidateStorageRequest,
+        responses(
+            (status = 200, description = "Validation report", body = ValidateStorageResponse),
+        )
+    )]
+    async fn validate_storage<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<ValidateStorageRequest>,
+    ) -> Result<ValidateStorageResponse> {
+        ApiServer::<C, A, S>::validate_storage(request, api_context, metadata).await
+    }
+
+    /// Create a new project
+    ///
+    /// Warehouses can only be created in existing projects.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/project",
+        request_body = CreateProjectRequest,
+        responses(
+            (status = 201, description = "Project created successfully", body = [CreateProjectResponse]),
+        )
+    )]
+    async fn create_project<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<CreateProjectRequest>,
+    ) -> Result<CreateProjectResponse> {
+        ApiServer::<C, A, S>::create_project(request, api_context, metadata).await
+    }
+
+    /// Rename a project
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/project/{project_id}/rename",
+        request_body = RenameProjectRequest,
+        responses(
+            (status = 200, description = "Project renamed successfully")
+        )
+    )]
+    async fn rename_project<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<RenameProjectRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::rename_project(project_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// Deactivate a project
+    ///
+    /// No new warehouses can be created in an inactive project.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/project/{project_id}/deactivate",
+        responses(
+            (status = 200, description = "Project deactivated successfully")
+        )
+    )]
+    async fn deactivate_project<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::deactivate_project(project_id.into(), api_context, metadata).await
+    }
+
+    /// Activate a project
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/project/{project_id}/activate",
+        responses(
+            (status = 200, description = "Project activated successfully")
+        )
+    )]
+    async fn activate_project<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::activate_project(project_id.into(), api_context, metadata).await
+    }
+
+    /// Delete a project by ID
+    ///
+    /// Only empty projects can be deleted, delete all warehouses of the project first.
+    #[utoipa::path(
+        delete,
+        tag = "management",
+        path = "management/v1/project/{project_id}",
+        responses(
+            (status = 200, description = "Project deleted successfully")
+        )
+    )]
+    async fn delete_project<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::delete_project(project_id.into(), api_context, metadata).await
+    }
+
     /// List all existing projects
     #[utoipa::path(
         get,
@@ -253,13 +429,100 @@ pub mod v1 {
             .await
     }
 
+    /// List versions of the storage credential of a warehouse
+    ///
+    /// Every update of the storage credential creates a new version.
+    /// Only metadata is returned, never the credential itself.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/storage-credential/versions",
+        responses(
+            (status = 200, description = "Versions of the storage credential", body = ListStorageCredentialVersionsResponse)
+        )
+    )]
+    async fn list_storage_credential_versions<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<ListStorageCredentialVersionsResponse> {
+        ApiServer::<C, A, S>::list_credential_versions(warehouse_id.into(), api_context, metadata)
+            .await
+    }
+
+    /// Roll back the storage credential of a warehouse
+    ///
+    /// Restores a previous version of the storage credential. The restored
+    /// credential is validated against the storage profile and stored as a new version.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/storage-credential/rollback",
+        request_body = RollbackStorageCredentialRequest,
+        responses(
+            (status = 200, description = "Storage credential rolled back successfully")
+        )
+    )]
+    async fn rollback_storage_credential<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<RollbackStorageCredentialRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::rollback_credential(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Set or remove the storage profile of a namespace
+    ///
+    /// Tables created in the namespace, or in child namespaces without their own
+    /// override, use this storage profile instead of the storage profile of the
+    /// warehouse. This includes their location, FileIO, vended credentials and
+    /// remote signing. Views always use the storage profile of the warehouse.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/namespace/{namespace_id}/storage",
+        request_body = UpdateNamespaceStorageRequest,
+        responses(
+            (status = 200, description = "Namespace storage profile updated successfully")
+        )
+    )]
+    async fn update_namespace_storage<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path((warehouse_id, namespace_id)): Path<(uuid::Uuid, uuid::Uuid)>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<UpdateNamespaceStorageRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::update_namespace_storage(
+            warehouse_id.into(),
+            namespace_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
     impl<C: Catalog, A: AuthZHandler, S: SecretStore> ApiServer<C, A, S> {
         pub fn new_v1_router() -> Router<ApiContext<State<A, C, S>>> {
             Router::new()
                 // Create a new warehouse
                 .route("/warehouse", post(create_warehouse))
-                // List all projects
-                .route("/project", get(list_projects))
+                // Create and list projects
+                .route("/project", post(create_project).get(list_projects))
+                .route("/project/:project_id", delete(delete_project))
+                .route("/project/:project_id/rename", post(rename_project))
+                // Deactivate and activate projects
+                .route("/project/:project_id/deactivate", post(deactivate_project))
+                .route("/project/:project_id/activate", post(activate_project))
+                // Validate storage without persisting anything
+                .route("/storage/validate", post(validate_storage))
                 .route(
                     "/warehouse",
                     // List all warehouses within a project
@@ -292,6 +555,23 @@ pub mod v1 {
                     "/warehouse/:warehouse_id/storage-credential",
                     post(update_storage_credential),
                 )
+                // Versions of the storage credential
+                .route(
+                    "/warehouse/:warehouse_id/storage-credential/versions",
+                    get(list_storage_credential_versions),
+                )
+                // Restore a previous version of the storage credential
+                .route(
+                    "/warehouse/:warehouse_id/storage-credential/rollback",
+                    post(rollback_storage_credential),
+                )
+                // Set or remove the storage profile override of a namespace
+                .route(
+                    "/warehouse/:warehouse_id/namespace/:namespace_id/storage",
+                    post(update_namespace_storage),
+                )
+                // Create, list, expire and revoke API keys of the caller
+                .merge(api_key::new_v1_router())
         }
     }
 }
@@ -0,0 +1,343 @@
+use crate::api::{ApiContext, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::service::api_key::{self, generate_api_key, hash_api_key};
+use crate::service::auth::{AuthZHandler, UserWarehouse};
+use crate::service::token_verification::AuthDetails;
+use crate::service::{Catalog, SecretStore, State};
+use crate::WarehouseIdent;
+use axum::extract::{Path, State as AxumState};
+use axum::routing::{get, post};
+use axum::{Extension, Json, Router};
+use iceberg_ext::catalog::rest::ErrorModel;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ApiKey {
+    pub api_key_id: uuid::Uuid,
+    pub name: String,
+    /// Principal the key acts as, formatted as `<issuer>|<subject>`.
+    pub owner: String,
+    /// Warehouse the key is restricted to. Unrestricted if not set.
+    pub warehouse_id: Option<uuid::Uuid>,
+    #[schema(value_type = String)]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(value_type = Option<String>)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[schema(value_type = Option<String>)]
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<api_key::ApiKey> for ApiKey {
+    fn from(key: api_key::ApiKey) -> Self {
+        Self {
+            api_key_id: key.api_key_id,
+            name: key.name,
+            owner: key.owner,
+            warehouse_id: key.warehouse_id.map(|w| *w),
+            created_at: key.created_at,
+            expires_at: key.expires_at,
+            revoked_at: key.revoked_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateApiKeyRequest {
+    /// Name of the key, i.e. the job or tool using it.
+    pub name: String,
+    /// Restrict the key to the catalog API of this warehouse.
+    pub warehouse_id: Option<uuid::Uuid>,
+    /// Time after which the key is no longer accepted. Never expires if not set.
+    #[schema(value_type = Option<String>)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateApiKeyResponse {
+    /// The key to use as bearer token. It is only returned once and can't be recovered.
+    pub api_key: String,
+    #[serde(flatten)]
+    pub details: ApiKey,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetApiKeyExpiryRequest {
+    /// New expiry of the key. The key never expires if not set.
+    #[schema(value_type = Option<String>)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListApiKeysResponse {
+    pub api_keys: Vec<ApiKey>,
+}
+
+impl axum::response::IntoResponse for ApiKey {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for CreateApiKeyResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        (http::StatusCode::CREATED, axum::Json(self)).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ListApiKeysResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+/// Create a new API key
+///
+/// The key acts as the calling principal, but without the groups of the
+/// calling token. The key itself is only contained in this response.
+/// Requires authentication with a token, API keys can't be used to manage
+/// API keys. Keys created with a warehouse-scoped catalog token are
+/// restricted to that warehouse and expire with the token.
+#[utoipa::path(
+    post,
+    tag = "management",
+    path = "management/v1/api-key",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created successfully", body = CreateApiKeyResponse),
+    )
+)]
+pub(crate) async fn create_api_key<C: Catalog, A: AuthZHandler, S: SecretStore>(
+    AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+    Extension(metadata): Extension<RequestMetadata>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<CreateApiKeyResponse> {
+    let owner = owner(&metadata)?;
+    if request
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    {
+        return Err(ErrorModel::bad_request(
+            "expires-at must be in the future",
+            "InvalidApiKeyExpiry",
+            None,
+        )
+        .into());
+    }
+
+    let (warehouse_id, expires_at) = restrict_to_caller(
+        &metadata,
+        request.warehouse_id.map(Into::into),
+        request.expires_at,
+    )?;
+
+    let api_key = generate_api_key()?;
+    let details = C::create_api_key(
+        &request.name,
+        &owner,
+        warehouse_id,
+        &hash_api_key(&api_key),
+        expires_at,
+        api_context.v1_state.catalog,
+    )
+    .await?;
+
+    Ok(CreateApiKeyResponse {
+        api_key,
+        details: details.into(),
+    })
+}
+
+/// List the API keys of the calling principal
+///
+/// Includes revoked and expired keys.
+#[utoipa::path(
+    get,
+    tag = "management",
+    path = "management/v1/api-key",
+    responses(
+        (status = 200, description = "List of API keys", body = ListApiKeysResponse)
+    )
+)]
+pub(crate) async fn list_api_keys<C: Catalog, A: AuthZHandler, S: SecretStore>(
+    AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+    Extension(metadata): Extension<RequestMetadata>,
+) -> Result<ListApiKeysResponse> {
+    let owner = owner(&metadata)?;
+    let api_keys = C::list_api_keys(&owner, api_context.v1_state.catalog).await?;
+    Ok(ListApiKeysResponse {
+        api_keys: api_keys.into_iter().map(Into::into).collect(),
+    })
+}
+
+/// Set or remove the expiry of an API key
+#[utoipa::path(
+    post,
+    tag = "management",
+    path = "management/v1/api-key/{api_key_id}/expiry",
+    request_body = SetApiKeyExpiryRequest,
+    responses(
+        (status = 200, description = "API key updated successfully", body = ApiKey)
+    )
+)]
+pub(crate) async fn set_api_key_expiry<C: Catalog, A: AuthZHandler, S: SecretStore>(
+    Path(api_key_id): Path<uuid::Uuid>,
+    AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+    Extension(metadata): Extension<RequestMetadata>,
+    Json(request): Json<SetApiKeyExpiryRequest>,
+) -> Result<ApiKey> {
+    let owner = owner(&metadata)?;
+    let (_, expires_at) = restrict_to_caller(&metadata, None, request.expires_at)?;
+    let api_key =
+        C::set_api_key_expiry(api_key_id, &owner, expires_at, api_context.v1_state.catalog).await?;
+    Ok(api_key.into())
+}
+
+/// Revoke an API key
+///
+/// Revoked keys are no longer accepted and can't be restored.
+#[utoipa::path(
+    post,
+    tag = "management",
+    path = "management/v1/api-key/{api_key_id}/revoke",
+    responses(
+        (status = 200, description = "API key revoked successfully")
+    )
+)]
+pub(crate) async fn revoke_api_key<C: Catalog, A: AuthZHandler, S: SecretStore>(
+    Path(api_key_id): Path<uuid::Uuid>,
+    AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+    Extension(metadata): Extension<RequestMetadata>,
+) -> Result<()> {
+    let owner = owner(&metadata)?;
+    C::revoke_api_key(api_key_id, &owner, api_context.v1_state.catalog).await
+}
+
+/// Principal of the caller, who owns the keys it manages.
+fn owner(metadata: &RequestMetadata) -> Result<String> {
+    match &metadata.auth_details {
+        Some(AuthDetails::ApiKey(_)) => Err(ErrorModel::forbidden(
+            "API keys can't be used to manage API keys",
+            "Forbidden",
+            None,
+        )
+        .into()),
+        Some(auth_details) => Ok(auth_details.principal_id().to_string()),
+        None => Err(ErrorModel::builder()
+            .code(http::StatusCode::UNAUTHORIZED.into())
+            .message("API keys require authentication".to_string())
+            .r#type("UnauthorizedError".to_string())
+            .build()
+            .into()),
+    }
+}
+
+/// Restrict the warehouse and expiry of a key to the scope of the calling token.
+/// Callers with a warehouse-scoped catalog token can only manage keys of their
+/// warehouse that don't outlive the token.
+fn restrict_to_caller(
+    metadata: &RequestMetadata,
+    warehouse_id: Option<WarehouseIdent>,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<(
+    Option<WarehouseIdent>,
+    Option<chrono::DateTime<chrono::Utc>>,
+)> {
+    let Some(UserWarehouse {
+        warehouse_id: Some(scope),
+        ..
+    }) = UserWarehouse::from_catalog_token(metadata)
+    else {
+        return Ok((warehouse_id, expires_at));
+    };
+
+    if warehouse_id.is_some_and(|warehouse_id| warehouse_id != scope) {
+        return Err(ErrorModel::forbidden(
+            "Token is not valid for this warehouse",
+            "WarehouseScopeMismatch",
+            None,
+        )
+        .into());
+    }
+
+    let token_expiry = metadata.auth_details.as_ref().and_then(|auth_details| {
+        i64::try_from(auth_details.claims().exp)
+            .ok()
+            .and_then(|exp| chrono::DateTime::from_timestamp(exp, 0))
+    });
+    let expires_at = match (expires_at, token_expiry) {
+        (Some(expires_at), Some(token_expiry)) => Some(expires_at.min(token_expiry)),
+        (expires_at, token_expiry) => expires_at.or(token_expiry),
+    };
+
+    Ok((Some(scope), expires_at))
+}
+
+pub(crate) fn new_v1_router<C: Catalog, A: AuthZHandler, S: SecretStore>(
+) -> Router<ApiContext<State<A, C, S>>> {
+    Router::new()
+        .route("/api-key", post(create_api_key).get(list_api_keys))
+        .route("/api-key/:api_key_id/expiry", post(set_api_key_expiry))
+        .route("/api-key/:api_key_id/revoke", post(revoke_api_key))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::service::token_issuer::{
+        CATALOG_TOKEN_AUDIENCE, PROJECT_ID_CLAIM, WAREHOUSE_ID_CLAIM,
+    };
+    use crate::service::token_verification::{Aud, Claims};
+
+    fn metadata(auth_details: AuthDetails) -> RequestMetadata {
+        RequestMetadata {
+            request_id: uuid::Uuid::now_v7(),
+            auth_details: Some(auth_details),
+        }
+    }
+
+    #[test]
+    fn test_restrict_to_caller() {
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let exp = chrono::DateTime::from_timestamp(4_000_000_000, 0).unwrap();
+        let claims = Claims {
+            sub: "alice".to_string(),
+            iss: "https://catalog.example.com/catalog".to_string(),
+            aud: Aud::String(CATALOG_TOKEN_AUDIENCE.to_string()),
+            exp: 4_000_000_000,
+            iat: 0,
+            other: serde_json::json!({
+                PROJECT_ID_CLAIM: uuid::Uuid::now_v7().to_string(),
+                WAREHOUSE_ID_CLAIM: warehouse_id.to_string(),
+            }),
+        };
+
+        let scoped = metadata(AuthDetails::CatalogToken(claims.clone()));
+        assert_eq!(
+            restrict_to_caller(&scoped, None, None).unwrap(),
+            (Some(warehouse_id), Some(exp))
+        );
+        let earlier = exp - chrono::Duration::days(1);
+        assert_eq!(
+            restrict_to_caller(&scoped, Some(warehouse_id), Some(earlier)).unwrap(),
+            (Some(warehouse_id), Some(earlier))
+        );
+        assert_eq!(
+            restrict_to_caller(&scoped, None, Some(exp + chrono::Duration::days(1))).unwrap(),
+            (Some(warehouse_id), Some(exp))
+        );
+        let other = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let err = restrict_to_caller(&scoped, Some(other), None).unwrap_err();
+        assert_eq!(err.error.code, http::StatusCode::FORBIDDEN);
+
+        let unscoped = metadata(AuthDetails::JWT(claims));
+        assert_eq!(
+            restrict_to_caller(&unscoped, None, None).unwrap(),
+            (None, None)
+        );
+    }
+}
@@ -0,0 +1,408 @@
+use crate::api::{ApiContext, Result};
+use crate::implementations::postgres::authz::{self, PostgresAuthZHandler, Principal};
+use crate::request_metadata::RequestMetadata;
+use crate::service::{Catalog, SecretStore, State};
+use axum::extract::{Path, State as AxumState};
+use axum::routing::{get, post, put};
+use axum::{Extension, Json, Router};
+use utoipa::ToSchema;
+
+/// Privilege granted to a role on an object.
+///
+/// Privileges are ordered, every privilege includes the privileges before it.
+/// A grant on an object applies to all objects it contains: a grant on a project
+/// applies to all its warehouses, a grant on a warehouse to all its namespaces,
+/// and a grant on a namespace to all its child namespaces, tables and views.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    strum_macros::Display,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(
+    feature = "sqlx",
+    sqlx(type_name = "privilege", rename_all = "kebab-case")
+)]
+pub enum Privilege {
+    /// List and load namespaces, tables and views, get warehouses.
+    Read,
+    /// Create, update, rename and drop namespaces, tables and views.
+    Write,
+    /// Manage warehouses and grants.
+    Manage,
+}
+
+/// Type of object a grant applies to.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    strum_macros::Display,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(
+    feature = "sqlx",
+    sqlx(type_name = "grant_object_type", rename_all = "kebab-case")
+)]
+pub enum GrantObjectType {
+    Project,
+    Warehouse,
+    Namespace,
+    /// A table or a view.
+    Tabular,
+}
+
+/// How a member of a role is matched against the token of a request.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    strum_macros::Display,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(
+    feature = "sqlx",
+    sqlx(type_name = "role_member_type", rename_all = "kebab-case")
+)]
+pub enum RoleMemberType {
+    /// Matches the principal, formatted as `<issuer>|<subject>`.
+    User,
+    /// Matches an entry of the configured groups claim.
+    Group,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RoleMember {
+    #[serde(rename = "type")]
+    pub member_type: RoleMemberType,
+    /// Principal or group name.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct Role {
+    pub role_id: uuid::Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<RoleMember>,
+    #[schema(value_type = String)]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(value_type = Option<String>)]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateRoleRequest {
+    /// Name of the role. Must be unique.
+    pub name: String,
+    pub description: Option<String>,
+    /// Initial members of the role.
+    #[serde(default)]
+    pub members: Vec<RoleMember>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct UpdateRoleMembersRequest {
+    /// New members of the role. Replaces all existing members.
+    pub members: Vec<RoleMember>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListRolesResponse {
+    pub roles: Vec<Role>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct Grant {
+    pub object_type: GrantObjectType,
+    pub object_id: uuid::Uuid,
+    pub privilege: Privilege,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct GrantRequest {
+    pub object_type: GrantObjectType,
+    pub object_id: uuid::Uuid,
+    /// Privilege to grant. Replaces an existing grant of the role on the object.
+    pub privilege: Privilege,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RevokeRequest {
+    pub object_type: GrantObjectType,
+    pub object_id: uuid::Uuid,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListGrantsResponse {
+    pub grants: Vec<Grant>,
+}
+
+impl axum::response::IntoResponse for Role {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ListRolesResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ListGrantsResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+type RbacContext<C, S> = ApiContext<State<PostgresAuthZHandler, C, S>>;
+
+/// Create a new role
+///
+/// Only available if the Postgres authorization backend is used.
+/// Requires an admin.
+#[utoipa::path(
+    post,
+    tag = "management",
+    path = "management/v1/role",
+    request_body = CreateRoleRequest,
+    responses(
+        (status = 201, description = "Role created successfully", body = [Role]),
+    )
+)]
+pub(crate) async fn create_role<C: Catalog, S: SecretStore>(
+    AxumState(api_context): AxumState<RbacContext<C, S>>,
+    Extension(metadata): Extension<RequestMetadata>,
+    Json(request): Json<CreateRoleRequest>,
+) -> Result<(http::StatusCode, Role)> {
+    Principal::from_metadata(&metadata)?.require_admin()?;
+    let role = authz::create_role(request, &api_context.v1_state.auth).await?;
+    Ok((http::StatusCode::CREATED, role))
+}
+
+/// List all roles
+///
+/// Only available if the Postgres authorization backend is used.
+/// Requires an admin.
+#[utoipa::path(
+    get,
+    tag = "management",
+    path = "management/v1/role",
+    responses(
+        (status = 200, description = "List of roles", body = [ListRolesResponse])
+    )
+)]
+pub(crate) async fn list_roles<C: Catalog, S: SecretStore>(
+    AxumState(api_context): AxumState<RbacContext<C, S>>,
+    Extension(metadata): Extension<RequestMetadata>,
+) -> Result<ListRolesResponse> {
+    Principal::from_metadata(&metadata)?.require_admin()?;
+    Ok(ListRolesResponse {
+        roles: authz::list_roles(&api_context.v1_state.auth).await?,
+    })
+}
+
+/// Get a role by ID
+///
+/// Only available if the Postgres authorization backend is used.
+/// Requires an admin.
+#[utoipa::path(
+    get,
+    tag = "management",
+    path = "management/v1/role/{role_id}",
+    responses(
+        (status = 200, description = "Role details", body = [Role])
+    )
+)]
+pub(crate) async fn get_role<C: Catalog, S: SecretStore>(
+    Path(role_id): Path<uuid::Uuid>,
+    AxumState(api_context): AxumState<RbacContext<C, S>>,
+    Extension(metadata): Extension<RequestMetadata>,
+) -> Result<Role> {
+    Principal::from_metadata(&metadata)?.require_admin()?;
+    authz::get_role(role_id, &api_context.v1_state.auth).await
+}
+
+/// Delete a role by ID
+///
+/// All grants of the role are deleted as well.
+/// Only available if the Postgres authorization backend is used.
+/// Requires an admin.
+#[utoipa::path(
+    delete,
+    tag = "management",
+    path = "management/v1/role/{role_id}",
+    responses(
+        (status = 200, description = "Role deleted successfully")
+    )
+)]
+pub(crate) async fn delete_role<C: Catalog, S: SecretStore>(
+    Path(role_id): Path<uuid::Uuid>,
+    AxumState(api_context): AxumState<RbacContext<C, S>>,
+    Extension(metadata): Extension<RequestMetadata>,
+) -> Result<()> {
+    Principal::from_metadata(&metadata)?.require_admin()?;
+    authz::delete_role(role_id, &api_context.v1_state.auth).await
+}
+
+/// Replace the members of a role
+///
+/// Only available if the Postgres authorization backend is used.
+/// Requires an admin.
+#[utoipa::path(
+    put,
+    tag = "management",
+    path = "management/v1/role/{role_id}/members",
+    request_body = UpdateRoleMembersRequest,
+    responses(
+        (status = 200, description = "Role members updated successfully", body = [Role])
+    )
+)]
+pub(crate) async fn update_role_members<C: Catalog, S: SecretStore>(
+    Path(role_id): Path<uuid::Uuid>,
+    AxumState(api_context): AxumState<RbacContext<C, S>>,
+    Extension(metadata): Extension<RequestMetadata>,
+    Json(request): Json<UpdateRoleMembersRequest>,
+) -> Result<Role> {
+    Principal::from_metadata(&metadata)?.require_admin()?;
+    authz::set_role_members(role_id, request.members, &api_context.v1_state.auth).await
+}
+
+/// List the grants of a role
+///
+/// Only available if the Postgres authorization backend is used.
+/// Requires an admin.
+#[utoipa::path(
+    get,
+    tag = "management",
+    path = "management/v1/role/{role_id}/grants",
+    responses(
+        (status = 200, description = "Grants of the role", body = [ListGrantsResponse])
+    )
+)]
+pub(crate) async fn list_grants<C: Catalog, S: SecretStore>(
+    Path(role_id): Path<uuid::Uuid>,
+    AxumState(api_context): AxumState<RbacContext<C, S>>,
+    Extension(metadata): Extension<RequestMetadata>,
+) -> Result<ListGrantsResponse> {
+    Principal::from_metadata(&metadata)?.require_admin()?;
+    Ok(ListGrantsResponse {
+        grants: authz::list_grants(role_id, &api_context.v1_state.auth).await?,
+    })
+}
+
+/// Grant a privilege on an object to a role
+///
+/// Only available if the Postgres authorization backend is used.
+/// Requires the `manage` privilege on the object.
+#[utoipa::path(
+    post,
+    tag = "management",
+    path = "management/v1/role/{role_id}/grant",
+    request_body = GrantRequest,
+    responses(
+        (status = 200, description = "Privilege granted successfully")
+    )
+)]
+pub(crate) async fn grant<C: Catalog, S: SecretStore>(
+    Path(role_id): Path<uuid::Uuid>,
+    AxumState(api_context): AxumState<RbacContext<C, S>>,
+    Extension(metadata): Extension<RequestMetadata>,
+    Json(request): Json<GrantRequest>,
+) -> Result<()> {
+    let state = &api_context.v1_state.auth;
+    authz::require_manage_object(
+        &Principal::from_metadata(&metadata)?,
+        request.object_type,
+        request.object_id,
+        state,
+    )
+    .await?;
+    authz::grant(
+        role_id,
+        Grant {
+            object_type: request.object_type,
+            object_id: request.object_id,
+            privilege: request.privilege,
+        },
+        state,
+    )
+    .await
+}
+
+/// Revoke all privileges on an object from a role
+///
+/// Only available if the Postgres authorization backend is used.
+/// Requires the `manage` privilege on the object.
+#[utoipa::path(
+    post,
+    tag = "management",
+    path = "management/v1/role/{role_id}/revoke",
+    request_body = RevokeRequest,
+    responses(
+        (status = 200, description = "Privilege revoked successfully")
+    )
+)]
+pub(crate) async fn revoke<C: Catalog, S: SecretStore>(
+    Path(role_id): Path<uuid::Uuid>,
+    AxumState(api_context): AxumState<RbacContext<C, S>>,
+    Extension(metadata): Extension<RequestMetadata>,
+    Json(request): Json<RevokeRequest>,
+) -> Result<()> {
+    let state = &api_context.v1_state.auth;
+    authz::require_manage_object(
+        &Principal::from_metadata(&metadata)?,
+        request.object_type,
+        request.object_id,
+        state,
+    )
+    .await?;
+    authz::revoke(role_id, request.object_type, request.object_id, state).await
+}
+
+pub(crate) fn new_v1_router<C: Catalog, S: SecretStore>() -> Router<RbacContext<C, S>> {
+    Router::new()
+        .route("/role", post(create_role).get(list_roles))
+        .route("/role/:role_id", get(get_role).delete(delete_role))
+        .route("/role/:role_id/members", put(update_role_members))
+        .route("/role/:role_id/grants", get(list_grants))
+        .route("/role/:role_id/grant", post(grant))
+        .route("/role/:role_id/revoke", post(revoke))
+}
@@ -2,12 +2,18 @@ use crate::api::management::v1::ApiServer;
 use crate::api::{ApiContext, Result};
 use crate::request_metadata::RequestMetadata;
 pub use crate::service::storage::{
-    AzCredential, AzdlsProfile, S3Credential, S3Profile, StorageCredential, StorageProfile,
+    AzCredential, AzdlsProfile, S3Credential, S3Profile, S3ServerSideEncryption, StorageCredential,
+    StorageProfile, StorageValidationCheck, StorageValidationStatus, StorageValidationStep,
 };
 
-pub use crate::service::WarehouseStatus;
-use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, State, Transaction};
-use crate::{ProjectIdent, WarehouseIdent};
+use crate::catalog::namespace::namespace_event_metadata;
+use crate::service::event_publisher::{CloudEventsPublisher, EventEntity, EventMetadata};
+pub use crate::service::secrets::SecretVersion;
+use crate::service::{
+    auth::AuthZHandler, secrets::SecretStore, Catalog, NamespaceIdentUuid, State, Transaction,
+};
+pub use crate::service::{ProjectStatus, WarehouseStatus};
+use crate::{ProjectIdent, WarehouseIdent, CONFIG};
 use iceberg_ext::catalog::rest::ErrorModel;
 use serde::Deserialize;
 use utoipa::ToSchema;
@@ -68,10 +74,46 @@ pub struct RenameWarehouseRequest {
     pub new_name: String,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateProjectRequest {
+    /// ID of the project to create. Generated if not set.
+    /// Not allowed with the openfga authorization backend.
+    #[serde(default)]
+    pub project_id: Option<uuid::Uuid>,
+    /// Name of the project. Must be unique.
+    pub project_name: String,
+    /// Optional description of the project.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateProjectResponse {
+    /// ID of the created project.
+    pub project_id: uuid::Uuid,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RenameProjectRequest {
+    /// New name for the project.
+    pub new_name: String,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
 pub struct ProjectResponse {
     /// ID of the project.
     pub project_id: uuid::Uuid,
+    /// Name of the project.
+    pub name: String,
+    /// Description of the project.
+    pub description: Option<String>,
+    /// Status of the project.
+    pub status: ProjectStatus,
+    #[schema(value_type = String)]
+    pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
@@ -107,16 +149,77 @@ pub struct ListWarehousesResponse {
 #[serde(rename_all = "kebab-case")]
 pub struct UpdateWarehouseCredentialRequest {
     /// New storage credential to use for the warehouse.
+    /// If the warehouse already has a credential, the previous one is kept
+    /// as an older version and can be restored.
     /// If not specified, the existing credential is removed.
     pub new_storage_credential: Option<StorageCredential>,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListStorageCredentialVersionsResponse {
+    /// Versions of the warehouse storage credential, newest first.
+    pub versions: Vec<SecretVersion>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RollbackStorageCredentialRequest {
+    /// Version of the storage credential to restore.
+    /// The restored value is stored as a new version.
+    pub version: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct UpdateNamespaceStorageRequest {
+    /// Storage profile to use for all tables in the namespace and in child
+    /// namespaces without their own override.
+    /// If not specified, the override is removed and tables use the storage
+    /// profile of the nearest parent namespace with an override, or of the
+    /// warehouse, again.
+    /// Namespaces that contain tables, directly or in child namespaces, can only
+    /// be updated with a profile that points to the same location as the current one.
+    pub storage_profile: Option<StorageProfile>,
+    /// Optional storage credential to use for the namespace storage profile.
+    /// The existing credential is not re-used.
+    pub storage_credential: Option<StorageCredential>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ValidateStorageRequest {
+    /// Project ID in which a warehouse with this storage would be created.
+    pub project_id: uuid::Uuid,
+    /// Storage profile to validate.
+    pub storage_profile: StorageProfile,
+    /// Optional storage credential to validate the storage profile with.
+    pub storage_credential: Option<StorageCredential>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ValidateStorageResponse {
+    /// True if all checks passed.
+    pub success: bool,
+    /// The storage profile after normalization.
+    pub storage_profile: StorageProfile,
+    /// Outcome of each validation step, in the order of the steps.
+    pub checks: Vec<StorageValidationCheck>,
+}
+
 impl axum::response::IntoResponse for CreateWarehouseResponse {
     fn into_response(self) -> axum::http::Response<axum::body::Body> {
         (http::StatusCode::CREATED, axum::Json(self)).into_response()
     }
 }
 
+impl axum::response::IntoResponse for CreateProjectResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        (http::StatusCode::CREATED, axum::Json(self)).into_response()
+    }
+}
+
 impl<C: Catalog, A: AuthZHandler, S: SecretStore> Service<C, A, S> for ApiServer<C, A, S> {}
 
 #[async_trait::async_trait]
@@ -136,7 +239,12 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         let project_ident = ProjectIdent::from(project_id);
 
         // ------------------- AuthZ -------------------
-        A::check_create_warehouse(&request_metadata, &project_ident, context.v1_state.auth).await?;
+        A::check_create_warehouse(
+            &request_metadata,
+            &project_ident,
+            context.v1_state.auth.clone(),
+        )
+        .await?;
 
         // ------------------- Business Logic -------------------
         storage_profile.normalize()?;
@@ -145,6 +253,24 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
             .await?;
 
         let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        let project = C::get_project(project_ident, transaction.transaction())
+            .await?
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(http::StatusCode::NOT_FOUND.into())
+                    .message(format!("Project {project_ident} not found"))
+                    .r#type("ProjectNotFound".to_string())
+                    .build()
+            })?;
+        if project.status != ProjectStatus::Active {
+            return Err(ErrorModel::bad_request(
+                format!("Project {project_ident} is not active"),
+                "ProjectNotActive",
+                None,
+            )
+            .into());
+        }
+
         let secret_id = if let Some(storage_credential) = storage_credential {
             Some(
                 context
@@ -166,13 +292,89 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         )
         .await?;
 
-        transaction.commit().await?;
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+        emit_warehouse_event::<C>(
+            warehouse,
+            "createWarehouse",
+            &request_metadata,
+            &context.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
+
+        A::on_warehouse_created(
+            &request_metadata,
+            project_ident,
+            warehouse_id,
+            context.v1_state.auth.clone(),
+        )
+        .await?;
+        if let Err(e) = transaction.commit().await {
+            A::on_warehouse_deleted(warehouse_id, context.v1_state.auth)
+                .await
+                .map_err(|e| {
+                    tracing::warn!(
+                        "Failed to clean up authorization of warehouse: {:?}",
+                        e.error
+                    );
+                })
+                .ok();
+            return Err(e);
+        }
+        context.v1_state.publisher.notify();
 
         Ok(CreateWarehouseResponse {
             warehouse_id: *warehouse_id,
         })
     }
 
+    async fn create_project(
+        request: CreateProjectRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<CreateProjectResponse> {
+        let CreateProjectRequest {
+            project_id,
+            project_name,
+            description,
+        } = request;
+        let id_requested = project_id.is_some();
+        let project_id = ProjectIdent::from(project_id.unwrap_or_else(uuid::Uuid::now_v7));
+
+        // ------------------- AuthZ -------------------
+        A::check_create_project(
+            &request_metadata,
+            project_id,
+            id_requested,
+            context.v1_state.auth.clone(),
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::create_project(
+            project_id,
+            project_name,
+            description,
+            transaction.transaction(),
+        )
+        .await?;
+        A::on_project_created(&request_metadata, project_id, context.v1_state.auth.clone()).await?;
+        if let Err(e) = transaction.commit().await {
+            A::on_project_deleted(project_id, context.v1_state.auth)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("Failed to clean up authorization of project: {:?}", e.error);
+                })
+                .ok();
+            return Err(e);
+        }
+
+        Ok(CreateProjectResponse {
+            project_id: *project_id,
+        })
+    }
+
     async fn list_projects(
         context: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
@@ -181,28 +383,94 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         let projects = A::check_list_projects(&request_metadata, context.v1_state.auth).await?;
 
         // ------------------- Business Logic -------------------
-        if let Some(projects) = projects {
-            return Ok(ListProjectsResponse {
-                projects: projects
-                    .into_iter()
-                    .map(|project_id| ProjectResponse {
-                        project_id: *project_id,
-                    })
-                    .collect(),
-            });
-        }
-
-        let projects = C::list_projects(context.v1_state.catalog).await?;
+        let projects = C::list_projects(projects.as_ref(), context.v1_state.catalog).await?;
         Ok(ListProjectsResponse {
-            projects: projects
-                .into_iter()
-                .map(|project_id| ProjectResponse {
-                    project_id: *project_id,
-                })
-                .collect(),
+            projects: projects.into_iter().map(Into::into).collect(),
         })
     }
 
+    async fn rename_project(
+        project_id: ProjectIdent,
+        request: RenameProjectRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_rename_project(&request_metadata, project_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+
+        C::rename_project(project_id, &request.new_name, transaction.transaction()).await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_project(
+        project_id: ProjectIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_delete_project(&request_metadata, project_id, context.v1_state.auth.clone())
+            .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+
+        C::delete_project(project_id, transaction.transaction()).await?;
+
+        transaction.commit().await?;
+
+        A::on_project_deleted(project_id, context.v1_state.auth)
+            .await
+            .map_err(|e| {
+                tracing::warn!("Failed to clean up authorization of project: {:?}", e.error);
+            })
+            .ok();
+
+        Ok(())
+    }
+
+    async fn deactivate_project(
+        project_id: ProjectIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_deactivate_project(&request_metadata, project_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_project_status(
+            project_id,
+            ProjectStatus::Inactive,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn activate_project(
+        project_id: ProjectIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_activate_project(&request_metadata, project_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_project_status(project_id, ProjectStatus::Active, transaction.transaction()).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
     async fn list_warehouses(
         request: ListWarehousesRequest,
         context: ApiContext<State<A, C, S>>,
@@ -263,14 +531,39 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         request_metadata: RequestMetadata,
     ) -> Result<()> {
         // ------------------- AuthZ -------------------
-        A::check_delete_warehouse(&request_metadata, warehouse_id, context.v1_state.auth).await?;
+        A::check_delete_warehouse(
+            &request_metadata,
+            warehouse_id,
+            context.v1_state.auth.clone(),
+        )
+        .await?;
 
         // ------------------- Business Logic -------------------
         let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
 
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
         C::delete_warehouse(warehouse_id, transaction.transaction()).await?;
+        emit_warehouse_event::<C>(
+            warehouse,
+            "deleteWarehouse",
+            &request_metadata,
+            &context.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
 
         transaction.commit().await?;
+        context.v1_state.publisher.notify();
+
+        A::on_warehouse_deleted(warehouse_id, context.v1_state.auth)
+            .await
+            .map_err(|e| {
+                tracing::warn!(
+                    "Failed to clean up authorization of warehouse: {:?}",
+                    e.error
+                );
+            })
+            .ok();
 
         Ok(())
     }
@@ -287,8 +580,18 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
 
         C::rename_warehouse(warehouse_id, &request.new_name, transaction.transaction()).await?;
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+        emit_warehouse_event::<C>(
+            warehouse,
+            "renameWarehouse",
+            &request_metadata,
+            &context.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
 
         transaction.commit().await?;
+        context.v1_state.publisher.notify();
 
         Ok(())
     }
@@ -311,8 +614,18 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
             transaction.transaction(),
         )
         .await?;
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+        emit_warehouse_event::<C>(
+            warehouse,
+            "deactivateWarehouse",
+            &request_metadata,
+            &context.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
 
         transaction.commit().await?;
+        context.v1_state.publisher.notify();
 
         Ok(())
     }
@@ -334,8 +647,18 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
             transaction.transaction(),
         )
         .await?;
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+        emit_warehouse_event::<C>(
+            warehouse,
+            "activateWarehouse",
+            &request_metadata,
+            &context.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
 
         transaction.commit().await?;
+        context.v1_state.publisher.notify();
 
         Ok(())
     }
@@ -387,7 +710,18 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         )
         .await?;
 
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+        emit_warehouse_event::<C>(
+            warehouse,
+            "updateStorageProfile",
+            &request_metadata,
+            &context.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
+
         transaction.commit().await?;
+        context.v1_state.publisher.notify();
 
         // Delete the old secret if it exists - never fail the request if the deletion fails
         if let Some(old_secret_id) = old_secret_id {
@@ -422,12 +756,35 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
         let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
         let old_secret_id = warehouse.storage_secret_id;
-        let storage_profile = warehouse.storage_profile;
+        let storage_profile = warehouse.storage_profile.clone();
 
         storage_profile
             .validate_access(new_storage_credential.as_ref(), None)
             .await?;
 
+        // Rotate an existing credential in place so that its history is kept
+        // as secret versions and can be rolled back.
+        if let (Some(old_secret_id), Some(new_storage_credential)) =
+            (old_secret_id, new_storage_credential.clone())
+        {
+            context
+                .v1_state
+                .secrets
+                .update_secret(&old_secret_id, new_storage_credential)
+                .await?;
+            emit_warehouse_event::<C>(
+                warehouse,
+                "updateStorageCredential",
+                &request_metadata,
+                &context.v1_state.publisher,
+                transaction.transaction(),
+            )
+            .await?;
+            transaction.commit().await?;
+            context.v1_state.publisher.notify();
+            return Ok(());
+        }
+
         let secret_id = if let Some(new_storage_credential) = new_storage_credential {
             Some(
                 context
@@ -448,7 +805,228 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         )
         .await?;
 
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+        emit_warehouse_event::<C>(
+            warehouse,
+            "updateStorageCredential",
+            &request_metadata,
+            &context.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
+
+        transaction.commit().await?;
+        context.v1_state.publisher.notify();
+
+        // Delete the old secret if it exists - never fail the request if the deletion fails
+        if let Some(old_secret_id) = old_secret_id {
+            context
+                .v1_state
+                .secrets
+                .delete_secret(&old_secret_id)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("Failed to delete old secret: {:?}", e.error);
+                })
+                .ok();
+        }
+
+        Ok(())
+    }
+
+    async fn list_credential_versions(
+        warehouse_id: WarehouseIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ListStorageCredentialVersionsResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_get_warehouse(&request_metadata, warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let secret_id =
+            require_warehouse_secret_id::<C>(warehouse_id, context.v1_state.catalog).await?;
+        let versions = context
+            .v1_state
+            .secrets
+            .list_secret_versions(&secret_id)
+            .await?;
+
+        Ok(ListStorageCredentialVersionsResponse { versions })
+    }
+
+    async fn rollback_credential(
+        warehouse_id: WarehouseIdent,
+        request: RollbackStorageCredentialRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_update_storage(&request_metadata, warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let RollbackStorageCredentialRequest { version } = request;
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+
+        let secret_id = warehouse
+            .storage_secret_id
+            .ok_or_else(no_storage_credential)?;
+        let old_credential = context
+            .v1_state
+            .secrets
+            .get_secret_version::<StorageCredential>(&secret_id, version)
+            .await?
+            .secret;
+
+        // The old credential might have been revoked in the meantime
+        warehouse
+            .storage_profile
+            .validate_access(Some(&old_credential), None)
+            .await?;
+
+        context
+            .v1_state
+            .secrets
+            .update_secret(&secret_id, old_credential)
+            .await?;
+
+        emit_warehouse_event::<C>(
+            warehouse,
+            "rollbackStorageCredential",
+            &request_metadata,
+            &context.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context.v1_state.publisher.notify();
+
+        Ok(())
+    }
+
+    async fn validate_storage(
+        request: ValidateStorageRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ValidateStorageResponse> {
+        let ValidateStorageRequest {
+            project_id,
+            storage_profile,
+            storage_credential,
+        } = request;
+        let project_ident = ProjectIdent::from(project_id);
+
+        // ------------------- AuthZ -------------------
+        // Validating storage is only useful to users that may create a warehouse
+        A::check_create_warehouse(&request_metadata, &project_ident, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let (storage_profile, report) = storage_profile
+            .dry_run_validation(storage_credential.as_ref())
+            .await;
+
+        Ok(ValidateStorageResponse {
+            success: report.is_success(),
+            storage_profile,
+            checks: report.checks,
+        })
+    }
+
+    async fn update_namespace_storage(
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        request: UpdateNamespaceStorageRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_update_storage(&request_metadata, warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let UpdateNamespaceStorageRequest {
+            storage_profile,
+            storage_credential,
+        } = request;
+
+        let storage_profile = if let Some(mut storage_profile) = storage_profile {
+            storage_profile.normalize()?;
+            storage_profile
+                .validate_access(storage_credential.as_ref(), None)
+                .await?;
+            Some(storage_profile)
+        } else if storage_credential.is_some() {
+            return Err(ErrorModel::bad_request(
+                "A storage credential can only be specified together with a storage profile",
+                "StorageCredentialWithoutProfile",
+                None,
+            )
+            .into());
+        } else {
+            None
+        };
+
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+        let namespace =
+            C::get_namespace_by_id(warehouse_id, namespace_id, transaction.transaction()).await?;
+        let old_secret_id = namespace.storage_secret_id;
+
+        // Existing tables keep their location, so their storage must stay reachable.
+        if C::namespace_contains_tabulars(warehouse_id, namespace_id, transaction.transaction())
+            .await?
+        {
+            let (previous_storage_profile, _) = namespace.effective_storage(&warehouse);
+            let (inherited_storage_profile, _) = namespace.inherited_storage(&warehouse);
+            previous_storage_profile.can_be_updated_with(
+                storage_profile
+                    .as_ref()
+                    .unwrap_or(&inherited_storage_profile),
+            )?;
+        }
+
+        let secret_id = if let Some(storage_credential) = storage_credential {
+            Some(
+                context
+                    .v1_state
+                    .secrets
+                    .create_secret(storage_credential)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        let event_data = NamespaceStorageEventData {
+            storage_profile: storage_profile.clone(),
+        };
+        C::set_namespace_storage_profile(
+            warehouse_id,
+            namespace_id,
+            storage_profile,
+            secret_id,
+            transaction.transaction(),
+        )
+        .await?;
+        context
+            .v1_state
+            .publisher
+            .publish::<C>(
+                uuid::Uuid::now_v7(),
+                "updateNamespaceStorage",
+                serialize_event_data(&event_data)?,
+                namespace_event_metadata(
+                    warehouse_id,
+                    namespace_id,
+                    &namespace.namespace,
+                    CONFIG.warehouse_prefix(warehouse_id),
+                    &request_metadata,
+                ),
+                transaction.transaction(),
+            )
+            .await?;
+
         transaction.commit().await?;
+        context.v1_state.publisher.notify();
 
         // Delete the old secret if it exists - never fail the request if the deletion fails
         if let Some(old_secret_id) = old_secret_id {
@@ -467,6 +1045,76 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
     }
 }
 
+async fn require_warehouse_secret_id<C: Catalog>(
+    warehouse_id: WarehouseIdent,
+    catalog_state: C::State,
+) -> Result<crate::SecretIdent> {
+    let mut transaction = C::Transaction::begin_read(catalog_state).await?;
+    let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
+    transaction.commit().await?;
+
+    warehouse
+        .storage_secret_id
+        .ok_or_else(|| no_storage_credential().into())
+}
+
+/// Data of `updateNamespaceStorage` events. Credentials are never part of events.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct NamespaceStorageEventData {
+    storage_profile: Option<StorageProfile>,
+}
+
+/// Store an event about `warehouse` in the outbox. The data of the event is the warehouse
+/// as returned by `get_warehouse`, which does not contain the storage credential.
+async fn emit_warehouse_event<C: Catalog>(
+    warehouse: crate::service::GetWarehouseResponse,
+    operation_id: &str,
+    request_metadata: &RequestMetadata,
+    publisher: &CloudEventsPublisher,
+    transaction: <C::Transaction as Transaction<C::State>>::Transaction<'_>,
+) -> Result<()> {
+    let metadata = EventMetadata {
+        entity: EventEntity::from(warehouse.id),
+        warehouse_id: *warehouse.id,
+        name: warehouse.name.clone(),
+        namespace: String::new(),
+        prefix: CONFIG.warehouse_prefix(warehouse.id),
+        num_events: 1,
+        sequence_number: 0,
+        trace_id: request_metadata.request_id,
+    };
+    let data = serialize_event_data(&GetWarehouseResponse::from(warehouse))?;
+
+    publisher
+        .publish::<C>(
+            uuid::Uuid::now_v7(),
+            operation_id,
+            data,
+            metadata,
+            transaction,
+        )
+        .await
+}
+
+fn serialize_event_data(data: &impl serde::Serialize) -> Result<serde_json::Value> {
+    Ok(serde_json::to_value(data).map_err(|e| {
+        ErrorModel::internal(
+            "Error serializing event data",
+            "EventDataSerializationError",
+            Some(Box::new(e)),
+        )
+    })?)
+}
+
+fn no_storage_credential() -> ErrorModel {
+    ErrorModel::not_found(
+        "Warehouse has no storage credential",
+        "StorageCredentialNotFound",
+        None,
+    )
+}
+
 impl axum::response::IntoResponse for ListProjectsResponse {
     fn into_response(self) -> axum::http::Response<axum::body::Body> {
         axum::Json(self).into_response()
@@ -479,12 +1127,36 @@ impl axum::response::IntoResponse for ListWarehousesResponse {
     }
 }
 
+impl axum::response::IntoResponse for ValidateStorageResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ListStorageCredentialVersionsResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
 impl axum::response::IntoResponse for GetWarehouseResponse {
     fn into_response(self) -> axum::http::Response<axum::body::Body> {
         axum::Json(self).into_response()
     }
 }
 
+impl From<crate::service::GetProjectResponse> for ProjectResponse {
+    fn from(project: crate::service::GetProjectResponse) -> Self {
+        Self {
+            project_id: *project.project_id,
+            name: project.name,
+            description: project.description,
+            status: project.status,
+            created_at: project.created_at,
+        }
+    }
+}
+
 impl From<crate::service::GetWarehouseResponse> for GetWarehouseResponse {
     fn from(warehouse: crate::service::GetWarehouseResponse) -> Self {
         Self {
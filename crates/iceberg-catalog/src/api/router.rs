@@ -3,9 +3,13 @@ use crate::tracing::{MakeRequestUuid7, RestMakeSpan};
 
 use super::management::v1::ManagementApiDoc;
 use crate::api::management::v1::ApiServer;
-use crate::api::{iceberg::v1::new_v1_full_router, shutdown_signal, ApiContext};
+use crate::api::{
+    iceberg::v1::new_v1_full_router, shutdown_signal, ApiContext, IcebergErrorResponse,
+};
+use crate::service::api_key::CatalogApiKeys;
 use crate::service::contract_verification::ContractVerifiers;
 use crate::service::health::ServiceHealthProvider;
+use crate::service::token_issuer::TokenIssuer;
 use crate::service::token_verification::Verifier;
 use crate::service::{
     auth::{AuthConfigHandler, AuthZHandler},
@@ -15,6 +19,7 @@ use crate::service::{
 use axum::response::IntoResponse;
 use axum::{routing::get, Json, Router};
 use axum_prometheus::PrometheusMetricLayer;
+use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::{
     catch_panic::CatchPanicLayer, compression::CompressionLayer,
@@ -46,14 +51,32 @@ pub fn new_full_router<
         State<A, C, S>,
     >();
 
-    let management_routes = Router::new().merge(ApiServer::new_v1_router());
+    let management_routes = Router::new()
+        .merge(ApiServer::new_v1_router())
+        .merge(A::management_router::<C, S>());
+
+    // Clients obtain their token here, so this route must not require one.
+    let oauth_routes = crate::api::iceberg::v1::oauth::router::<
+        crate::catalog::ConfigServer<CP, C, AH, A>,
+        State<A, C, S>,
+    >();
+
+    let token_verifier = token_verifier.map(|verifier| {
+        verifier.with_api_keys(Arc::new(CatalogApiKeys::<C>::new(catalog_state.clone())))
+    });
+
+    let token_issuer = token_verifier
+        .as_ref()
+        .and_then(Verifier::token_issuer)
+        .cloned();
 
     let router = maybe_add_auth(
-        token_verifier,
+        token_verifier.clone(),
         Router::new()
             .nest("/catalog/v1", v1_routes)
             .nest("/management/v1", management_routes),
     )
+    .nest("/catalog/v1", oauth_routes)
     .route(
         "/health",
         get(|| async move {
@@ -61,6 +84,7 @@ pub fn new_full_router<
             Json(health).into_response()
         }),
     )
+    .merge(jwks_router(token_issuer))
     .merge(utoipa_swagger_ui::SwaggerUi::new("/swagger-ui").url(
         "/api-docs/management/v1/openapi.json",
         ManagementApiDoc::openapi(),
@@ -92,6 +116,7 @@ pub fn new_full_router<
             secrets: secrets_state,
             publisher,
             contract_verifiers: table_change_checkers,
+            token_verifier,
         },
     });
 
@@ -102,6 +127,25 @@ pub fn new_full_router<
     }
 }
 
+/// Publishes the keys of catalog-issued tokens, if enabled.
+fn jwks_router<C: Catalog, A: AuthZHandler, S: SecretStore>(
+    token_issuer: Option<TokenIssuer>,
+) -> Router<ApiContext<State<A, C, S>>> {
+    if let Some(token_issuer) = token_issuer {
+        Router::new().route(
+            "/.well-known/jwks.json",
+            get(|| async move {
+                token_issuer
+                    .jwks()
+                    .map(Json)
+                    .map_err(IcebergErrorResponse::from)
+            }),
+        )
+    } else {
+        Router::new()
+    }
+}
+
 fn maybe_add_auth<C: Catalog, A: AuthZHandler, S: SecretStore>(
     token_verifier: Option<Verifier>,
     router: Router<ApiContext<State<A, C, S>>>,
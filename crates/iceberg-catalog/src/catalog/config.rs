@@ -7,6 +7,7 @@ use http::StatusCode;
 use std::marker::PhantomData;
 use std::str::FromStr;
 
+use crate::service::token_verification::Verifier;
 use crate::service::SecretStore;
 use crate::service::{
     auth::{AuthConfigHandler, AuthZHandler, UserWarehouse},
@@ -123,9 +124,22 @@ impl<
         );
 
         let (config, new_token) = futures::join!(config, new_token);
-        let new_token = new_token?;
+        let mut new_token = new_token?;
         let mut config = config?;
 
+        if new_token.is_none() {
+            if let Some(token_issuer) = api_context
+                .v1_state
+                .token_verifier
+                .as_ref()
+                .and_then(Verifier::token_issuer)
+            {
+                new_token = token_issuer
+                    .issue_for_request(&request_metadata, &project_id, warehouse_id)?
+                    .map(|(token, _)| token);
+            }
+        }
+
         if let Some(new_token) = new_token {
             config.overrides.insert("token".to_string(), new_token);
         }
@@ -7,6 +7,10 @@ use serde::Serialize;
 
 use super::compression_codec::CompressionCodec;
 
+/// Write a metadata file using `file_io`.
+///
+/// Server side encryption is applied by the `FileIO`, which is configured from the
+/// storage profile (see `S3Profile::file_io`).
 pub(crate) async fn write_metadata_file(
     metadata_location: &Location,
     metadata: impl Serialize,
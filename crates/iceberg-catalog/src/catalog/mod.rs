@@ -4,8 +4,10 @@ mod config;
 pub(crate) mod io;
 mod metrics;
 pub(crate) mod namespace;
+mod oauth;
 #[cfg(feature = "s3-signer")]
 mod s3_signer;
+mod table_changes;
 mod tables;
 mod views;
 
@@ -14,6 +16,7 @@ use iceberg::spec::{TableMetadata, ViewMetadata};
 use iceberg_ext::catalog::rest::IcebergErrorResponse;
 pub use namespace::{MAX_NAMESPACE_DEPTH, UNSUPPORTED_NAMESPACE_PROPERTIES};
 
+use crate::api::iceberg::v1::{PageToken, PaginationQuery};
 use crate::api::{iceberg::v1::Prefix, ErrorModel, Result};
 use crate::service::storage::StorageCredential;
 use crate::{
@@ -21,6 +24,7 @@ use crate::{
     WarehouseIdent,
 };
 use std::collections::HashMap;
+use std::future::Future;
 use std::marker::PhantomData;
 
 pub trait CommonMetadata {
@@ -62,6 +66,49 @@ fn require_warehouse_id(prefix: Option<Prefix>) -> Result<WarehouseIdent> {
         .try_into()
 }
 
+/// Fetches pages until `page_size` items passed `filter` or no items are left.
+///
+/// Every page is requested with the number of items still missing, so filtered items
+/// advance the page token without exceeding the page size. The returned token continues
+/// after the last fetched item and is `None` once all items have been fetched.
+async fn fetch_filtered_page<T, FetchFut, FilterFut>(
+    query: PaginationQuery,
+    mut fetch: impl FnMut(PaginationQuery) -> FetchFut,
+    mut filter: impl FnMut(Vec<T>) -> FilterFut,
+) -> Result<(Vec<T>, Option<String>)>
+where
+    FetchFut: Future<Output = Result<(Vec<T>, Option<String>)>>,
+    FilterFut: Future<Output = Result<Vec<T>>>,
+{
+    // Page sizes below 1 are treated as 1, no page size means all items.
+    let page_size = query
+        .page_size
+        .map(|size| usize::try_from(size.max(1)).unwrap_or(1));
+    let mut page_token = query.page_token;
+    let mut items = vec![];
+
+    loop {
+        let missing = page_size.map(|size| size - items.len());
+        let (page, next_page_token) = fetch(PaginationQuery {
+            page_token,
+            page_size: missing.map(|missing| i32::try_from(missing).unwrap_or(i32::MAX)),
+        })
+        .await?;
+        let exhausted = missing.map_or(true, |missing| page.len() < missing);
+        items.extend(filter(page).await?);
+
+        match next_page_token {
+            Some(token) if !exhausted => {
+                if page_size.is_some_and(|size| items.len() >= size) {
+                    return Ok((items, Some(token)));
+                }
+                page_token = PageToken::Present(token);
+            }
+            _ => return Ok((items, None)),
+        }
+    }
+}
+
 async fn maybe_get_secret<S: SecretStore>(
     secret: Option<crate::SecretIdent>,
     state: &S,
@@ -72,3 +119,64 @@ async fn maybe_get_secret<S: SecretStore>(
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::IcebergErrorResponse;
+
+    /// Pages over `0..total`, the token is the last returned item.
+    fn fetch_numbers(query: PaginationQuery, total: usize) -> Result<(Vec<usize>, Option<String>)> {
+        let start = query
+            .page_token
+            .as_option()
+            .map_or(0, |token| token.parse::<usize>().unwrap() + 1);
+        let size = query
+            .page_size
+            .map_or(total, |size| usize::try_from(size).unwrap());
+        let page = (start..total).take(size).collect::<Vec<_>>();
+        let token = page.last().map(ToString::to_string);
+        Ok((page, token))
+    }
+
+    async fn list(page_size: Option<i32>, page_token: PageToken) -> (Vec<usize>, Option<String>) {
+        fetch_filtered_page(
+            PaginationQuery {
+                page_token,
+                page_size,
+            },
+            |query| std::future::ready(fetch_numbers(query, 10)),
+            |page: Vec<usize>| async move {
+                Ok::<_, IcebergErrorResponse>(page.into_iter().filter(|i| i % 3 == 0).collect())
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_filtered_pages_are_filled() {
+        let (items, token) = list(Some(2), PageToken::NotSpecified).await;
+        assert_eq!(items, vec![0, 3]);
+        assert_eq!(token.as_deref(), Some("3"));
+
+        let (items, token) = list(Some(2), PageToken::Present("3".to_string())).await;
+        assert_eq!(items, vec![6, 9]);
+        assert_eq!(token.as_deref(), Some("9"));
+
+        let (items, token) = list(Some(2), PageToken::Present("9".to_string())).await;
+        assert!(items.is_empty());
+        assert_eq!(token, None);
+    }
+
+    #[tokio::test]
+    async fn test_unpaginated_listing_is_fetched_once() {
+        let (items, token) = list(None, PageToken::NotSpecified).await;
+        assert_eq!(items, vec![0, 3, 6, 9]);
+        assert_eq!(token, None);
+
+        let (items, token) = list(Some(5), PageToken::Present("6".to_string())).await;
+        assert_eq!(items, vec![9]);
+        assert_eq!(token, None);
+    }
+}
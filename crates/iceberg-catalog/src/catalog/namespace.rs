@@ -1,11 +1,14 @@
 use crate::api::iceberg::v1::{
     ApiContext, CreateNamespaceRequest, CreateNamespaceResponse, ErrorModel, GetNamespaceResponse,
-    ListNamespacesQuery, ListNamespacesResponse, NamespaceParameters, Prefix, Result,
-    UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
+    IcebergErrorResponse, ListNamespacesQuery, ListNamespacesResponse, NamespaceParameters,
+    PaginationQuery, Prefix, Result, UpdateNamespacePropertiesRequest,
+    UpdateNamespacePropertiesResponse,
 };
+use crate::catalog::tables::maybe_body_to_json;
 use crate::request_metadata::RequestMetadata;
+use crate::service::event_publisher::{EventEntity, EventMetadata};
 use crate::service::{GetWarehouseResponse, NamespaceIdentUuid};
-use crate::CONFIG;
+use crate::{WarehouseIdent, CONFIG};
 use http::StatusCode;
 use iceberg::NamespaceIdent;
 use iceberg_ext::configs::namespace::NamespaceProperties;
@@ -13,7 +16,7 @@ use iceberg_ext::configs::{ConfigProperty as _, Location};
 use std::collections::HashMap;
 use std::ops::Deref;
 
-use super::{require_warehouse_id, CatalogServer};
+use super::{fetch_filtered_page, require_warehouse_id, CatalogServer};
 use crate::service::{
     auth::AuthZHandler, secrets::SecretStore, Catalog, NamespaceIdentExt, State, Transaction as _,
 };
@@ -47,12 +50,68 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             &request_metadata,
             warehouse_id,
             query.parent.as_ref(),
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
         .await?;
 
         // ------------------- BUSINESS LOGIC -------------------
-        C::list_namespaces(warehouse_id, &query, state.v1_state.catalog).await
+        let parent = query.parent.as_ref();
+        let request_metadata = &request_metadata;
+        let (namespaces, next_page_token) = fetch_filtered_page(
+            query.clone().into(),
+            |PaginationQuery {
+                 page_token,
+                 page_size,
+             }| {
+                let catalog = state.v1_state.catalog.clone();
+                let query = ListNamespacesQuery {
+                    page_token,
+                    page_size,
+                    parent: parent.cloned(),
+                };
+                async move {
+                    let response = C::list_namespaces(warehouse_id, &query, catalog).await?;
+                    Ok::<_, IcebergErrorResponse>((response.namespaces, response.next_page_token))
+                }
+            },
+            |namespaces: Vec<NamespaceIdent>| {
+                let auth = state.v1_state.auth.clone();
+                async move {
+                    // Namespaces below a parent are listed relative to it.
+                    let full_idents = namespaces
+                        .iter()
+                        .map(|namespace| match parent {
+                            Some(parent) => NamespaceIdent::from_vec(
+                                parent.iter().chain(namespace.iter()).cloned().collect(),
+                            )
+                            .expect("Listed namespaces are not empty"),
+                            None => namespace.clone(),
+                        })
+                        .collect::<Vec<_>>();
+                    let visible = A::filter_visible_namespaces(
+                        request_metadata,
+                        warehouse_id,
+                        full_idents.clone(),
+                        auth,
+                    )
+                    .await?;
+                    Ok::<_, IcebergErrorResponse>(
+                        namespaces
+                            .into_iter()
+                            .zip(full_idents)
+                            .filter(|(_, full_ident)| visible.contains(full_ident))
+                            .map(|(namespace, _)| namespace)
+                            .collect(),
+                    )
+                }
+            },
+        )
+        .await?;
+
+        Ok(ListNamespacesResponse {
+            next_page_token,
+            namespaces,
+        })
     }
 
     async fn create_namespace(
@@ -62,7 +121,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         request_metadata: RequestMetadata,
     ) -> Result<CreateNamespaceResponse> {
         // ------------------- VALIDATIONS -------------------
-        let warehouse_id = require_warehouse_id(prefix)?;
+        let warehouse_id = require_warehouse_id(prefix.clone())?;
         let CreateNamespaceRequest {
             namespace,
             properties,
@@ -92,7 +151,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             &request_metadata,
             warehouse_id,
             request.namespace.parent().as_ref(),
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
         .await?;
 
@@ -111,7 +170,44 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         request.properties = Some(namespace_props.into());
 
         let r = C::create_namespace(warehouse_id, namespace_id, request, t.transaction()).await?;
-        t.commit().await?;
+        state
+            .v1_state
+            .publisher
+            .publish::<C>(
+                uuid::Uuid::now_v7(),
+                "createNamespace",
+                maybe_body_to_json(&r),
+                namespace_event_metadata(
+                    warehouse_id,
+                    namespace_id,
+                    &r.namespace,
+                    prefix.map(Prefix::into_string).unwrap_or_default(),
+                    &request_metadata,
+                ),
+                t.transaction(),
+            )
+            .await?;
+        A::on_namespace_created(
+            &request_metadata,
+            warehouse_id,
+            namespace_id,
+            &r.namespace,
+            state.v1_state.auth.clone(),
+        )
+        .await?;
+        if let Err(e) = t.commit().await {
+            A::on_namespace_dropped(warehouse_id, namespace_id, state.v1_state.auth)
+                .await
+                .map_err(|e| {
+                    tracing::warn!(
+                        "Failed to clean up authorization of namespace: {:?}",
+                        e.error
+                    );
+                })
+                .ok();
+            return Err(e);
+        }
+        state.v1_state.publisher.notify();
         Ok(r)
     }
 
@@ -186,7 +282,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         request_metadata: RequestMetadata,
     ) -> Result<()> {
         //  ------------------- VALIDATIONS -------------------
-        let warehouse_id = require_warehouse_id(parameters.prefix)?;
+        let warehouse_id = require_warehouse_id(parameters.prefix.clone())?;
         validate_namespace_ident(&parameters.namespace)?;
 
         if CONFIG
@@ -206,15 +302,62 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             &request_metadata,
             warehouse_id,
             &parameters.namespace,
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
         .await?;
 
         //  ------------------- BUSINESS LOGIC -------------------
         let mut t = C::Transaction::begin_write(state.v1_state.catalog).await?;
-        let r = C::drop_namespace(warehouse_id, &parameters.namespace, t.transaction()).await?;
+        let namespace =
+            C::get_namespace(warehouse_id, &parameters.namespace, t.transaction()).await?;
+        C::drop_namespace(warehouse_id, &parameters.namespace, t.transaction()).await?;
+        state
+            .v1_state
+            .publisher
+            .publish::<C>(
+                uuid::Uuid::now_v7(),
+                "dropNamespace",
+                serde_json::Value::Null,
+                namespace_event_metadata(
+                    warehouse_id,
+                    namespace.namespace_id,
+                    &namespace.namespace,
+                    parameters
+                        .prefix
+                        .map(Prefix::into_string)
+                        .unwrap_or_default(),
+                    &request_metadata,
+                ),
+                t.transaction(),
+            )
+            .await?;
         t.commit().await?;
-        Ok(r)
+        state.v1_state.publisher.notify();
+
+        A::on_namespace_dropped(warehouse_id, namespace.namespace_id, state.v1_state.auth)
+            .await
+            .map_err(|e| {
+                tracing::warn!(
+                    "Failed to clean up authorization of namespace: {:?}",
+                    e.error
+                );
+            })
+            .ok();
+
+        // Delete the secret of the storage profile override - never fail the request if the deletion fails
+        if let Some(secret_id) = namespace.storage_secret_id {
+            state
+                .v1_state
+                .secrets
+                .delete_secret(&secret_id)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("Failed to delete namespace storage secret: {:?}", e.error);
+                })
+                .ok();
+        }
+
+        Ok(())
     }
 
     /// Set or remove properties on a namespace
@@ -225,7 +368,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         request_metadata: RequestMetadata,
     ) -> Result<UpdateNamespacePropertiesResponse> {
         //  ------------------- VALIDATIONS -------------------
-        let warehouse_id = require_warehouse_id(parameters.prefix)?;
+        let warehouse_id = require_warehouse_id(parameters.prefix.clone())?;
         validate_namespace_ident(&parameters.namespace)?;
         let UpdateNamespacePropertiesRequest { removals, updates } = request;
         updates
@@ -253,6 +396,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         let mut t = C::Transaction::begin_write(state.v1_state.catalog).await?;
         let previous_properties =
             C::get_namespace(warehouse_id, &parameters.namespace, t.transaction()).await?;
+        let namespace_id = previous_properties.namespace_id;
         let (new_properties, r) =
             update_namespace_properties(previous_properties.properties, updates, removals);
         C::update_namespace_properties(
@@ -262,11 +406,52 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             t.transaction(),
         )
         .await?;
+        state
+            .v1_state
+            .publisher
+            .publish::<C>(
+                uuid::Uuid::now_v7(),
+                "updateNamespaceProperties",
+                maybe_body_to_json(&r),
+                namespace_event_metadata(
+                    warehouse_id,
+                    namespace_id,
+                    &parameters.namespace,
+                    parameters
+                        .prefix
+                        .map(Prefix::into_string)
+                        .unwrap_or_default(),
+                    &request_metadata,
+                ),
+                t.transaction(),
+            )
+            .await?;
         t.commit().await?;
+        state.v1_state.publisher.notify();
         Ok(r)
     }
 }
 
+/// Metadata of an event about `namespace`. The name of the event is the last level of the namespace.
+pub(crate) fn namespace_event_metadata(
+    warehouse_id: WarehouseIdent,
+    namespace_id: NamespaceIdentUuid,
+    namespace: &NamespaceIdent,
+    prefix: String,
+    request_metadata: &RequestMetadata,
+) -> EventMetadata {
+    EventMetadata {
+        entity: EventEntity::from(namespace_id),
+        warehouse_id: *warehouse_id,
+        name: namespace.as_ref().last().cloned().unwrap_or_default(),
+        namespace: namespace.to_url_string(),
+        prefix,
+        num_events: 1,
+        sequence_number: 0,
+        trace_id: request_metadata.request_id,
+    }
+}
+
 pub(crate) fn uppercase_first_letter(s: &str) -> String {
     let mut c = s.chars();
     match c.next() {
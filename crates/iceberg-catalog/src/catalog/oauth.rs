@@ -0,0 +1,428 @@
+use crate::api::iceberg::v1::{ApiContext, ErrorModel, Result};
+use crate::api::{
+    OAuthAccessTokenType, OAuthClientCredentialsRequest, OAuthTokenExchangeRequest,
+    OAuthTokenRequest, OAuthTokenResponse, OAuthTokenType,
+};
+use crate::request_metadata::RequestMetadata;
+use crate::service::auth::{AuthConfigHandler, AuthZHandler};
+use crate::service::token_verification::{catalog_token_warehouse, AuthDetails, Verifier};
+use crate::service::{config::ConfigProvider, Catalog, SecretStore, State, Transaction};
+use crate::WarehouseIdent;
+use http::StatusCode;
+use serde::Deserialize;
+use std::str::FromStr;
+
+use super::config::Server;
+
+/// Prefix of the scope used to request a token for a specific warehouse,
+/// i.e. `warehouse:<warehouse-id>`.
+pub(crate) const WAREHOUSE_SCOPE_PREFIX: &str = "warehouse:";
+
+lazy_static::lazy_static! {
+    static ref HTTP_CLIENT: reqwest::Client = reqwest::Client::new();
+}
+
+#[async_trait::async_trait]
+impl<
+        C: ConfigProvider<D>,
+        A: AuthZHandler,
+        D: Catalog,
+        S: SecretStore,
+        T: AuthConfigHandler<A>,
+    > crate::api::iceberg::v1::oauth::Service<State<A, D, S>> for Server<C, D, T, A>
+{
+    async fn get_token(
+        api_context: ApiContext<State<A, D, S>>,
+        request_metadata: RequestMetadata,
+        request: OAuthTokenRequest,
+    ) -> Result<OAuthTokenResponse> {
+        let verifier = api_context.v1_state.token_verifier.clone().ok_or_else(|| {
+            ErrorModel::not_implemented(
+                "Authentication is not enabled for this catalog, tokens are not required.",
+                "OAuthNotEnabled",
+                None,
+            )
+        })?;
+
+        match request {
+            OAuthTokenRequest::OAuthClientCredentialsRequest(request) => {
+                client_credentials(&verifier, request).await
+            }
+            OAuthTokenRequest::OAuthTokenExchangeRequest(request) => {
+                exchange_token::<A, D, S, T>(api_context, &verifier, request_metadata, request)
+                    .await
+            }
+        }
+    }
+}
+
+/// Forward a `client_credentials` grant to the token endpoint of the `OpenID` provider.
+async fn client_credentials(
+    verifier: &Verifier,
+    request: OAuthClientCredentialsRequest,
+) -> Result<OAuthTokenResponse> {
+    let token_endpoint = verifier.token_endpoint().ok_or_else(|| {
+        ErrorModel::not_implemented(
+            "The OpenID provider does not advertise a token endpoint.",
+            "OAuthTokenEndpointMissing",
+            None,
+        )
+    })?;
+
+    let mut form = vec![
+        ("grant_type", "client_credentials".to_string()),
+        ("client_id", request.client_id),
+        ("client_secret", request.client_secret),
+    ];
+    if let Some(scope) = request.scope {
+        form.push(("scope", scope));
+    }
+
+    let response = HTTP_CLIENT
+        .post(token_endpoint.clone())
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::BAD_GATEWAY.into())
+                .message("Failed to reach the token endpoint of the OpenID provider.".to_string())
+                .r#type("OAuthProviderUnavailable".to_string())
+                .source(Some(Box::new(e)))
+                .build()
+        })?;
+
+    let status = response.status();
+    let body = response.bytes().await.map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::BAD_GATEWAY.into())
+            .message("Failed to read the response of the OpenID provider.".to_string())
+            .r#type("OAuthProviderUnavailable".to_string())
+            .source(Some(Box::new(e)))
+            .build()
+    })?;
+
+    Ok(parse_provider_response(status, &body)?)
+}
+
+/// Successful token response of the `OpenID` provider. Providers differ in the
+/// capitalization of `token_type`, so it is parsed leniently.
+#[derive(Debug, Deserialize)]
+struct ProviderTokenResponse {
+    access_token: String,
+    token_type: String,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+fn parse_provider_response(
+    status: reqwest::StatusCode,
+    body: &[u8],
+) -> std::result::Result<OAuthTokenResponse, ErrorModel> {
+    if !status.is_success() {
+        let (r#type, message) = match serde_json::from_slice::<ProviderErrorResponse>(body) {
+            Ok(e) => (
+                e.error,
+                e.error_description
+                    .unwrap_or_else(|| "The OpenID provider rejected the request.".to_string()),
+            ),
+            Err(_) => (
+                "OAuthProviderError".to_string(),
+                format!("The OpenID provider responded with status {status}."),
+            ),
+        };
+        // Client errors (i.e. invalid credentials) are passed on, anything else
+        // is a problem of the provider.
+        let code = if status.is_client_error() {
+            status.as_u16()
+        } else {
+            StatusCode::BAD_GATEWAY.as_u16()
+        };
+        return Err(ErrorModel::builder()
+            .code(code)
+            .message(message)
+            .r#type(r#type)
+            .build());
+    }
+
+    let response: ProviderTokenResponse = serde_json::from_slice(body).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::BAD_GATEWAY.into())
+            .message("The OpenID provider returned an invalid token response.".to_string())
+            .r#type("OAuthProviderInvalidResponse".to_string())
+            .source(Some(Box::new(e)))
+            .build()
+    })?;
+
+    let token_type = if response.token_type.eq_ignore_ascii_case("bearer") {
+        OAuthAccessTokenType::Bearer
+    } else {
+        OAuthAccessTokenType::NA
+    };
+
+    Ok(OAuthTokenResponse {
+        access_token: response.access_token,
+        token_type,
+        expires_in: response.expires_in,
+        issued_token_type: Some(OAuthTokenType::AccessToken),
+        refresh_token: response.refresh_token,
+        scope: response.scope,
+    })
+}
+
+/// RFC 8693 token exchange. The subject token must be accepted by the [`Verifier`],
+/// API keys are rejected. Tokens issued by the catalog can only be exchanged for
+/// their own warehouse.
+/// If a `warehouse:<id>` scope is requested, the [`AuthConfigHandler`] may issue a
+/// warehouse-scoped token. Otherwise the subject token is returned unchanged.
+async fn exchange_token<A: AuthZHandler, D: Catalog, S: SecretStore, T: AuthConfigHandler<A>>(
+    api_context: ApiContext<State<A, D, S>>,
+    verifier: &Verifier,
+    request_metadata: RequestMetadata,
+    request: OAuthTokenExchangeRequest,
+) -> Result<OAuthTokenResponse> {
+    if !matches!(
+        request.subject_token_type,
+        OAuthTokenType::AccessToken | OAuthTokenType::Jwt
+    ) {
+        return Err(ErrorModel::bad_request(
+            "Only access tokens and JWTs can be exchanged.",
+            "OAuthUnsupportedSubjectTokenType",
+            None,
+        )
+        .into());
+    }
+    if request.actor_token.is_some() {
+        return Err(ErrorModel::bad_request(
+            "Delegation via actor tokens is not supported.",
+            "OAuthActorTokenNotSupported",
+            None,
+        )
+        .into());
+    }
+
+    let auth_details = verifier.authenticate(&request.subject_token).await?;
+    let requested_warehouse = warehouse_from_scope(request.scope.as_deref())?;
+    check_subject_scope(&auth_details, requested_warehouse)?;
+    let expires_in = expires_in(auth_details.claims().exp, chrono::Utc::now().timestamp());
+    let metadata = RequestMetadata {
+        auth_details: Some(auth_details),
+        ..request_metadata
+    };
+
+    if let Some(warehouse_id) = requested_warehouse {
+        T::check_user_get_config_for_warehouse(
+            api_context.v1_state.auth.clone(),
+            warehouse_id,
+            &metadata,
+        )
+        .await?;
+
+        let mut t = D::Transaction::begin_read(api_context.v1_state.catalog).await?;
+        let warehouse = D::get_warehouse(warehouse_id, t.transaction()).await?;
+        t.commit().await?;
+
+        let new_token = T::exchange_token_for_warehouse(
+            api_context.v1_state.auth,
+            &metadata,
+            &warehouse.project_id,
+            warehouse_id,
+        )
+        .await?;
+
+        let new_token = match new_token {
+            Some(new_token) => Some((new_token, None)),
+            None => verifier
+                .token_issuer()
+                .map(|token_issuer| {
+                    token_issuer.issue_for_request(&metadata, &warehouse.project_id, warehouse_id)
+                })
+                .transpose()?
+                .flatten()
+                .map(|(token, expires_in)| (token, Some(expires_in))),
+        };
+
+        if let Some((new_token, expires_in)) = new_token {
+            return Ok(OAuthTokenResponse {
+                access_token: new_token,
+                token_type: OAuthAccessTokenType::Bearer,
+                expires_in,
+                issued_token_type: Some(OAuthTokenType::AccessToken),
+                refresh_token: None,
+                scope: request.scope,
+            });
+        }
+    }
+
+    Ok(OAuthTokenResponse {
+        access_token: request.subject_token,
+        token_type: OAuthAccessTokenType::Bearer,
+        expires_in: Some(expires_in),
+        issued_token_type: Some(OAuthTokenType::AccessToken),
+        refresh_token: None,
+        scope: request.scope,
+    })
+}
+
+/// The scope of API keys and catalog tokens is enforced per request path, which
+/// doesn't apply to token exchange. API keys are rejected, catalog tokens must
+/// not be exchanged for a different warehouse.
+fn check_subject_scope(
+    auth_details: &AuthDetails,
+    requested_warehouse: Option<WarehouseIdent>,
+) -> std::result::Result<(), ErrorModel> {
+    match auth_details {
+        AuthDetails::ApiKey(_) => Err(ErrorModel::bad_request(
+            "API keys can't be exchanged, use them as bearer token instead.",
+            "OAuthUnsupportedSubjectTokenType",
+            None,
+        )),
+        AuthDetails::CatalogToken(claims) => {
+            let warehouse_id = catalog_token_warehouse(claims)?;
+            match requested_warehouse {
+                Some(requested) if requested != warehouse_id => Err(ErrorModel::forbidden(
+                    "Catalog tokens can only be exchanged for their own warehouse.",
+                    "WarehouseScopeMismatch",
+                    None,
+                )),
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+fn expires_in(exp: usize, now: i64) -> u64 {
+    let exp = u64::try_from(exp).unwrap_or(u64::MAX);
+    let now = u64::try_from(now).unwrap_or_default();
+    exp.saturating_sub(now)
+}
+
+/// Extract the warehouse from a space separated list of scopes.
+fn warehouse_from_scope(
+    scope: Option<&str>,
+) -> std::result::Result<Option<WarehouseIdent>, ErrorModel> {
+    let Some(scope) = scope else {
+        return Ok(None);
+    };
+
+    let mut warehouses = scope
+        .split_whitespace()
+        .filter_map(|s| s.strip_prefix(WAREHOUSE_SCOPE_PREFIX));
+
+    let Some(warehouse) = warehouses.next() else {
+        return Ok(None);
+    };
+    if warehouses.next().is_some() {
+        return Err(ErrorModel::bad_request(
+            "Only a single warehouse scope can be requested.",
+            "OAuthInvalidScope",
+            None,
+        ));
+    }
+
+    uuid::Uuid::from_str(warehouse)
+        .map(|id| Some(id.into()))
+        .map_err(|e| {
+            ErrorModel::bad_request(
+                format!("Invalid warehouse scope: {warehouse}"),
+                "OAuthInvalidScope",
+                Some(Box::new(e)),
+            )
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_warehouse_from_scope() {
+        let id = uuid::Uuid::now_v7();
+        assert_eq!(warehouse_from_scope(None).unwrap(), None);
+        assert_eq!(warehouse_from_scope(Some("catalog")).unwrap(), None);
+        assert_eq!(
+            warehouse_from_scope(Some(&format!("catalog warehouse:{id}")))
+                .unwrap()
+                .map(|w| *w),
+            Some(id)
+        );
+        warehouse_from_scope(Some("warehouse:not-a-uuid")).unwrap_err();
+        warehouse_from_scope(Some(&format!("warehouse:{id} warehouse:{id}"))).unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_provider_response() {
+        let body = serde_json::json!({
+            "access_token": "abc",
+            "token_type": "Bearer",
+            "expires_in": 300,
+        })
+        .to_string();
+        let response = parse_provider_response(reqwest::StatusCode::OK, body.as_bytes()).unwrap();
+        assert_eq!(response.access_token, "abc");
+        assert_eq!(response.token_type, OAuthAccessTokenType::Bearer);
+        assert_eq!(response.expires_in, Some(300));
+        assert_eq!(
+            response.issued_token_type,
+            Some(OAuthTokenType::AccessToken)
+        );
+    }
+
+    #[test]
+    fn test_parse_provider_error() {
+        let body = serde_json::json!({
+            "error": "invalid_client",
+            "error_description": "Invalid client credentials",
+        })
+        .to_string();
+        let err = parse_provider_response(reqwest::StatusCode::UNAUTHORIZED, body.as_bytes())
+            .unwrap_err();
+        assert_eq!(err.code, 401);
+        assert_eq!(err.r#type, "invalid_client");
+
+        let err = parse_provider_response(reqwest::StatusCode::INTERNAL_SERVER_ERROR, b"oops")
+            .unwrap_err();
+        assert_eq!(err.code, 502);
+        assert_eq!(err.r#type, "OAuthProviderError");
+    }
+
+    #[test]
+    fn test_catalog_token_is_only_exchanged_for_its_warehouse() {
+        let warehouse_id = uuid::Uuid::now_v7();
+        let claims: crate::service::token_verification::Claims =
+            serde_json::from_value(serde_json::json!({
+                "sub": "alice",
+                "iat": 22,
+                "aud": "iceberg-catalog",
+                "iss": "https://catalog.example.com/catalog",
+                "exp": 9022,
+                "warehouse_id": warehouse_id.to_string(),
+            }))
+            .unwrap();
+
+        let catalog_token = AuthDetails::CatalogToken(claims.clone());
+        check_subject_scope(&catalog_token, None).unwrap();
+        check_subject_scope(&catalog_token, Some(warehouse_id.into())).unwrap();
+        let err =
+            check_subject_scope(&catalog_token, Some(uuid::Uuid::now_v7().into())).unwrap_err();
+        assert_eq!(err.code, 403);
+        assert_eq!(err.r#type, "WarehouseScopeMismatch");
+
+        // Tokens of the OpenID provider can be exchanged for any warehouse
+        check_subject_scope(&AuthDetails::JWT(claims), Some(uuid::Uuid::now_v7().into())).unwrap();
+    }
+
+    #[test]
+    fn test_expires_in() {
+        assert_eq!(expires_in(100, 40), 60);
+        assert_eq!(expires_in(100, 140), 0);
+    }
+}
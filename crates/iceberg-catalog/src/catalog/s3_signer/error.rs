@@ -8,6 +8,12 @@ pub(crate) enum SignError {
         expected_location: String,
         actual_location: String,
     },
+    #[error("Header `{header}` must be `{expected}` for this storage profile.")]
+    ServerSideEncryptionMismatch {
+        header: String,
+        expected: String,
+        actual: Option<String>,
+    },
 }
 
 impl From<SignError> for IcebergErrorResponse {
@@ -21,6 +27,20 @@ impl From<SignError> for IcebergErrorResponse {
             } => ErrorModel::bad_request(message, "RequestUriMismatch", None)
                 .append_detail(format!("Request URI: {request_uri}"))
                 .into(),
+            SignError::ServerSideEncryptionMismatch {
+                header: _,
+                expected: _,
+                actual,
+            } => ErrorModel::builder()
+                .code(http::StatusCode::FORBIDDEN.into())
+                .message(message)
+                .r#type("ServerSideEncryptionMismatch".to_string())
+                .build()
+                .append_detail(format!(
+                    "Received: {}",
+                    actual.as_deref().unwrap_or("<not set>")
+                ))
+                .into(),
         }
     }
 }
@@ -16,14 +16,19 @@ use crate::catalog::require_warehouse_id;
 use crate::request_metadata::RequestMetadata;
 use crate::service::secrets::SecretStore;
 use crate::service::storage::{S3Location, S3Profile, StorageCredential};
-use crate::service::{auth::AuthZHandler, Catalog, ListFlags, State};
+use crate::service::{
+    auth::{AuthZHandler, UserWarehouse},
+    Catalog, ListFlags, State,
+};
 use crate::service::{GetTableMetadataResponse, TableIdentUuid};
 use crate::WarehouseIdent;
 
 const READ_METHODS: &[&str] = &["GET", "HEAD"];
 const WRITE_METHODS: &[&str] = &["PUT", "POST", "DELETE"];
+const SSE_HEADER: &str = "x-amz-server-side-encryption";
+const SSE_KMS_KEY_ID_HEADER: &str = "x-amz-server-side-encryption-aws-kms-key-id";
 // Keep only the following headers:
-const HEADERS_TO_SIGN: [&str; 7] = [
+const HEADERS_TO_SIGN: [&str; 11] = [
     "amz-sdk-invocation-id",
     "amz-sdk-request",
     "content-length",
@@ -31,6 +36,10 @@ const HEADERS_TO_SIGN: [&str; 7] = [
     "expect",
     "host",
     "content-md5",
+    SSE_HEADER,
+    SSE_KMS_KEY_ID_HEADER,
+    "x-amz-server-side-encryption-context",
+    "x-amz-server-side-encryption-bucket-key-enabled",
 ];
 
 #[async_trait::async_trait]
@@ -48,6 +57,22 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
     ) -> Result<S3SignResponse> {
         let warehouse_id = require_warehouse_id(prefix.clone())?;
 
+        // Warehouse-scoped catalog tokens must not be used to sign requests for other warehouses.
+        if let Some(UserWarehouse {
+            warehouse_id: Some(token_warehouse_id),
+            ..
+        }) = UserWarehouse::from_catalog_token(&request_metadata)
+        {
+            if token_warehouse_id != warehouse_id {
+                return Err(ErrorModel::forbidden(
+                    "Token is not valid for this warehouse",
+                    "WarehouseScopeMismatch",
+                    None,
+                )
+                .into());
+            }
+        }
+
         let S3SignRequest {
             region: request_region,
             uri: request_url,
@@ -61,8 +86,9 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
         let parsed_url = s3_utils::parse_s3_url(&request_url)?;
 
-        // Unfortunately there is currently no way to pass information about warehouse_id & table_id
-        // to this function from a get_table or create_table process without exchanging the token.
+        // Unfortunately there is currently no way to pass information about the table_id
+        // to this function from a get_table or create_table process. Catalog-issued tokens
+        // only carry the warehouse.
         // Spark does not support per-table signer.uri.
         // Tabular uses a token-exchange to include the information.
         // We are looking for the path in the database, which allows us to also work with AuthN solutions
@@ -148,6 +174,13 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
         validate_region(&request_region, &storage_profile).map_err(extend_err)?;
         validate_uri(&parsed_url, &location).map_err(extend_err)?;
+        validate_server_side_encryption(
+            &request_method,
+            &parsed_url.url,
+            &request_headers,
+            &storage_profile,
+        )
+        .map_err(extend_err)?;
 
         // If all is good, we need the storage secret
         let storage_secret = if let Some(storage_secret_ident) = storage_secret_ident {
@@ -332,6 +365,62 @@ fn validate_region(region: &str, storage_profile: &S3Profile) -> Result<()> {
     Ok(())
 }
 
+/// Requests creating objects must use the encryption configured in the storage profile.
+/// Other requests, such as uploading parts of a multipart upload, inherit the encryption
+/// of the object and are not checked.
+fn validate_server_side_encryption(
+    method: &http::Method,
+    request_url: &url::Url,
+    request_headers: &HashMap<String, Vec<String>>,
+    storage_profile: &S3Profile,
+) -> Result<()> {
+    let Some(sse) = &storage_profile.server_side_encryption else {
+        return Ok(());
+    };
+
+    let has_query_key = |key: &str| request_url.query_pairs().any(|(k, _)| k == key);
+    let creates_object = if method == http::Method::PUT {
+        // PutObject & CopyObject. UploadPart carries an `uploadId`.
+        !has_query_key("uploadId")
+    } else if method == http::Method::POST {
+        // CreateMultipartUpload
+        has_query_key("uploads")
+    } else {
+        false
+    };
+    if !creates_object {
+        return Ok(());
+    }
+
+    let header_value = |name: &str| {
+        request_headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| v.first())
+            .map(String::as_str)
+    };
+
+    let check_header = |name: &str, expected: &str| {
+        let actual = header_value(name);
+        if actual == Some(expected) {
+            Ok(())
+        } else {
+            Err(SignError::ServerSideEncryptionMismatch {
+                header: name.to_string(),
+                expected: expected.to_string(),
+                actual: actual.map(ToString::to_string),
+            })
+        }
+    };
+
+    check_header(SSE_HEADER, sse.header_value())?;
+    if let Some(kms_key_id) = sse.kms_key_id() {
+        check_header(SSE_KMS_KEY_ID_HEADER, kms_key_id)?;
+    }
+
+    Ok(())
+}
+
 async fn validate_table_method<A: AuthZHandler>(
     method: &http::Method,
     metadata: &RequestMetadata,
@@ -467,7 +556,7 @@ pub(super) mod s3_utils {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::service::storage::S3Flavor;
+    use crate::service::storage::{S3Flavor, S3ServerSideEncryption};
 
     #[derive(Debug)]
     struct TC {
@@ -726,6 +815,7 @@ mod test {
             sts_role_arn: None,
             sts_enabled: false,
             flavor: S3Flavor::Minio,
+            server_side_encryption: None,
         };
 
         let result = validate_region("my-region", &storage_profile);
@@ -734,4 +824,88 @@ mod test {
         let result = validate_region("wrong-region", &storage_profile);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_validate_server_side_encryption() {
+        let storage_profile = S3Profile {
+            bucket: "my-bucket".to_string(),
+            endpoint: None,
+            region: "my-region".to_string(),
+            assume_role_arn: None,
+            path_style_access: None,
+            key_prefix: None,
+            sts_role_arn: None,
+            sts_enabled: false,
+            flavor: S3Flavor::Aws,
+            server_side_encryption: Some(S3ServerSideEncryption::SseKms {
+                kms_key_id: Some("my-key".to_string()),
+            }),
+        };
+        let object_url =
+            url::Url::parse("https://my-bucket.s3.my-region.amazonaws.com/table/data/file.parquet")
+                .unwrap();
+        let encrypted_headers = HashMap::from([
+            (SSE_HEADER.to_string(), vec!["aws:kms".to_string()]),
+            (
+                "X-Amz-Server-Side-Encryption-Aws-Kms-Key-Id".to_string(),
+                vec!["my-key".to_string()],
+            ),
+        ]);
+
+        validate_server_side_encryption(
+            &http::Method::PUT,
+            &object_url,
+            &encrypted_headers,
+            &storage_profile,
+        )
+        .unwrap();
+        // Missing headers
+        validate_server_side_encryption(
+            &http::Method::PUT,
+            &object_url,
+            &HashMap::new(),
+            &storage_profile,
+        )
+        .unwrap_err();
+        // Wrong key
+        let mut wrong_key_headers = encrypted_headers.clone();
+        wrong_key_headers.insert(
+            SSE_KMS_KEY_ID_HEADER.to_string(),
+            vec!["other-key".to_string()],
+        );
+        validate_server_side_encryption(
+            &http::Method::PUT,
+            &object_url,
+            &wrong_key_headers,
+            &storage_profile,
+        )
+        .unwrap_err();
+        // Reads and uploads of parts are not checked
+        validate_server_side_encryption(
+            &http::Method::GET,
+            &object_url,
+            &HashMap::new(),
+            &storage_profile,
+        )
+        .unwrap();
+        let mut part_url = object_url.clone();
+        part_url.set_query(Some("partNumber=1&uploadId=abc"));
+        validate_server_side_encryption(
+            &http::Method::PUT,
+            &part_url,
+            &HashMap::new(),
+            &storage_profile,
+        )
+        .unwrap();
+        // Initiating a multipart upload is checked
+        let mut create_multipart_url = object_url.clone();
+        create_multipart_url.set_query(Some("uploads"));
+        validate_server_side_encryption(
+            &http::Method::POST,
+            &create_multipart_url,
+            &HashMap::new(),
+            &storage_profile,
+        )
+        .unwrap_err();
+    }
 }
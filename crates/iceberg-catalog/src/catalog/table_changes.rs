@@ -0,0 +1,406 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use iceberg::spec::{NestedFieldRef, Schema, TableMetadata, Type};
+use serde::Serialize;
+
+/// Version of [`TableChanges`]. Incremented on incompatible changes of the payload.
+pub(crate) const TABLE_CHANGES_VERSION: u32 = 1;
+
+/// Data of `updateTable` events, computed from the table metadata before and after the commit.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TableChanges {
+    pub(crate) version: u32,
+    /// `None` if the table was staged.
+    pub(crate) previous_metadata_location: Option<String>,
+    pub(crate) new_metadata_location: String,
+    pub(crate) previous_snapshot_id: Option<i64>,
+    pub(crate) current_snapshot_id: Option<i64>,
+    pub(crate) added_snapshot_ids: Vec<i64>,
+    pub(crate) removed_snapshot_ids: Vec<i64>,
+    /// `None` if the current schema did not change.
+    pub(crate) schema: Option<SchemaChanges>,
+    /// `None` if the default partition spec did not change.
+    pub(crate) partition_spec: Option<PartitionSpecChanges>,
+    /// Body of the commit request.
+    pub(crate) request: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct SchemaChanges {
+    pub(crate) previous_schema_id: i32,
+    pub(crate) new_schema_id: i32,
+    pub(crate) added_columns: Vec<Column>,
+    pub(crate) dropped_columns: Vec<Column>,
+    pub(crate) renamed_columns: Vec<RenamedColumn>,
+    pub(crate) retyped_columns: Vec<RetypedColumn>,
+}
+
+/// A column of a schema. Nested columns are named by their path, i.e. `address.city`.
+/// Primitive types are rendered as in the Iceberg spec, nested types as `struct`, `list` or `map`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Column {
+    pub(crate) id: i32,
+    pub(crate) name: String,
+    #[serde(rename = "type")]
+    pub(crate) typ: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct RenamedColumn {
+    pub(crate) id: i32,
+    pub(crate) previous_name: String,
+    pub(crate) new_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct RetypedColumn {
+    pub(crate) id: i32,
+    pub(crate) name: String,
+    pub(crate) previous_type: String,
+    pub(crate) new_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PartitionSpecChanges {
+    pub(crate) previous_spec_id: i32,
+    pub(crate) new_spec_id: i32,
+    pub(crate) added_fields: Vec<PartitionField>,
+    pub(crate) removed_fields: Vec<PartitionField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PartitionField {
+    pub(crate) field_id: i32,
+    pub(crate) source_id: i32,
+    pub(crate) name: String,
+    pub(crate) transform: String,
+}
+
+impl TableChanges {
+    pub(crate) fn new(
+        previous: &TableMetadata,
+        previous_metadata_location: Option<String>,
+        new: &TableMetadata,
+        new_metadata_location: String,
+        request: serde_json::Value,
+    ) -> Self {
+        let previous_snapshots = snapshot_ids(previous);
+        let new_snapshots = snapshot_ids(new);
+
+        Self {
+            version: TABLE_CHANGES_VERSION,
+            previous_metadata_location,
+            new_metadata_location,
+            previous_snapshot_id: previous.current_snapshot().map(|s| s.snapshot_id()),
+            current_snapshot_id: new.current_snapshot().map(|s| s.snapshot_id()),
+            added_snapshot_ids: new_snapshots
+                .difference(&previous_snapshots)
+                .copied()
+                .collect(),
+            removed_snapshot_ids: previous_snapshots
+                .difference(&new_snapshots)
+                .copied()
+                .collect(),
+            schema: SchemaChanges::new(previous.current_schema(), new.current_schema()),
+            partition_spec: PartitionSpecChanges::new(previous, new),
+            request,
+        }
+    }
+}
+
+impl SchemaChanges {
+    fn new(previous: &Schema, new: &Schema) -> Option<Self> {
+        if previous.schema_id() == new.schema_id() {
+            return None;
+        }
+
+        let previous_columns = columns(previous);
+        let new_columns = columns(new);
+        let mut changes = Self {
+            previous_schema_id: previous.schema_id(),
+            new_schema_id: new.schema_id(),
+            added_columns: new_columns
+                .iter()
+                .filter(|(id, _)| !previous_columns.contains_key(id))
+                .map(|(_, column)| column.clone())
+                .collect(),
+            dropped_columns: previous_columns
+                .iter()
+                .filter(|(id, _)| !new_columns.contains_key(id))
+                .map(|(_, column)| column.clone())
+                .collect(),
+            renamed_columns: vec![],
+            retyped_columns: vec![],
+        };
+
+        for (id, previous_column) in &previous_columns {
+            let Some(new_column) = new_columns.get(id) else {
+                continue;
+            };
+            if previous_column.name != new_column.name {
+                changes.renamed_columns.push(RenamedColumn {
+                    id: *id,
+                    previous_name: previous_column.name.clone(),
+                    new_name: new_column.name.clone(),
+                });
+            }
+            if previous_column.typ != new_column.typ {
+                changes.retyped_columns.push(RetypedColumn {
+                    id: *id,
+                    name: new_column.name.clone(),
+                    previous_type: previous_column.typ.clone(),
+                    new_type: new_column.typ.clone(),
+                });
+            }
+        }
+
+        Some(changes)
+    }
+}
+
+impl PartitionSpecChanges {
+    fn new(previous: &TableMetadata, new: &TableMetadata) -> Option<Self> {
+        if previous.default_spec_id == new.default_spec_id {
+            return None;
+        }
+
+        let previous_fields = partition_fields(previous);
+        let new_fields = partition_fields(new);
+        Some(Self {
+            previous_spec_id: previous.default_spec_id,
+            new_spec_id: new.default_spec_id,
+            added_fields: new_fields
+                .iter()
+                .filter(|(id, _)| !previous_fields.contains_key(id))
+                .map(|(_, field)| field.clone())
+                .collect(),
+            removed_fields: previous_fields
+                .iter()
+                .filter(|(id, _)| !new_fields.contains_key(id))
+                .map(|(_, field)| field.clone())
+                .collect(),
+        })
+    }
+}
+
+fn snapshot_ids(metadata: &TableMetadata) -> BTreeSet<i64> {
+    metadata.snapshots().map(|s| s.snapshot_id()).collect()
+}
+
+/// Fields of the default partition spec by field id.
+fn partition_fields(metadata: &TableMetadata) -> BTreeMap<i32, PartitionField> {
+    metadata
+        .default_partition_spec()
+        .map(|spec| {
+            spec.fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.field_id,
+                        PartitionField {
+                            field_id: field.field_id,
+                            source_id: field.source_id,
+                            name: field.name.clone(),
+                            transform: field.transform.to_string(),
+                        },
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// All columns of the schema by field id, including nested ones.
+fn columns(schema: &Schema) -> BTreeMap<i32, Column> {
+    let mut columns = BTreeMap::new();
+    collect_columns(schema.as_struct().fields(), None, &mut columns);
+    columns
+}
+
+fn collect_columns(
+    fields: &[NestedFieldRef],
+    parent: Option<&str>,
+    columns: &mut BTreeMap<i32, Column>,
+) {
+    for field in fields {
+        let name = parent.map_or_else(
+            || field.name.clone(),
+            |parent| format!("{parent}.{}", field.name),
+        );
+        let typ = match &*field.field_type {
+            Type::Primitive(primitive) => primitive.to_string(),
+            Type::Struct(struct_type) => {
+                collect_columns(struct_type.fields(), Some(&name), columns);
+                "struct".to_string()
+            }
+            Type::List(list) => {
+                collect_columns(
+                    std::slice::from_ref(&list.element_field),
+                    Some(&name),
+                    columns,
+                );
+                "list".to_string()
+            }
+            Type::Map(map) => {
+                collect_columns(
+                    &[map.key_field.clone(), map.value_field.clone()],
+                    Some(&name),
+                    columns,
+                );
+                "map".to_string()
+            }
+        };
+        columns.insert(
+            field.id,
+            Column {
+                id: field.id,
+                name,
+                typ,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use iceberg::spec::{
+        NestedField, PrimitiveType, StructType, Transform, UnboundPartitionField,
+        UnboundPartitionSpec,
+    };
+    use iceberg_ext::spec::TableMetadataAggregate;
+
+    fn schema(schema_id: i32, fields: Vec<NestedField>) -> Schema {
+        Schema::builder()
+            .with_schema_id(schema_id)
+            .with_fields(fields.into_iter().map(Arc::new).collect::<Vec<_>>())
+            .build()
+            .unwrap()
+    }
+
+    fn previous_metadata() -> TableMetadata {
+        let schema = schema(
+            0,
+            vec![
+                NestedField::required(1, "id", Type::Primitive(PrimitiveType::Int)),
+                NestedField::optional(2, "name", Type::Primitive(PrimitiveType::String)),
+                NestedField::optional(3, "legacy", Type::Primitive(PrimitiveType::String)),
+            ],
+        );
+        TableMetadataAggregate::new("s3://bucket/table".to_string(), schema)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unchanged_metadata() {
+        let metadata = previous_metadata();
+        let changes = TableChanges::new(
+            &metadata,
+            Some("s3://bucket/table/metadata/1.metadata.json".to_string()),
+            &metadata,
+            "s3://bucket/table/metadata/2.metadata.json".to_string(),
+            serde_json::Value::Null,
+        );
+        assert_eq!(changes.version, TABLE_CHANGES_VERSION);
+        assert!(changes.added_snapshot_ids.is_empty());
+        assert!(changes.removed_snapshot_ids.is_empty());
+        assert_eq!(changes.schema, None);
+        assert_eq!(changes.partition_spec, None);
+    }
+
+    #[test]
+    fn test_schema_and_partition_changes() {
+        let previous = previous_metadata();
+        let new_schema = schema(
+            1,
+            vec![
+                NestedField::required(1, "id", Type::Primitive(PrimitiveType::Long)),
+                NestedField::optional(2, "full_name", Type::Primitive(PrimitiveType::String)),
+                NestedField::optional(
+                    4,
+                    "address",
+                    Type::Struct(StructType::new(vec![Arc::new(NestedField::optional(
+                        5,
+                        "city",
+                        Type::Primitive(PrimitiveType::String),
+                    ))])),
+                ),
+            ],
+        );
+        let partition_spec = UnboundPartitionSpec::builder()
+            .add_partition_fields(vec![UnboundPartitionField::builder()
+                .source_id(1)
+                .name("id_bucket".to_string())
+                .transform(Transform::Bucket(16))
+                .build()])
+            .unwrap()
+            .build();
+        let mut aggregate = TableMetadataAggregate::new_from_metadata(previous.clone());
+        aggregate
+            .add_schema(new_schema, None)
+            .unwrap()
+            .set_current_schema(-1)
+            .unwrap()
+            .add_partition_spec(partition_spec)
+            .unwrap()
+            .set_default_partition_spec(-1)
+            .unwrap();
+        let new = aggregate.build().unwrap();
+
+        let changes = TableChanges::new(
+            &previous,
+            None,
+            &new,
+            "s3://bucket/table/metadata/1.metadata.json".to_string(),
+            serde_json::Value::Null,
+        );
+
+        let schema_changes = changes.schema.unwrap();
+        assert_eq!(schema_changes.previous_schema_id, 0);
+        assert_eq!(schema_changes.new_schema_id, 1);
+        assert_eq!(
+            schema_changes
+                .added_columns
+                .iter()
+                .map(|c| (c.name.as_str(), c.typ.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("address", "struct"), ("address.city", "string")]
+        );
+        assert_eq!(schema_changes.dropped_columns[0].name, "legacy");
+        assert_eq!(
+            schema_changes.renamed_columns,
+            vec![RenamedColumn {
+                id: 2,
+                previous_name: "name".to_string(),
+                new_name: "full_name".to_string(),
+            }]
+        );
+        assert_eq!(
+            schema_changes.retyped_columns,
+            vec![RetypedColumn {
+                id: 1,
+                name: "id".to_string(),
+                previous_type: "int".to_string(),
+                new_type: "long".to_string(),
+            }]
+        );
+
+        let partition_changes = changes.partition_spec.unwrap();
+        assert_eq!(partition_changes.new_spec_id, new.default_spec_id);
+        assert!(partition_changes.removed_fields.is_empty());
+        assert_eq!(partition_changes.added_fields.len(), 1);
+        assert_eq!(partition_changes.added_fields[0].name, "id_bucket");
+        assert_eq!(partition_changes.added_fields[0].source_id, 1);
+        assert_eq!(partition_changes.added_fields[0].transform, "bucket[16]");
+    }
+}
@@ -3,9 +3,9 @@ use std::str::FromStr as _;
 
 use crate::api::iceberg::v1::{
     ApiContext, CommitTableRequest, CommitTableResponse, CommitTransactionRequest,
-    CreateTableRequest, DataAccess, ErrorModel, ListTablesResponse, LoadTableResult,
-    NamespaceParameters, PaginationQuery, Prefix, RegisterTableRequest, RenameTableRequest, Result,
-    TableIdent, TableParameters,
+    CreateTableRequest, DataAccess, ErrorModel, IcebergErrorResponse, ListTablesResponse,
+    LoadTableResult, NamespaceParameters, PaginationQuery, Prefix, RegisterTableRequest,
+    RenameTableRequest, Result, TableIdent, TableParameters,
 };
 use crate::catalog::compression_codec::CompressionCodec;
 use crate::request_metadata::RequestMetadata;
@@ -17,12 +17,13 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use super::commit_tables::apply_commit;
+use super::table_changes::TableChanges;
 use super::{
-    io::write_metadata_file, maybe_get_secret, namespace::validate_namespace_ident,
-    require_warehouse_id, CatalogServer,
+    fetch_filtered_page, io::write_metadata_file, maybe_get_secret,
+    namespace::validate_namespace_ident, require_warehouse_id, CatalogServer,
 };
 use crate::service::contract_verification::{ContractVerification, ContractVerificationOutcome};
-use crate::service::event_publisher::{CloudEventsPublisher, EventMetadata};
+use crate::service::event_publisher::{CloudEventsPublisher, EventEntity, EventMetadata};
 use crate::service::storage::{StorageLocations as _, StoragePermissions, StorageProfile};
 use crate::service::tabular_idents::TabularIdentUuid;
 use crate::service::{
@@ -30,6 +31,7 @@ use crate::service::{
     LoadTableResponse as CatalogLoadTableResult, State, Transaction,
 };
 use crate::service::{GetNamespaceResponse, TableCommit, TableIdentUuid, WarehouseStatus};
+use crate::{SecretIdent, WarehouseIdent};
 
 #[async_trait::async_trait]
 impl<C: Catalog, A: AuthZHandler, S: SecretStore>
@@ -52,26 +54,61 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             &request_metadata,
             warehouse_id,
             &namespace,
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
         .await?;
 
         // ------------------- BUSINESS LOGIC -------------------
         let include_staged = false;
-        let tables = C::list_tables(
-            warehouse_id,
-            &namespace,
-            ListFlags {
-                include_staged,
-                include_deleted: false,
-            },
-            state.v1_state.catalog,
+        let namespace = &namespace;
+        let request_metadata = &request_metadata;
+        let (tables, next_page_token) = fetch_filtered_page(
             pagination_query,
+            |query| {
+                let catalog = state.v1_state.catalog.clone();
+                async move {
+                    let tables = C::list_tables(
+                        warehouse_id,
+                        namespace,
+                        ListFlags {
+                            include_staged,
+                            include_deleted: false,
+                        },
+                        catalog,
+                        query,
+                    )
+                    .await?;
+                    let next_page_token = tables.next_page_token.clone();
+                    Ok::<_, IcebergErrorResponse>((tables.into_iter().collect(), next_page_token))
+                }
+            },
+            |tables: Vec<(TableIdentUuid, TableIdent)>| {
+                let auth = state.v1_state.auth.clone();
+                async move {
+                    let visible = A::filter_visible_tabulars(
+                        request_metadata,
+                        warehouse_id,
+                        namespace,
+                        tables
+                            .iter()
+                            .map(|(id, _)| TabularIdentUuid::Table(**id))
+                            .collect(),
+                        auth,
+                    )
+                    .await?;
+                    Ok::<_, IcebergErrorResponse>(
+                        tables
+                            .into_iter()
+                            .filter(|(id, _)| visible.contains(&TabularIdentUuid::Table(**id)))
+                            .collect(),
+                    )
+                }
+            },
         )
         .await?;
 
         Ok(ListTablesResponse {
-            next_page_token: None,
+            next_page_token,
             identifiers: tables.into_iter().map(|t| t.1).collect(),
         })
     }
@@ -101,7 +138,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             &request_metadata,
             warehouse_id,
             &namespace,
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
         .await?;
 
@@ -111,8 +148,9 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         let mut t = C::Transaction::begin_write(state.v1_state.catalog).await?;
         let namespace = C::get_namespace(warehouse_id, &namespace, t.transaction()).await?;
         let warehouse = C::get_warehouse(warehouse_id, t.transaction()).await?;
-        let storage_profile = warehouse.storage_profile;
         require_active_warehouse(warehouse.status)?;
+        // Tables use the storage profile of their namespace if one is set.
+        let (storage_profile, storage_secret_id) = namespace.effective_storage(&warehouse);
 
         let table_location = determine_tabular_location(
             &namespace,
@@ -153,7 +191,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         .await?;
 
         // We don't commit the transaction yet, first we need to write the metadata file.
-        let storage_secret = if let Some(secret_id) = &warehouse.storage_secret_id {
+        let storage_secret = if let Some(secret_id) = &storage_secret_id {
             let secret_state = state.v1_state.secrets;
             Some(secret_state.get_secret_by_id(secret_id).await?.secret)
         } else {
@@ -190,12 +228,9 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             config: Some(config.into()),
         };
 
-        // Metadata file written, now we can commit the transaction
-        t.commit().await?;
-
-        emit_change_event(
+        emit_change_event::<C>(
             EventMetadata {
-                tabular_id: TabularIdentUuid::Table(*table_id),
+                entity: EventEntity::Table(*table_id),
                 warehouse_id: *warehouse_id,
                 name: table.name.clone(),
                 namespace: table.namespace.to_url_string(),
@@ -206,9 +241,30 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             },
             body,
             "createTable",
-            state.v1_state.publisher.clone(),
+            &state.v1_state.publisher,
+            t.transaction(),
+        )
+        .await?;
+
+        // Metadata file written, now we can commit the transaction
+        A::on_tabular_created(
+            &request_metadata,
+            warehouse_id,
+            namespace.namespace_id,
+            TableIdentUuid::from(*table_id),
+            state.v1_state.auth.clone(),
         )
-        .await;
+        .await?;
+        if let Err(e) = t.commit().await {
+            undo_tabular_created::<A>(
+                warehouse_id,
+                TableIdentUuid::from(*table_id),
+                state.v1_state.auth,
+            )
+            .await;
+            return Err(e);
+        }
+        state.v1_state.publisher.notify();
         Ok(load_table_result)
     }
 
@@ -403,7 +459,6 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         )
         .await?;
         let previous_table = remove_table(&table_id, &table_ident, &mut previous_table)?;
-        let warehouse = C::get_warehouse(warehouse_id, t.transaction()).await?;
 
         // Contract verification
         state
@@ -415,7 +470,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
         // Apply changes
         let new_metadata = apply_commit(
-            previous_table.table_metadata,
+            previous_table.table_metadata.clone(),
             &previous_table.metadata_location,
             &requirements,
             updates,
@@ -441,10 +496,12 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
         // We don't commit the transaction yet, first we need to write the metadata file.
         let storage_secret =
-            maybe_get_secret(warehouse.storage_secret_id, &state.v1_state.secrets).await?;
+            maybe_get_secret(previous_table.storage_secret_ident, &state.v1_state.secrets).await?;
 
         // Write metadata file
-        let file_io = warehouse.storage_profile.file_io(storage_secret.as_ref())?;
+        let file_io = previous_table
+            .storage_profile
+            .file_io(storage_secret.as_ref())?;
         write_metadata_file(
             &commit.new_metadata_location,
             &commit.new_metadata,
@@ -453,10 +510,16 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         )
         .await?;
 
-        t.commit().await?;
-        emit_change_event(
+        let changes = TableChanges::new(
+            &previous_table.table_metadata,
+            previous_table.metadata_location,
+            &commit.new_metadata,
+            commit.new_metadata_location.to_string(),
+            body,
+        );
+        emit_change_event::<C>(
             EventMetadata {
-                tabular_id: TabularIdentUuid::Table(*previous_table.table_id),
+                entity: EventEntity::Table(*previous_table.table_id),
                 warehouse_id: *warehouse_id,
                 name: table_ident.name,
                 namespace: table_ident.namespace.to_url_string(),
@@ -468,11 +531,14 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
                 sequence_number: 0,
                 trace_id: request_metadata.request_id,
             },
-            body,
+            maybe_body_to_json(&changes),
             "updateTable",
-            state.v1_state.publisher.clone(),
+            &state.v1_state.publisher,
+            t.transaction(),
         )
-        .await;
+        .await?;
+        t.commit().await?;
+        state.v1_state.publisher.notify();
 
         Ok(CommitTableResponse {
             metadata_location: commit.new_metadata_location.to_string(),
@@ -514,7 +580,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             &request_metadata,
             warehouse_id,
             table_id,
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
         .await?;
 
@@ -538,11 +604,9 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .await?
             .into_result()?;
 
-        transaction.commit().await?;
-
-        emit_change_event(
+        emit_change_event::<C>(
             EventMetadata {
-                tabular_id: TabularIdentUuid::Table(*table_id),
+                entity: EventEntity::Table(*table_id),
                 warehouse_id: *warehouse_id,
                 name: table.name,
                 namespace: table.namespace.to_url_string(),
@@ -555,9 +619,20 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             },
             serde_json::Value::Null,
             "dropTable",
-            state.v1_state.publisher,
+            &state.v1_state.publisher,
+            transaction.transaction(),
         )
-        .await;
+        .await?;
+
+        transaction.commit().await?;
+        state.v1_state.publisher.notify();
+
+        A::on_tabular_dropped(warehouse_id, table_id, state.v1_state.auth)
+            .await
+            .map_err(|e| {
+                tracing::warn!("Failed to clean up authorization of table: {:?}", e.error);
+            })
+            .ok();
 
         Ok(())
     }
@@ -643,19 +718,14 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         .flatten();
 
         // We need to be allowed to delete the old table and create the new one
-        let rename_check = A::check_rename_table(
+        A::check_rename_table(
             &request_metadata,
             warehouse_id,
             source_id,
-            state.v1_state.auth.clone(),
-        );
-        let create_check = A::check_create_table(
-            &request_metadata,
-            warehouse_id,
             &destination.namespace,
-            state.v1_state.auth,
-        );
-        futures::try_join!(rename_check, create_check)?;
+            state.v1_state.auth.clone(),
+        )
+        .await?;
 
         // ------------------- BUSINESS LOGIC -------------------
         if source == destination {
@@ -675,6 +745,15 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         })?;
 
         let mut transaction = C::Transaction::begin_write(state.v1_state.catalog).await?;
+        if source.namespace != destination.namespace {
+            require_same_namespace_storage::<C>(
+                warehouse_id,
+                &source.namespace,
+                &destination.namespace,
+                &mut transaction,
+            )
+            .await?;
+        }
         C::rename_table(
             warehouse_id,
             source_id,
@@ -691,11 +770,9 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .await?
             .into_result()?;
 
-        transaction.commit().await?;
-
-        emit_change_event(
+        emit_change_event::<C>(
             EventMetadata {
-                tabular_id: TabularIdentUuid::Table(*source_id),
+                entity: EventEntity::Table(*source_id),
                 warehouse_id: *warehouse_id,
                 name: source.name,
                 namespace: source.namespace.to_url_string(),
@@ -706,9 +783,20 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             },
             body,
             "renameTable",
-            state.v1_state.publisher.clone(),
+            &state.v1_state.publisher,
+            transaction.transaction(),
+        )
+        .await?;
+        commit_tabular_move::<C, A>(
+            warehouse_id,
+            source_id,
+            &source.namespace,
+            &destination.namespace,
+            transaction,
+            state.v1_state.auth,
         )
-        .await;
+        .await?;
+        state.v1_state.publisher.notify();
 
         Ok(())
     }
@@ -798,7 +886,6 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         let table_ids = require_table_ids(table_ids)?;
 
         let mut transaction = C::Transaction::begin_write(state.v1_state.catalog).await?;
-        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await?;
 
         // Store data for events before it is moved
         let mut events = vec![];
@@ -866,6 +953,9 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
                     new_compression_codec,
                     updates: change.updates,
                     previous_metadata: previous_table.table_metadata,
+                    previous_metadata_location: previous_table.metadata_location,
+                    storage_profile: previous_table.storage_profile,
+                    storage_secret_ident: previous_table.storage_secret_ident,
                 })
             })
             .collect::<Result<Vec<_>>>()?;
@@ -893,34 +983,43 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .collect::<Result<Vec<()>, ErrorModel>>()?;
 
         // We don't commit the transaction yet, first we need to write the metadata file.
-        let storage_secret =
-            maybe_get_secret(warehouse.storage_secret_id, &state.v1_state.secrets).await?;
-
-        // Write metadata files
-        let file_io = warehouse.storage_profile.file_io(storage_secret.as_ref())?;
-
+        // Tables may live in namespaces with different storage profiles,
+        // so each table gets its own FileIO.
+        let secrets = &state.v1_state.secrets;
         let write_futures: Vec<_> = commits
             .iter()
-            .map(|commit| {
+            .map(|commit| async move {
+                let storage_secret = maybe_get_secret(commit.storage_secret_ident, secrets).await?;
+                let file_io = commit.storage_profile.file_io(storage_secret.as_ref())?;
                 write_metadata_file(
                     &commit.new_metadata_location,
                     &commit.new_metadata,
                     commit.new_compression_codec,
                     &file_io,
                 )
+                .await?;
+                Ok::<_, IcebergErrorResponse>(())
             })
             .collect();
         futures::future::try_join_all(write_futures).await?;
 
-        transaction.commit().await?;
         let number_of_events = events.len();
-
-        for (event_sequence_number, (body, (table_ident, table_id))) in
-            events.into_iter().zip(event_table_ids).enumerate()
+        for (event_sequence_number, ((body, commit), (table_ident, table_id))) in events
+            .into_iter()
+            .zip(&commits)
+            .zip(event_table_ids)
+            .enumerate()
         {
-            emit_change_event(
+            let changes = TableChanges::new(
+                &commit.previous_metadata,
+                commit.previous_metadata_location.clone(),
+                &commit.new_metadata,
+                commit.new_metadata_location.to_string(),
+                body,
+            );
+            emit_change_event::<C>(
                 EventMetadata {
-                    tabular_id: TabularIdentUuid::Table(*table_id),
+                    entity: EventEntity::Table(*table_id),
                     warehouse_id: *warehouse_id,
                     name: table_ident.name,
                     namespace: table_ident.namespace.to_url_string(),
@@ -932,13 +1031,17 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
                     sequence_number: event_sequence_number,
                     trace_id: request_metadata.request_id,
                 },
-                body,
+                maybe_body_to_json(&changes),
                 "updateTable",
-                state.v1_state.publisher.clone(),
+                &state.v1_state.publisher,
+                transaction.transaction(),
             )
-            .await;
+            .await?;
         }
 
+        transaction.commit().await?;
+        state.v1_state.publisher.notify();
+
         Ok(())
     }
 }
@@ -947,8 +1050,11 @@ struct CommitContext {
     pub new_metadata: iceberg::spec::TableMetadata,
     pub new_metadata_location: Location,
     pub previous_metadata: iceberg::spec::TableMetadata,
+    pub previous_metadata_location: Option<String>,
     pub updates: Vec<TableUpdate>,
     pub new_compression_codec: CompressionCodec,
+    pub storage_profile: StorageProfile,
+    pub storage_secret_ident: Option<SecretIdent>,
 }
 
 impl CommitContext {
@@ -1031,8 +1137,11 @@ pub(super) fn determine_tabular_location(
         );
 
         let namespace_location = match namespace_props.get_location() {
-            Some(location) => location,
-            None => storage_profile
+            // The namespace location might not be covered by the storage profile
+            // if the namespace has a storage profile override. In this case
+            // we use the default location of the override.
+            Some(location) if storage_profile.is_allowed_location(&location) => location,
+            _ => storage_profile
                 .default_namespace_location(namespace.namespace_id)
                 .map_err(|e| {
                     ErrorModel::internal(
@@ -1047,6 +1156,88 @@ pub(super) fn determine_tabular_location(
     }
 }
 
+/// Remove what the authorization hook wrote for a tabular whose creation was not committed.
+pub(crate) async fn undo_tabular_created<A: AuthZHandler>(
+    warehouse_id: WarehouseIdent,
+    tabular_id: TableIdentUuid,
+    auth: A::State,
+) {
+    A::on_tabular_dropped(warehouse_id, tabular_id, auth)
+        .await
+        .map_err(|e| {
+            tracing::warn!("Failed to clean up authorization of tabular: {:?}", e.error);
+        })
+        .ok();
+}
+
+/// Commit the rename of a table or view. If it is moved into another namespace, the
+/// authorization hook runs before the commit and is reverted if the commit fails.
+pub(crate) async fn commit_tabular_move<C: Catalog, A: AuthZHandler>(
+    warehouse_id: WarehouseIdent,
+    tabular_id: TableIdentUuid,
+    source: &NamespaceIdent,
+    destination: &NamespaceIdent,
+    mut transaction: C::Transaction,
+    auth: A::State,
+) -> Result<()> {
+    if source == destination {
+        return transaction.commit().await;
+    }
+
+    let source_id = C::get_namespace(warehouse_id, source, transaction.transaction())
+        .await?
+        .namespace_id;
+    let destination_id = C::get_namespace(warehouse_id, destination, transaction.transaction())
+        .await?
+        .namespace_id;
+    A::on_tabular_moved(
+        warehouse_id,
+        tabular_id,
+        source_id,
+        destination_id,
+        auth.clone(),
+    )
+    .await?;
+    if let Err(e) = transaction.commit().await {
+        A::on_tabular_moved(warehouse_id, tabular_id, destination_id, source_id, auth)
+            .await
+            .map_err(|e| {
+                tracing::warn!(
+                    "Failed to revert authorization of moved tabular: {:?}",
+                    e.error
+                );
+            })
+            .ok();
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Tables keep their location when they are moved across namespaces.
+/// Moving a table into a namespace with a different storage profile would
+/// leave the table unreachable, so we reject such renames.
+async fn require_same_namespace_storage<C: Catalog>(
+    warehouse_id: WarehouseIdent,
+    source: &NamespaceIdent,
+    destination: &NamespaceIdent,
+    transaction: &mut C::Transaction,
+) -> Result<()> {
+    let source = C::get_namespace(warehouse_id, source, transaction.transaction()).await?;
+    let destination =
+        C::get_namespace(warehouse_id, destination, transaction.transaction()).await?;
+
+    if source.storage_override() != destination.storage_override() {
+        return Err(ErrorModel::bad_request(
+            "Tables cannot be moved across namespaces with different storage profiles",
+            "StorageProfileMismatch",
+            None,
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
 fn require_table_ids(
     table_ids: HashMap<TableIdent, Option<TableIdentUuid>>,
 ) -> Result<HashMap<TableIdent, TableIdentUuid>> {
@@ -1123,15 +1314,16 @@ pub(crate) fn require_active_warehouse(status: WarehouseStatus) -> Result<()> {
     Ok(())
 }
 
-async fn emit_change_event(
+async fn emit_change_event<C: Catalog>(
     parameters: EventMetadata,
     body: serde_json::Value,
     operation_id: &str,
-    publisher: CloudEventsPublisher,
-) {
-    let _ = publisher
-        .publish(Uuid::now_v7(), operation_id, body, parameters)
-        .await;
+    publisher: &CloudEventsPublisher,
+    transaction: <C::Transaction as Transaction<C::State>>::Transaction<'_>,
+) -> Result<()> {
+    publisher
+        .publish::<C>(Uuid::now_v7(), operation_id, body, parameters, transaction)
+        .await
 }
 
 // Quick validation of properties for early fails.
@@ -166,15 +166,14 @@ mod test {
     pub(crate) fn get_api_context(
         pool: PgPool,
     ) -> ApiContext<State<AllowAllAuthZHandler, Catalog, SecretsState>> {
-        let (tx, _) = tokio::sync::mpsc::channel(1000);
-
         ApiContext {
             v1_state: State {
                 auth: AllowAllAuthState,
                 catalog: CatalogState::from_pools(pool.clone(), pool.clone()),
-                secrets: SecretsState::from_pools(pool.clone(), pool),
-                publisher: CloudEventsPublisher::new(tx.clone()),
+                secrets: SecretsState::from_pools(pool.clone(), pool).unwrap(),
+                publisher: CloudEventsPublisher::new(),
                 contract_verifiers: ContractVerifiers::new(vec![]),
+                token_verifier: None,
             },
         }
     }
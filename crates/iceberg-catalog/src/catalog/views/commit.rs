@@ -13,9 +13,8 @@ use crate::catalog::tables::{
 use crate::catalog::views::validate_view_updates;
 use crate::request_metadata::RequestMetadata;
 use crate::service::contract_verification::ContractVerification;
-use crate::service::event_publisher::EventMetadata;
+use crate::service::event_publisher::{EventEntity, EventMetadata};
 use crate::service::storage::{StorageLocations as _, StoragePermissions};
-use crate::service::tabular_idents::TabularIdentUuid;
 use crate::service::{
     auth::AuthZHandler, secrets::SecretStore, Catalog, GetWarehouseResponse, State, Transaction,
     ViewMetadataWithLocation,
@@ -327,17 +326,15 @@ pub(crate) async fn commit_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
             StoragePermissions::ReadWriteDelete,
         )
         .await?;
-    transaction.commit().await?;
-
-    let _ = state
+    state
         .v1_state
         .publisher
-        .publish(
+        .publish::<C>(
             Uuid::now_v7(),
             "commitView",
             body,
             EventMetadata {
-                tabular_id: TabularIdentUuid::View(*view_id),
+                entity: EventEntity::View(*view_id),
                 warehouse_id: *warehouse_id,
                 name: parameters.view.name,
                 namespace: parameters.view.namespace.to_url_string(),
@@ -349,8 +346,11 @@ pub(crate) async fn commit_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
                 sequence_number: 0,
                 trace_id: request_metadata.request_id,
             },
+            transaction.transaction(),
         )
-        .await;
+        .await?;
+    transaction.commit().await?;
+    state.v1_state.publisher.notify();
 
     Ok(LoadViewResult {
         metadata_location: metadata_location.to_string(),
@@ -4,18 +4,18 @@ use crate::api::ApiContext;
 use crate::catalog::compression_codec::CompressionCodec;
 use crate::catalog::io::write_metadata_file;
 use crate::catalog::tables::{
-    determine_tabular_location, maybe_body_to_json, require_active_warehouse,
+    determine_tabular_location, maybe_body_to_json, require_active_warehouse, undo_tabular_created,
     validate_table_or_view_ident,
 };
 use crate::catalog::views::validate_view_properties;
 use crate::catalog::{maybe_get_secret, require_warehouse_id};
 use crate::request_metadata::RequestMetadata;
 use crate::service::auth::AuthZHandler;
-use crate::service::event_publisher::EventMetadata;
+use crate::service::event_publisher::{EventEntity, EventMetadata};
 use crate::service::storage::{StorageLocations as _, StoragePermissions};
 use crate::service::tabular_idents::TabularIdentUuid;
 use crate::service::Result;
-use crate::service::{Catalog, SecretStore, State, Transaction};
+use crate::service::{Catalog, SecretStore, State, TableIdentUuid, Transaction};
 use http::StatusCode;
 use iceberg::spec::ViewMetadataBuilder;
 use iceberg::{TableIdent, ViewCreation};
@@ -153,17 +153,15 @@ pub(crate) async fn create_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
         )
         .await?;
 
-    t.commit().await?;
-
-    let _ = state
+    state
         .v1_state
         .publisher
-        .publish(
+        .publish::<C>(
             Uuid::now_v7(),
             "createView",
             body,
             EventMetadata {
-                tabular_id: TabularIdentUuid::View(*view_id),
+                entity: EventEntity::View(*view_id),
                 warehouse_id: *warehouse_id.as_uuid(),
                 name: view.name,
                 namespace: view.namespace.to_url_string(),
@@ -172,8 +170,27 @@ pub(crate) async fn create_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
                 sequence_number: 0,
                 trace_id: request_metadata.request_id,
             },
+            t.transaction(),
+        )
+        .await?;
+    A::on_tabular_created(
+        &request_metadata,
+        warehouse_id,
+        namespace_id,
+        TableIdentUuid::from(*view_id),
+        state.v1_state.auth.clone(),
+    )
+    .await?;
+    if let Err(e) = t.commit().await {
+        undo_tabular_created::<A>(
+            warehouse_id,
+            TableIdentUuid::from(*view_id),
+            state.v1_state.auth,
         )
         .await;
+        return Err(e);
+    }
+    state.v1_state.publisher.notify();
 
     let load_view_result = LoadViewResult {
         metadata_location: metadata_location.to_string(),
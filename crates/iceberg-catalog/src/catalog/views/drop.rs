@@ -6,7 +6,7 @@ use crate::catalog::tables::validate_table_or_view_ident;
 use crate::request_metadata::RequestMetadata;
 use crate::service::auth::AuthZHandler;
 use crate::service::contract_verification::ContractVerification;
-use crate::service::event_publisher::EventMetadata;
+use crate::service::event_publisher::{EventEntity, EventMetadata};
 use crate::service::tabular_idents::TabularIdentUuid;
 use crate::service::Result;
 use crate::service::{Catalog, SecretStore, State, Transaction};
@@ -34,7 +34,7 @@ pub(crate) async fn drop_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
         &request_metadata,
         warehouse_id,
         view_id.as_ref().and_then(|id| id.as_ref().ok()),
-        state.v1_state.auth,
+        state.v1_state.auth.clone(),
     )
     .await?;
 
@@ -60,17 +60,15 @@ pub(crate) async fn drop_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
     C::drop_view(view_id, transaction.transaction()).await?;
 
     // TODO: Delete metadata files
-    transaction.commit().await?;
-
-    let _ = state
+    state
         .v1_state
         .publisher
-        .publish(
+        .publish::<C>(
             Uuid::now_v7(),
             "dropView",
             serde_json::Value::Null,
             EventMetadata {
-                tabular_id: TabularIdentUuid::View(*view_id),
+                entity: EventEntity::View(*view_id),
                 warehouse_id: *warehouse_id,
                 name: view.name.clone(),
                 namespace: view.namespace.to_url_string(),
@@ -79,8 +77,18 @@ pub(crate) async fn drop_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
                 sequence_number: 0,
                 trace_id: request_metadata.request_id,
             },
+            transaction.transaction(),
         )
-        .await;
+        .await?;
+    transaction.commit().await?;
+    state.v1_state.publisher.notify();
+
+    A::on_tabular_dropped(warehouse_id, view_id, state.v1_state.auth)
+        .await
+        .map_err(|e| {
+            tracing::warn!("Failed to clean up authorization of view: {:?}", e.error);
+        })
+        .ok();
 
     Ok(())
 }
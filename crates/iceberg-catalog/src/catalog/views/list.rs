@@ -1,11 +1,13 @@
 use crate::api::iceberg::v1::{NamespaceParameters, PaginationQuery};
 use crate::api::ApiContext;
-use crate::api::Result;
+use crate::api::{IcebergErrorResponse, Result};
 use crate::catalog::namespace::validate_namespace_ident;
-use crate::catalog::require_warehouse_id;
+use crate::catalog::{fetch_filtered_page, require_warehouse_id};
 use crate::request_metadata::RequestMetadata;
 use crate::service::auth::AuthZHandler;
-use crate::service::{Catalog, SecretStore, State};
+use crate::service::tabular_idents::TabularIdentUuid;
+use crate::service::{Catalog, SecretStore, State, TableIdentUuid};
+use iceberg::TableIdent;
 use iceberg_ext::catalog::rest::ListTablesResponse;
 
 pub(crate) async fn list_views<C: Catalog, A: AuthZHandler, S: SecretStore>(
@@ -24,23 +26,50 @@ pub(crate) async fn list_views<C: Catalog, A: AuthZHandler, S: SecretStore>(
         &request_metadata,
         warehouse_id,
         &namespace,
-        state.v1_state.auth,
+        state.v1_state.auth.clone(),
     )
     .await?;
 
     // ------------------- BUSINESS LOGIC -------------------
-
-    let views = C::list_views(
-        warehouse_id,
-        &namespace,
-        false,
-        state.v1_state.catalog.clone(),
+    let namespace = &namespace;
+    let request_metadata = &request_metadata;
+    let (views, next_page_token) = fetch_filtered_page(
         pagination_query,
+        |query| {
+            let catalog = state.v1_state.catalog.clone();
+            async move {
+                let views = C::list_views(warehouse_id, namespace, false, catalog, query).await?;
+                let next_page_token = views.next_page_token.clone();
+                Ok::<_, IcebergErrorResponse>((views.into_iter().collect(), next_page_token))
+            }
+        },
+        |views: Vec<(TableIdentUuid, TableIdent)>| {
+            let auth = state.v1_state.auth.clone();
+            async move {
+                let visible = A::filter_visible_tabulars(
+                    request_metadata,
+                    warehouse_id,
+                    namespace,
+                    views
+                        .iter()
+                        .map(|(id, _)| TabularIdentUuid::View(**id))
+                        .collect(),
+                    auth,
+                )
+                .await?;
+                Ok::<_, IcebergErrorResponse>(
+                    views
+                        .into_iter()
+                        .filter(|(id, _)| visible.contains(&TabularIdentUuid::View(**id)))
+                        .collect(),
+                )
+            }
+        },
     )
     .await?;
 
     Ok(ListTablesResponse {
-        next_page_token: None,
+        next_page_token,
         identifiers: views.into_iter().map(|t| t.1).collect(),
     })
 }
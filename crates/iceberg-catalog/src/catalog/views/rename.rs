@@ -1,11 +1,13 @@
 use crate::api::iceberg::types::Prefix;
 use crate::api::ApiContext;
 use crate::catalog::require_warehouse_id;
-use crate::catalog::tables::{maybe_body_to_json, validate_table_or_view_ident};
+use crate::catalog::tables::{
+    commit_tabular_move, maybe_body_to_json, validate_table_or_view_ident,
+};
 use crate::request_metadata::RequestMetadata;
 use crate::service::auth::AuthZHandler;
 use crate::service::contract_verification::ContractVerification;
-use crate::service::event_publisher::EventMetadata;
+use crate::service::event_publisher::{EventEntity, EventMetadata};
 use crate::service::tabular_idents::TabularIdentUuid;
 use crate::service::Result;
 use crate::service::{Catalog, SecretStore, State, Transaction};
@@ -39,19 +41,14 @@ pub(crate) async fn rename_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
     .transpose();
 
     // We need to be allowed to delete the old table and create the new one
-    let rename_check = A::check_rename_view(
+    A::check_rename_view(
         &request_metadata,
         warehouse_id,
         source_id.as_ref().and_then(|id| id.as_ref().ok()),
-        state.v1_state.auth.clone(),
-    );
-    let create_check = A::check_create_view(
-        &request_metadata,
-        warehouse_id,
         &destination.namespace,
-        state.v1_state.auth,
-    );
-    futures::try_join!(rename_check, create_check)?;
+        state.v1_state.auth.clone(),
+    )
+    .await?;
 
     // ------------------- BUSINESS LOGIC -------------------
     if source == destination {
@@ -86,27 +83,36 @@ pub(crate) async fn rename_view<C: Catalog, A: AuthZHandler, S: SecretStore>(
         .await?
         .into_result()?;
 
-    transaction.commit().await?;
-
-    let _ = state
+    state
         .v1_state
         .publisher
-        .publish(
+        .publish::<C>(
             Uuid::now_v7(),
             "renameView",
             body,
             EventMetadata {
-                tabular_id: TabularIdentUuid::View(*source_id),
+                entity: EventEntity::View(*source_id),
                 warehouse_id: *warehouse_id.as_uuid(),
-                name: request.source.name,
-                namespace: request.source.namespace.to_url_string(),
+                name: source.name.clone(),
+                namespace: source.namespace.to_url_string(),
                 prefix: prefix.map(Prefix::into_string).unwrap_or_default(),
                 num_events: 1,
                 sequence_number: 0,
                 trace_id: request_metadata.request_id,
             },
+            transaction.transaction(),
         )
-        .await;
+        .await?;
+    commit_tabular_move::<C, A>(
+        warehouse_id,
+        source_id,
+        &source.namespace,
+        &destination.namespace,
+        transaction,
+        state.v1_state.auth,
+    )
+    .await?;
+    state.v1_state.publisher.notify();
 
     Ok(())
 }
@@ -1,6 +1,6 @@
 //! Contains Configuration of the service Module
 use anyhow::anyhow;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
@@ -15,6 +15,8 @@ use crate::WarehouseIdent;
 
 const DEFAULT_RESERVED_NAMESPACES: [&str; 2] = ["system", "examples"];
 const DEFAULT_ENCRYPTION_KEY: &str = "<This is unsafe, please set a proper key>";
+/// Key ID of `pg_encryption_key` in the Postgres secret keyring.
+pub(crate) const DEFAULT_ENCRYPTION_KEY_ID: &str = "default";
 
 lazy_static::lazy_static! {
     /// Configuration of the service module.
@@ -74,6 +76,12 @@ pub struct DynAppConfig {
     // ------------- POSTGRES IMPLEMENTATION -------------
     #[redact]
     pub(crate) pg_encryption_key: String,
+    /// Additional keys to decrypt secrets, indexed by key ID.
+    /// `pg_encryption_key` is always available as key ID `default`.
+    #[redact]
+    pub(crate) pg_encryption_keys: HashMap<String, String>,
+    /// ID of the key used to encrypt secrets.
+    pub pg_encryption_key_id: String,
     pub(crate) pg_database_url_read: Option<String>,
     pub(crate) pg_database_url_write: Option<String>,
     pub(crate) pg_host_r: Option<String>,
@@ -101,8 +109,38 @@ pub struct DynAppConfig {
     #[redact]
     pub nats_token: Option<String>,
 
+    // ------------- KAFKA CLOUDEVENTS -------------
+    /// Publish events to Kafka. Requires the `kafka` feature.
+    pub kafka: Option<KafkaConfig>,
+
+    // ------------- WEBHOOK CLOUDEVENTS -------------
+    /// HTTP endpoints events are posted to, indexed by name.
+    pub webhooks: HashMap<String, WebhookConfig>,
+
+    // ------------- EVENT OUTBOX -------------
+    /// Interval in which the outbox is checked for events that are due for publishing.
+    pub event_outbox_poll_interval_ms: u64,
+    /// Maximum delay between retries of an event that could not be published.
+    pub event_outbox_max_backoff_seconds: u64,
+    /// Time published events are kept in the outbox.
+    pub event_outbox_retention_seconds: u64,
+    /// Number of attempts after which publishing an event is given up.
+    pub event_outbox_max_attempts: i32,
+    /// Time a relay may take to publish the events it claimed. Afterwards they
+    /// are claimed again.
+    pub event_outbox_claim_timeout_seconds: u64,
+
     // ------------- AUTHORIZATION -------------
     pub openid_provider_uri: Option<Url>,
+    /// Additional trusted OpenID providers, indexed by name.
+    /// Tokens are verified by the provider matching their `iss` claim.
+    pub openid_providers: HashMap<String, OpenIdProviderConfig>,
+    /// Verify opaque tokens via introspection. JWTs are never introspected.
+    pub token_introspection: Option<TokenIntrospectionConfig>,
+    /// Issue catalog-signed warehouse-scoped tokens. Requires an OpenID provider.
+    pub token_issuer: Option<TokenIssuerConfig>,
+    /// Verify Kubernetes service account tokens via the `TokenReview` API.
+    pub kubernetes_auth: Option<KubernetesAuthConfig>,
 
     // ------------- Health -------------
     pub health_check_frequency_seconds: u64,
@@ -110,8 +148,19 @@ pub struct DynAppConfig {
 
     // ------------- KV2 -------------
     pub kv2: Option<KV2Config>,
+    // ------------- FILE IMPLEMENTATION -------------
+    pub file_secrets: Option<FileSecretsConfig>,
     // ------------- Secrets -------------
     pub secret_backend: SecretBackend,
+    /// Time in seconds secrets are cached in memory. Set to 0 to disable caching.
+    pub secret_cache_ttl_seconds: u64,
+    /// Maximum number of secrets cached in memory.
+    pub secret_cache_max_entries: u64,
+    // ------------- Authorization -------------
+    pub authz_backend: AuthZBackend,
+    pub rbac: RbacConfig,
+    pub openfga: Option<OpenFGAConfig>,
+    pub cedar: Option<CedarConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -120,17 +169,416 @@ pub enum SecretBackend {
     KV2,
     #[serde(alias = "postgres")]
     Postgres,
+    #[serde(alias = "file")]
+    File,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileSecretsConfig {
+    /// Directory containing one `<secret-id>.json` file per secret.
+    pub directory: PathBuf,
+    /// Whether the catalog may create, update and delete secrets.
+    /// Disable for read-only replicas and mounted secrets.
+    #[serde(default)]
+    pub allow_writes: bool,
+    #[serde(default = "default_file_secrets_reload_interval")]
+    pub reload_interval_seconds: u64,
+}
+
+fn default_file_secrets_reload_interval() -> u64 {
+    10
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AuthZBackend {
+    #[serde(alias = "allow-all", alias = "allowall")]
+    AllowAll,
+    #[serde(alias = "postgres")]
+    Postgres,
+    #[serde(alias = "openfga")]
+    OpenFGA,
+    #[serde(alias = "cedar")]
+    Cedar,
+}
+
+/// Configuration of the role based `AuthZHandler` stored in Postgres.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RbacConfig {
+    /// Claim of the token that contains the groups of the user.
+    #[serde(default = "default_rbac_groups_claim")]
+    pub groups_claim: String,
+    /// Principals, formatted as `<issuer>|<subject>`, that are allowed everything,
+    /// including managing roles.
+    #[serde(default)]
+    pub admin_subjects: Vec<String>,
+    /// Groups whose members are allowed everything, including managing roles.
+    #[serde(default)]
+    pub admin_groups: Vec<String>,
+}
+
+impl Default for RbacConfig {
+    fn default() -> Self {
+        Self {
+            groups_claim: default_rbac_groups_claim(),
+            admin_subjects: vec![],
+            admin_groups: vec![],
+        }
+    }
+}
+
+fn default_rbac_groups_claim() -> String {
+    "groups".to_string()
+}
+
+/// Configuration of the `AuthZHandler` backed by an OpenFGA store.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Redact)]
+pub struct OpenFGAConfig {
+    /// Base URL of the OpenFGA HTTP API, i.e. `http://localhost:8080`.
+    pub endpoint: Url,
+    pub store_id: String,
+    /// Authorization model to use. Defaults to the latest model of the store.
+    pub authorization_model_id: Option<String>,
+    /// Pre-shared key sent as bearer token.
+    #[redact]
+    pub api_token: Option<String>,
+    /// Claim of the token that contains the groups of the user.
+    #[serde(default = "default_rbac_groups_claim")]
+    pub groups_claim: String,
+}
+
+/// An OpenID provider whose tokens are accepted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OpenIdProviderConfig {
+    /// URL of the provider, must serve `.well-known/openid-configuration`.
+    pub provider_uri: Url,
+    /// Accepted values of the `aud` claim. If empty, the audience is not validated.
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    /// Tolerated clock difference when validating `exp` and `nbf`.
+    #[serde(default = "default_clock_skew")]
+    pub clock_skew_seconds: u64,
+    /// Claim identifying the subject, copied to `sub`.
+    #[serde(default = "default_subject_claim")]
+    pub subject_claim: String,
+    /// Claim containing the groups of the subject, copied to `groups`.
+    #[serde(default)]
+    pub groups_claim: Option<String>,
+    /// Claim containing the project of the subject, copied to `project_id`.
+    #[serde(default)]
+    pub project_claim: Option<String>,
+}
+
+impl OpenIdProviderConfig {
+    #[must_use]
+    pub fn new(provider_uri: Url) -> Self {
+        Self {
+            provider_uri,
+            audiences: vec![],
+            clock_skew_seconds: default_clock_skew(),
+            subject_claim: default_subject_claim(),
+            groups_claim: None,
+            project_claim: None,
+        }
+    }
+}
+
+fn default_clock_skew() -> u64 {
+    60
+}
+
+fn default_subject_claim() -> String {
+    "sub".to_string()
+}
+
+/// Configuration of OAuth 2.0 token introspection (RFC 7662) for opaque tokens.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Redact)]
+pub struct TokenIntrospectionConfig {
+    /// Introspection endpoint of the provider.
+    pub endpoint: Url,
+    /// Credentials of the catalog at the introspection endpoint.
+    pub client_id: String,
+    #[redact]
+    pub client_secret: String,
+    /// Maximum time an introspection result is cached. Results are never cached
+    /// beyond the expiry of the token.
+    #[serde(default = "default_introspection_cache_ttl")]
+    pub cache_ttl_seconds: u64,
+    #[serde(default = "default_introspection_cache_max_entries")]
+    pub cache_max_entries: u64,
+}
+
+fn default_introspection_cache_ttl() -> u64 {
+    300
+}
+
+fn default_introspection_cache_max_entries() -> u64 {
+    10000
+}
+
+/// Configuration of the Kafka producer publishing cloud events.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Redact)]
+pub struct KafkaConfig {
+    /// Comma separated list of `host:port` bootstrap brokers.
+    pub brokers: String,
+    pub topic: String,
+    #[serde(default)]
+    pub acks: KafkaAcks,
+    /// Defaults to `plaintext`.
+    pub security_protocol: Option<KafkaSecurityProtocol>,
+    /// `PLAIN`, `SCRAM-SHA-256` or `SCRAM-SHA-512`.
+    pub sasl_mechanism: Option<String>,
+    pub sasl_username: Option<String>,
+    #[redact]
+    pub sasl_password: Option<String>,
+    /// CA certificate to verify the brokers. If not set, the system roots are used.
+    pub ssl_ca_location: Option<PathBuf>,
+    /// Client certificate and key for mutual TLS.
+    pub ssl_certificate_location: Option<PathBuf>,
+    pub ssl_key_location: Option<PathBuf>,
+    #[redact]
+    pub ssl_key_password: Option<String>,
+    /// Time an event may take to be delivered, including retries.
+    #[serde(default = "default_kafka_delivery_timeout")]
+    pub delivery_timeout_ms: u64,
+    /// Additional librdkafka producer properties, for example `compression.type`.
+    /// As `.` separates nested keys in environment variables, `_` in keys is
+    /// replaced by `.`. They take precedence over the settings above.
+    #[serde(default)]
+    #[redact]
+    pub properties: HashMap<String, String>,
+}
+
+fn default_kafka_delivery_timeout() -> u64 {
+    30000
+}
+
+/// Acknowledgements the producer requires from the brokers.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KafkaAcks {
+    /// Don't wait for the brokers.
+    None,
+    /// Wait for the partition leader.
+    Leader,
+    /// Wait for all in-sync replicas.
+    #[default]
+    All,
+}
+
+impl KafkaAcks {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KafkaAcks::None => "0",
+            KafkaAcks::Leader => "1",
+            KafkaAcks::All => "all",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KafkaSecurityProtocol {
+    Plaintext,
+    Ssl,
+    SaslPlaintext,
+    SaslSsl,
+}
+
+impl KafkaSecurityProtocol {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KafkaSecurityProtocol::Plaintext => "plaintext",
+            KafkaSecurityProtocol::Ssl => "ssl",
+            KafkaSecurityProtocol::SaslPlaintext => "sasl_plaintext",
+            KafkaSecurityProtocol::SaslSsl => "sasl_ssl",
+        }
+    }
+}
+
+/// An HTTP endpoint events are posted to.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Redact)]
+pub struct WebhookConfig {
+    pub url: Url,
+    /// Secret used to sign requests with HMAC-SHA256. If not set, requests are not signed.
+    #[redact]
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Types of the events sent to this endpoint, for example `updateTable`.
+    /// If empty, all events are sent.
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// Timeout of a single request.
+    #[serde(default = "default_webhook_timeout")]
+    pub timeout_ms: u64,
+}
+
+fn default_webhook_timeout() -> u64 {
+    5000
+}
+
+/// Configuration of the authenticator for Kubernetes service account tokens.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KubernetesAuthConfig {
+    /// URL of the Kubernetes API server, usually `https://kubernetes.default.svc`.
+    pub api_url: Url,
+    /// Token of the catalog's own service account, used to call the `TokenReview` API.
+    /// The service account requires the `system:auth-delegator` cluster role.
+    #[serde(default = "default_kubernetes_token_path")]
+    pub token_path: PathBuf,
+    /// CA certificate of the API server. If not set, the system roots are used.
+    #[serde(default = "default_kubernetes_ca_cert_path")]
+    pub ca_cert_path: Option<PathBuf>,
+    /// Audiences the tokens must be issued for. If empty, the audience of the
+    /// API server is required.
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    /// Maximum time a review result is cached. Results are never cached
+    /// beyond the expiry of the token.
+    #[serde(default = "default_introspection_cache_ttl")]
+    pub cache_ttl_seconds: u64,
+    #[serde(default = "default_introspection_cache_max_entries")]
+    pub cache_max_entries: u64,
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn default_kubernetes_ca_cert_path() -> Option<PathBuf> {
+    Some(PathBuf::from(
+        "/var/run/secrets/kubernetes.io/serviceaccount/ca.crt",
+    ))
+}
+
+fn default_kubernetes_token_path() -> PathBuf {
+    PathBuf::from("/var/run/secrets/kubernetes.io/serviceaccount/token")
+}
+
+/// Configuration of the catalog's own token issuer.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Redact)]
+pub struct TokenIssuerConfig {
+    /// Secret the signing keys are derived from. Must be the same on all replicas.
+    #[redact]
+    pub signing_secret: String,
+    /// Lifetime of issued tokens.
+    #[serde(default = "default_token_ttl")]
+    pub ttl_seconds: u64,
+    /// Interval after which a new signing key is used.
+    #[serde(default = "default_token_key_rotation")]
+    pub key_rotation_seconds: u64,
+}
+
+fn default_token_ttl() -> u64 {
+    3600
+}
+
+fn default_token_key_rotation() -> u64 {
+    86400
+}
+
+/// Configuration of the `AuthZHandler` evaluating Cedar policies in-process.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CedarConfig {
+    /// File containing the Cedar policies. Changes are picked up without restart.
+    pub policy_file: PathBuf,
+    #[serde(default = "default_cedar_reload_interval")]
+    pub reload_interval_seconds: u64,
+    /// Claim of the token that contains the groups of the user.
+    #[serde(default = "default_rbac_groups_claim")]
+    pub groups_claim: String,
+}
+
+fn default_cedar_reload_interval() -> u64 {
+    10
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Redact)]
 pub struct KV2Config {
     pub url: Url,
-    pub user: String,
+    /// Shorthand for `auth` with `type = "userpass"`.
+    #[serde(default)]
+    pub user: Option<String>,
     #[redact]
-    pub password: String,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub auth: Option<KV2Auth>,
     pub secret_mount: String,
 }
 
+impl KV2Config {
+    /// The configured authentication method. Falls back to `userpass` if only
+    /// `user` and `password` are set.
+    ///
+    /// # Errors
+    /// Fails if neither `auth` nor `user` and `password` are set.
+    pub fn auth_method(&self) -> anyhow::Result<KV2Auth> {
+        match (&self.auth, &self.user, &self.password) {
+            (Some(auth), None, None) => Ok(auth.clone()),
+            (Some(_), _, _) => Err(anyhow!(
+                "KV2 'auth' must not be combined with 'user' and 'password'"
+            )),
+            (None, Some(user), Some(password)) => Ok(KV2Auth::Userpass {
+                user: user.clone(),
+                password: password.clone(),
+                mount: default_userpass_mount(),
+            }),
+            (None, _, _) => Err(anyhow!(
+                "KV2 requires either 'auth' or both 'user' and 'password'"
+            )),
+        }
+    }
+}
+
+/// Method to authenticate against Vault.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Redact)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum KV2Auth {
+    Userpass {
+        user: String,
+        #[redact]
+        password: String,
+        #[serde(default = "default_userpass_mount")]
+        mount: String,
+    },
+    /// Login with the service account token of the pod.
+    Kubernetes {
+        role: String,
+        #[serde(default = "default_kubernetes_jwt_path")]
+        jwt_path: PathBuf,
+        #[serde(default = "default_kubernetes_mount")]
+        mount: String,
+    },
+    #[serde(rename = "approle")]
+    AppRole {
+        role_id: String,
+        #[redact]
+        secret_id: String,
+        #[serde(default = "default_approle_mount")]
+        mount: String,
+    },
+    /// A pre-issued token. It is renewed if renewable, but can't be re-issued.
+    Token {
+        #[redact]
+        token: String,
+    },
+}
+
+fn default_userpass_mount() -> String {
+    "userpass".to_string()
+}
+
+fn default_kubernetes_mount() -> String {
+    "kubernetes".to_string()
+}
+
+fn default_kubernetes_jwt_path() -> PathBuf {
+    PathBuf::from("/var/run/secrets/kubernetes.io/serviceaccount/token")
+}
+
+fn default_approle_mount() -> String {
+    "approle".to_string()
+}
+
 impl Default for DynAppConfig {
     fn default() -> Self {
         Self {
@@ -143,6 +591,8 @@ impl Default for DynAppConfig {
                 "examples".to_string(),
             ])),
             pg_encryption_key: DEFAULT_ENCRYPTION_KEY.to_string(),
+            pg_encryption_keys: HashMap::new(),
+            pg_encryption_key_id: DEFAULT_ENCRYPTION_KEY_ID.to_string(),
             pg_database_url_read: None,
             pg_database_url_write: None,
             pg_host_r: None,
@@ -164,12 +614,30 @@ impl Default for DynAppConfig {
             nats_user: None,
             nats_password: None,
             nats_token: None,
+            kafka: None,
+            webhooks: HashMap::new(),
+            event_outbox_poll_interval_ms: 1000,
+            event_outbox_max_backoff_seconds: 300,
+            event_outbox_retention_seconds: 86400,
+            event_outbox_max_attempts: 20,
+            event_outbox_claim_timeout_seconds: 300,
             openid_provider_uri: None,
+            openid_providers: HashMap::new(),
+            token_introspection: None,
+            token_issuer: None,
+            kubernetes_auth: None,
             listen_port: 8080,
             health_check_frequency_seconds: 10,
             health_check_jitter_millis: 500,
             kv2: None,
+            file_secrets: None,
             secret_backend: SecretBackend::Postgres,
+            secret_cache_ttl_seconds: 60,
+            secret_cache_max_entries: 1000,
+            authz_backend: AuthZBackend::AllowAll,
+            rbac: RbacConfig::default(),
+            openfga: None,
+            cedar: None,
         }
     }
 }
@@ -193,6 +661,40 @@ impl DynAppConfig {
         self.prefix_template
             .replace("{warehouse_id}", warehouse_id.to_string().as_str())
     }
+
+    /// All trusted OpenID providers. `openid_provider_uri` comes first, followed
+    /// by `openid_providers` ordered by name.
+    #[must_use]
+    pub fn openid_providers(&self) -> Vec<OpenIdProviderConfig> {
+        self.openid_provider_uri
+            .clone()
+            .map(OpenIdProviderConfig::new)
+            .into_iter()
+            .chain(
+                self.openid_providers
+                    .iter()
+                    .sorted_by_key(|(name, _)| *name)
+                    .map(|(_, provider)| provider.clone()),
+            )
+            .collect()
+    }
+
+    /// Whether requests must be authenticated.
+    #[must_use]
+    pub fn authentication_enabled(&self) -> bool {
+        self.openid_provider_uri.is_some()
+            || !self.openid_providers.is_empty()
+            || self.token_introspection.is_some()
+            || self.kubernetes_auth.is_some()
+    }
+
+    /// All keys that can decrypt postgres secrets, indexed by key ID.
+    pub(crate) fn pg_encryption_keyring(&self) -> HashMap<String, String> {
+        let mut keys = self.pg_encryption_keys.clone();
+        keys.entry(DEFAULT_ENCRYPTION_KEY_ID.to_string())
+            .or_insert_with(|| self.pg_encryption_key.clone());
+        keys
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -294,4 +796,127 @@ mod test {
         assert!(CONFIG.reserved_namespaces.contains("system"));
         assert!(CONFIG.reserved_namespaces.contains("examples"));
     }
+
+    #[test]
+    fn test_kv2_auth_method() {
+        let config: KV2Config = serde_json::from_value(serde_json::json!({
+            "url": "http://localhost:8200",
+            "user": "test",
+            "password": "test",
+            "secret_mount": "secret"
+        }))
+        .unwrap();
+        assert!(matches!(
+            config.auth_method().unwrap(),
+            KV2Auth::Userpass { user, mount, .. } if user == "test" && mount == "userpass"
+        ));
+
+        let config: KV2Config = serde_json::from_value(serde_json::json!({
+            "url": "http://localhost:8200",
+            "auth": {"type": "kubernetes", "role": "iceberg"},
+            "secret_mount": "secret"
+        }))
+        .unwrap();
+        assert!(matches!(
+            config.auth_method().unwrap(),
+            KV2Auth::Kubernetes { role, jwt_path, mount }
+                if role == "iceberg"
+                    && jwt_path == default_kubernetes_jwt_path()
+                    && mount == "kubernetes"
+        ));
+
+        let config: KV2Config = serde_json::from_value(serde_json::json!({
+            "url": "http://localhost:8200",
+            "auth": {"type": "approle", "role_id": "role", "secret_id": "secret"},
+            "user": "test",
+            "password": "test",
+            "secret_mount": "secret"
+        }))
+        .unwrap();
+        assert!(config.auth_method().is_err());
+    }
+
+    #[test]
+    fn test_kafka_config() {
+        let config: KafkaConfig = serde_json::from_value(serde_json::json!({
+            "brokers": "localhost:9092",
+            "topic": "iceberg",
+        }))
+        .unwrap();
+        assert_eq!(config.acks, KafkaAcks::All);
+        assert_eq!(config.delivery_timeout_ms, 30000);
+
+        let config: KafkaConfig = serde_json::from_value(serde_json::json!({
+            "brokers": "localhost:9092",
+            "topic": "iceberg",
+            "acks": "leader",
+            "security_protocol": "sasl_ssl",
+        }))
+        .unwrap();
+        assert_eq!(config.acks, KafkaAcks::Leader);
+        assert_eq!(
+            config.security_protocol,
+            Some(KafkaSecurityProtocol::SaslSsl)
+        );
+    }
+
+    #[test]
+    fn test_webhook_config() {
+        let config: WebhookConfig = serde_json::from_value(serde_json::json!({
+            "url": "https://example.com/hooks/iceberg",
+        }))
+        .unwrap();
+        assert!(config.secret.is_none());
+        assert!(config.event_types.is_empty());
+        assert_eq!(config.timeout_ms, 5000);
+        assert_eq!(config.max_retries, 5);
+
+        let config: WebhookConfig = serde_json::from_value(serde_json::json!({
+            "url": "https://example.com/hooks/iceberg",
+            "secret": "my-secret",
+            "event_types": ["updateTable", "dropTable"],
+        }))
+        .unwrap();
+        assert_eq!(config.event_types, vec!["updateTable", "dropTable"]);
+        assert!(!format!("{config:?}").contains("my-secret"));
+    }
+
+    #[test]
+    fn test_openid_providers() {
+        let mut config = DynAppConfig {
+            openid_provider_uri: Some("https://legacy.example.com".parse().unwrap()),
+            ..DynAppConfig::default()
+        };
+        for (name, uri) in [
+            ("keycloak", "https://keycloak.example.com"),
+            ("entra", "https://entra.example.com"),
+        ] {
+            config.openid_providers.insert(
+                name.to_string(),
+                serde_json::from_value(serde_json::json!({
+                    "provider_uri": uri,
+                    "audiences": ["catalog"],
+                }))
+                .unwrap(),
+            );
+        }
+
+        let providers = config.openid_providers();
+        let uris = providers
+            .iter()
+            .map(|p| p.provider_uri.host_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            uris,
+            vec![
+                "legacy.example.com",
+                "entra.example.com",
+                "keycloak.example.com"
+            ]
+        );
+        assert!(providers[0].audiences.is_empty());
+        assert_eq!(providers[1].audiences, vec!["catalog".to_string()]);
+        assert_eq!(providers[1].clock_skew_seconds, 60);
+        assert_eq!(providers[1].subject_claim, "sub");
+    }
 }
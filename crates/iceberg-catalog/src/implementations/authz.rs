@@ -32,14 +32,20 @@ pub struct AllowAllAuthZHandler;
 impl AuthConfigHandler<AllowAllAuthZHandler> for AllowAllAuthZHandler {
     async fn get_and_validate_user_warehouse(
         _: AllowAllAuthState,
-        _: &RequestMetadata,
+        metadata: &RequestMetadata,
     ) -> Result<UserWarehouse> {
+        if let Some(user_warehouse) = UserWarehouse::from_catalog_token(metadata) {
+            return Ok(user_warehouse);
+        }
         // The AuthHandler should return the user's project or warehouse if this
         // information is available. Otherwise return "None".
         // This requires the user to specify the project as part of the "warehouse" provided to the GET /config
         // endpoint.
         Ok(UserWarehouse {
-            project_id: Some(ProjectIdent::from(DEFAULT_PROJECT_ID)),
+            project_id: Some(
+                UserWarehouse::project_from_token(metadata)
+                    .unwrap_or(ProjectIdent::from(DEFAULT_PROJECT_ID)),
+            ),
             warehouse_id: None,
         })
     }
@@ -152,6 +158,7 @@ impl AuthZHandler for AllowAllAuthZHandler {
         _: &RequestMetadata,
         _: WarehouseIdent,
         _: Option<TableIdentUuid>,
+        _: &NamespaceIdent,
         _: AllowAllAuthState,
     ) -> Result<()> {
         Ok(())
@@ -197,6 +204,47 @@ impl AuthZHandler for AllowAllAuthZHandler {
     }
 
     // ---------------- Management API ----------------
+    async fn check_create_project(
+        _: &RequestMetadata,
+        _: ProjectIdent,
+        _: bool,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_rename_project(
+        _: &RequestMetadata,
+        _: ProjectIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_delete_project(
+        _: &RequestMetadata,
+        _: ProjectIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_deactivate_project(
+        _: &RequestMetadata,
+        _: ProjectIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_activate_project(
+        _: &RequestMetadata,
+        _: ProjectIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     async fn check_create_warehouse(
         _: &RequestMetadata,
         _: &ProjectIdent,
@@ -333,6 +381,7 @@ impl AuthZHandler for AllowAllAuthZHandler {
         _: &RequestMetadata,
         _: WarehouseIdent,
         _: Option<&TableIdentUuid>,
+        _: &NamespaceIdent,
         _: Self::State,
     ) -> Result<()> {
         Ok(())
@@ -0,0 +1,1303 @@
+//! `AuthZHandler` evaluating [Cedar](https://www.cedarpolicy.com) policies in-process.
+//!
+//! Policies are read from a file that is reloaded when it changes. The principal is an
+//! `Iceberg::User` with the claims of the token as attributes and its groups as
+//! `Iceberg::Group` parents. Resources form the hierarchy `Iceberg::Project` >
+//! `Iceberg::Warehouse` > `Iceberg::Namespace` > `Iceberg::Table` / `Iceberg::View`, so
+//! `resource in Iceberg::Warehouse::"<id>"` matches everything inside a warehouse.
+//! Actions are named after the checked operation, i.e. `Iceberg::Action::"LoadTable"`,
+//! and are members of one of the groups `Read`, `Write` or `Manage`.
+use crate::api::iceberg::v1::NamespaceIdent;
+use crate::api::{ErrorModel, Result};
+use crate::config::CedarConfig;
+use crate::implementations::postgres::dbutils::DBErrorHandler;
+use crate::implementations::DEFAULT_PROJECT_ID;
+use crate::request_metadata::RequestMetadata;
+use crate::service::auth::{AuthConfigHandler, AuthZHandler, UserWarehouse};
+use crate::service::health::{Health, HealthExt, HealthStatus};
+use crate::service::tabular_idents::TabularIdentUuid;
+use crate::service::token_verification::PrincipalId;
+use crate::service::TableIdentUuid;
+use crate::{ProjectIdent, WarehouseIdent};
+use anyhow::Context as _;
+use async_trait::async_trait;
+use cedar_policy::{Authorizer, Context, Entities, EntityUid, PolicySet, Request};
+use http::StatusCode;
+use serde_json::{json, Map, Value};
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Operations checked by the catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display, strum_macros::EnumString)]
+pub enum Action {
+    ListNamespaces,
+    CreateNamespace,
+    LoadNamespace,
+    DropNamespace,
+    UpdateNamespaceProperties,
+    CreateTable,
+    ListTables,
+    LoadTable,
+    RenameTable,
+    DropTable,
+    CommitTable,
+    CreateView,
+    ListViews,
+    LoadView,
+    RenameView,
+    DropView,
+    CommitView,
+    GetConfig,
+    ListProjects,
+    CreateProject,
+    RenameProject,
+    DeleteProject,
+    DeactivateProject,
+    ActivateProject,
+    ListWarehouses,
+    CreateWarehouse,
+    GetWarehouse,
+    RenameWarehouse,
+    DeleteWarehouse,
+    DeactivateWarehouse,
+    ActivateWarehouse,
+    UpdateStorage,
+}
+
+impl Action {
+    /// The action group this action is a member of.
+    #[must_use]
+    pub fn group(self) -> &'static str {
+        match self {
+            Action::ListNamespaces
+            | Action::LoadNamespace
+            | Action::ListTables
+            | Action::LoadTable
+            | Action::ListViews
+            | Action::LoadView
+            | Action::GetConfig
+            | Action::ListProjects
+            | Action::ListWarehouses
+            | Action::GetWarehouse => "Read",
+            Action::CreateNamespace
+            | Action::DropNamespace
+            | Action::UpdateNamespaceProperties
+            | Action::CreateTable
+            | Action::RenameTable
+            | Action::DropTable
+            | Action::CommitTable
+            | Action::CreateView
+            | Action::RenameView
+            | Action::DropView
+            | Action::CommitView => "Write",
+            Action::CreateProject
+            | Action::RenameProject
+            | Action::DeleteProject
+            | Action::DeactivateProject
+            | Action::ActivateProject
+            | Action::CreateWarehouse
+            | Action::RenameWarehouse
+            | Action::DeleteWarehouse
+            | Action::DeactivateWarehouse
+            | Action::ActivateWarehouse
+            | Action::UpdateStorage => "Manage",
+        }
+    }
+}
+
+/// The caller of a request as a Cedar principal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyPrincipal {
+    /// Formatted as `<issuer>|<subject>`.
+    pub id: String,
+    pub groups: BTreeSet<String>,
+    /// Attributes of the `Iceberg::User` entity.
+    pub attributes: Map<String, Value>,
+}
+
+impl PolicyPrincipal {
+    /// Builds a principal from the additional claims of a token.
+    /// Claims that Cedar can't represent, such as `null` or floats, are dropped.
+    #[must_use]
+    pub fn new(principal: &PrincipalId, claims: &Value, groups_claim: &str) -> Self {
+        let groups = match claims.get(groups_claim) {
+            Some(Value::Array(groups)) => groups
+                .iter()
+                .filter_map(|g| g.as_str().map(str::to_string))
+                .collect(),
+            Some(Value::String(group)) => BTreeSet::from([group.clone()]),
+            _ => BTreeSet::new(),
+        };
+        let mut attributes = match to_cedar_value(claims) {
+            Some(Value::Object(attributes)) => attributes,
+            _ => Map::new(),
+        };
+        attributes.insert("sub".to_string(), Value::String(principal.subject.clone()));
+        attributes.insert("iss".to_string(), Value::String(principal.issuer.clone()));
+
+        Self {
+            id: principal.to_string(),
+            groups,
+            attributes,
+        }
+    }
+
+    fn from_metadata(metadata: &RequestMetadata, groups_claim: &str) -> Result<Self> {
+        match &metadata.auth_details {
+            Some(auth_details) => Ok(Self::new(
+                &auth_details.principal_id(),
+                &auth_details.claims().other,
+                groups_claim,
+            )),
+            None => Err(ErrorModel::builder()
+                .code(StatusCode::UNAUTHORIZED.into())
+                .message("Authentication is required".to_string())
+                .r#type("UnauthorizedError".to_string())
+                .build()
+                .into()),
+        }
+    }
+}
+
+/// Converts JSON to the subset supported by Cedar's entity format.
+fn to_cedar_value(value: &Value) -> Option<Value> {
+    match value {
+        Value::Null => None,
+        Value::Number(number) => number.as_i64().map(Value::from),
+        Value::Bool(_) | Value::String(_) => Some(value.clone()),
+        Value::Array(items) => Some(Value::Array(
+            items.iter().filter_map(to_cedar_value).collect(),
+        )),
+        Value::Object(map) => Some(Value::Object(
+            map.iter()
+                .filter_map(|(key, value)| to_cedar_value(value).map(|v| (key.clone(), v)))
+                .collect(),
+        )),
+    }
+}
+
+/// The object an action is performed on. The most specific identifier that is set
+/// determines the resource, all others become its ancestors.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PolicyResource {
+    pub project_id: Option<Uuid>,
+    pub warehouse_id: Option<Uuid>,
+    pub namespace: Option<Vec<String>>,
+    pub table_id: Option<Uuid>,
+    pub view_id: Option<Uuid>,
+}
+
+impl PolicyResource {
+    /// Uids and attributes of the resource and its ancestors, outermost first.
+    fn hierarchy(&self) -> Vec<(Value, Value)> {
+        let mut hierarchy = vec![];
+        if let Some(project_id) = self.project_id {
+            hierarchy.push((uid("Project", project_id), json!({})));
+        }
+        if let Some(warehouse_id) = self.warehouse_id {
+            hierarchy.push((uid("Warehouse", warehouse_id), json!({})));
+            if let Some(namespace) = &self.namespace {
+                for depth in 1..=namespace.len() {
+                    let path = &namespace[..depth];
+                    hierarchy.push((
+                        uid("Namespace", namespace_id(warehouse_id, path)),
+                        json!({ "path": path }),
+                    ));
+                }
+            }
+        }
+        if let Some(table_id) = self.table_id {
+            hierarchy.push((uid("Table", table_id), json!({})));
+        } else if let Some(view_id) = self.view_id {
+            hierarchy.push((uid("View", view_id), json!({})));
+        }
+        hierarchy
+    }
+}
+
+/// Namespaces are identified by name, nested parts are separated by `\u{1f}`
+/// like in the REST API.
+fn namespace_id(warehouse_id: Uuid, path: &[String]) -> String {
+    format!("{warehouse_id}/{}", path.join("\u{1f}"))
+}
+
+fn uid(type_name: &str, id: impl ToString) -> Value {
+    json!({ "type": format!("Iceberg::{type_name}"), "id": id.to_string() })
+}
+
+fn entity(uid: &Value, attrs: Value, parents: Vec<Value>) -> Value {
+    json!({ "uid": uid, "attrs": attrs, "parents": parents })
+}
+
+fn entity_uid(uid: &Value) -> anyhow::Result<EntityUid> {
+    EntityUid::from_json(uid.clone()).map_err(|e| anyhow::anyhow!("Invalid entity {uid}: {e}"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyDecision {
+    pub allowed: bool,
+    /// Policies that determined the decision, by their `@id` annotation if present.
+    pub reasons: Vec<String>,
+    /// Errors of policies that failed to evaluate. Such policies are ignored.
+    pub errors: Vec<String>,
+}
+
+/// A set of Cedar policies.
+#[derive(Debug, Clone)]
+pub struct PolicyEngine {
+    policies: PolicySet,
+}
+
+impl PolicyEngine {
+    /// # Errors
+    /// Fails if the policies can't be parsed.
+    pub fn parse(source: &str) -> anyhow::Result<Self> {
+        let policies = PolicySet::from_str(source).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(Self { policies })
+    }
+
+    /// # Errors
+    /// Fails if the file can't be read or the policies can't be parsed.
+    pub async fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let source = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read policies from {}", path.display()))?;
+        Self::parse(&source)
+            .with_context(|| format!("Failed to parse policies in {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn num_policies(&self) -> usize {
+        self.policies.policies().count()
+    }
+
+    /// # Errors
+    /// Fails if the request can't be represented as Cedar entities.
+    pub fn evaluate(
+        &self,
+        principal: &PolicyPrincipal,
+        action: Action,
+        resource: &PolicyResource,
+    ) -> anyhow::Result<PolicyDecision> {
+        let principal_uid = uid("User", &principal.id);
+        let group_uids = principal
+            .groups
+            .iter()
+            .map(|group| uid("Group", group))
+            .collect::<Vec<_>>();
+        let action_uid = uid("Action", action);
+        let action_group_uid = uid("Action", action.group());
+
+        let mut entities = vec![
+            entity(
+                &principal_uid,
+                Value::Object(principal.attributes.clone()),
+                group_uids.clone(),
+            ),
+            entity(&action_uid, json!({}), vec![action_group_uid.clone()]),
+            entity(&action_group_uid, json!({}), vec![]),
+        ];
+        entities.extend(
+            group_uids
+                .iter()
+                .map(|group_uid| entity(group_uid, json!({}), vec![])),
+        );
+
+        let mut parent: Option<Value> = None;
+        for (resource_uid, attrs) in resource.hierarchy() {
+            entities.push(entity(&resource_uid, attrs, parent.into_iter().collect()));
+            parent = Some(resource_uid);
+        }
+        let resource_uid = parent.context("The resource is empty")?;
+
+        let entities = Entities::from_json_value(Value::Array(entities), None)?;
+        let request = Request::new(
+            entity_uid(&principal_uid)?,
+            entity_uid(&action_uid)?,
+            entity_uid(&resource_uid)?,
+            Context::empty(),
+            None,
+        )?;
+        let response = Authorizer::new().is_authorized(&request, &self.policies, &entities);
+
+        let diagnostics = response.diagnostics();
+        Ok(PolicyDecision {
+            allowed: response.decision() == cedar_policy::Decision::Allow,
+            reasons: diagnostics
+                .reason()
+                .map(|id| {
+                    self.policies
+                        .policy(id)
+                        .and_then(|policy| policy.annotation("id"))
+                        .map_or_else(|| id.to_string(), str::to_string)
+                })
+                .collect(),
+            errors: diagnostics.errors().map(ToString::to_string).collect(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct LoadedPolicies {
+    engine: Arc<PolicyEngine>,
+    modified: SystemTime,
+    /// Whether the last attempt to reload the file failed.
+    reload_failed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CedarAuthZState {
+    policy_file: PathBuf,
+    policies: Arc<RwLock<LoadedPolicies>>,
+    read_pool: PgPool,
+    groups_claim: String,
+    health: Arc<RwLock<Vec<Health>>>,
+}
+
+impl CedarAuthZState {
+    /// Loads the policies and spawns a background task that reloads the file when it changes.
+    /// The pool is used to resolve the ancestors of warehouses and tabulars.
+    ///
+    /// # Errors
+    /// Fails if the policy file can't be read or parsed.
+    pub async fn from_config(config: &CedarConfig, read_pool: PgPool) -> anyhow::Result<Self> {
+        let modified = tokio::fs::metadata(&config.policy_file)
+            .await
+            .with_context(|| format!("Failed to read {}", config.policy_file.display()))?
+            .modified()?;
+        let engine = PolicyEngine::from_file(&config.policy_file).await?;
+        tracing::info!("Loaded {} Cedar policies", engine.num_policies());
+
+        let slf = Self {
+            policy_file: config.policy_file.clone(),
+            policies: Arc::new(RwLock::new(LoadedPolicies {
+                engine: Arc::new(engine),
+                modified,
+                reload_failed: false,
+            })),
+            read_pool,
+            groups_claim: config.groups_claim.clone(),
+            health: Arc::new(RwLock::new(vec![Health::now(
+                "policy_file",
+                HealthStatus::Unknown,
+            )])),
+        };
+        slf.reload_task(Duration::from_secs(config.reload_interval_seconds));
+        Ok(slf)
+    }
+
+    fn reload_task(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let slf = self.clone();
+        tokio::task::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let result = slf.reload().await;
+                if let Err(e) = &result {
+                    tracing::error!(?e, "Failed to reload policies: {:?}", e);
+                }
+                slf.policies.write().await.reload_failed = result.is_err();
+            }
+        })
+    }
+
+    /// Reads the policy file if it changed since it was last read.
+    /// Policies that can't be parsed are not applied, the previous policies stay active.
+    async fn reload(&self) -> anyhow::Result<()> {
+        let modified = tokio::fs::metadata(&self.policy_file).await?.modified()?;
+        if self.policies.read().await.modified == modified {
+            return Ok(());
+        }
+
+        let engine = PolicyEngine::from_file(&self.policy_file).await?;
+        tracing::info!("Reloaded {} Cedar policies", engine.num_policies());
+        let mut policies = self.policies.write().await;
+        policies.engine = Arc::new(engine);
+        policies.modified = modified;
+        Ok(())
+    }
+
+    async fn is_allowed(
+        &self,
+        principal: &PolicyPrincipal,
+        action: Action,
+        resource: &PolicyResource,
+    ) -> Result<bool> {
+        let engine = self.policies.read().await.engine.clone();
+        let decision = engine.evaluate(principal, action, resource).map_err(|e| {
+            ErrorModel::internal(
+                "Failed to evaluate policies",
+                "PolicyEvaluationError",
+                Some(e.into()),
+            )
+        })?;
+        if !decision.errors.is_empty() {
+            tracing::warn!(errors = ?decision.errors, "Some policies failed to evaluate");
+        }
+        Ok(decision.allowed)
+    }
+
+    async fn require(
+        &self,
+        metadata: &RequestMetadata,
+        action: Action,
+        resource: PolicyResource,
+    ) -> Result<()> {
+        let principal = PolicyPrincipal::from_metadata(metadata, &self.groups_claim)?;
+        if self.is_allowed(&principal, action, &resource).await? {
+            Ok(())
+        } else {
+            Err(ErrorModel::forbidden(
+                format!("Action '{action}' is not permitted by the policies"),
+                "Forbidden",
+                None,
+            )
+            .into())
+        }
+    }
+
+    /// Builds the resource including all its ancestors. Tabulars that exist are placed
+    /// in the namespace they are stored in, missing ones in the namespace of the request.
+    async fn resource(
+        &self,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table_id: Option<Uuid>,
+        view_id: Option<Uuid>,
+    ) -> Result<PolicyResource> {
+        let project_id = sqlx::query_scalar!(
+            r#"SELECT project_id FROM warehouse WHERE warehouse_id = $1"#,
+            *warehouse_id
+        )
+        .fetch_optional(&self.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching project of warehouse".into()))?;
+
+        let stored_namespace = match table_id.or(view_id) {
+            Some(tabular_id) => sqlx::query_scalar!(
+                r#"
+                SELECT n.namespace_name
+                FROM tabular t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                WHERE t.tabular_id = $1
+                "#,
+                tabular_id
+            )
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching namespace of tabular".into()))?,
+            None => None,
+        };
+
+        Ok(PolicyResource {
+            project_id,
+            warehouse_id: Some(*warehouse_id),
+            namespace: stored_namespace.or_else(|| namespace.map(|ns| ns.clone().inner())),
+            table_id,
+            view_id,
+        })
+    }
+
+    async fn warehouse_resource(&self, warehouse_id: WarehouseIdent) -> Result<PolicyResource> {
+        self.resource(warehouse_id, None, None, None).await
+    }
+}
+
+#[async_trait]
+impl HealthExt for CedarAuthZState {
+    async fn health(&self) -> Vec<Health> {
+        self.health.read().await.clone()
+    }
+
+    async fn update_health(&self) {
+        let status = if self.policies.read().await.reload_failed {
+            HealthStatus::Unhealthy
+        } else {
+            HealthStatus::Healthy
+        };
+        let mut lock = self.health.write().await;
+        lock.clear();
+        lock.push(Health::now("policy_file", status));
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Authorization decisions are made by evaluating Cedar policies.
+pub struct CedarAuthZHandler;
+
+#[async_trait]
+impl AuthConfigHandler<CedarAuthZHandler> for CedarAuthZHandler {
+    async fn get_and_validate_user_warehouse(
+        state: CedarAuthZState,
+        metadata: &RequestMetadata,
+    ) -> Result<UserWarehouse> {
+        PolicyPrincipal::from_metadata(metadata, &state.groups_claim)?;
+        if let Some(user_warehouse) = UserWarehouse::from_catalog_token(metadata) {
+            // Permissions might have been revoked since the token was issued
+            if let Some(warehouse_id) = user_warehouse.warehouse_id {
+                Self::check_user_get_config_for_warehouse(state, warehouse_id, metadata).await?;
+            }
+            return Ok(user_warehouse);
+        }
+        Ok(UserWarehouse {
+            project_id: Some(
+                UserWarehouse::project_from_token(metadata)
+                    .unwrap_or(ProjectIdent::from(DEFAULT_PROJECT_ID)),
+            ),
+            warehouse_id: None,
+        })
+    }
+
+    async fn exchange_token_for_warehouse(
+        _: CedarAuthZState,
+        _: &RequestMetadata,
+        _: &ProjectIdent,
+        _: WarehouseIdent,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn check_list_warehouse_in_project(
+        state: CedarAuthZState,
+        project_id: &ProjectIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        <Self as AuthZHandler>::check_list_warehouse_in_project(metadata, *project_id, state)
+            .await
+            .map(|_| ())
+    }
+
+    async fn check_user_get_config_for_warehouse(
+        state: CedarAuthZState,
+        warehouse_id: WarehouseIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        let resource = state.warehouse_resource(warehouse_id).await?;
+        state.require(metadata, Action::GetConfig, resource).await
+    }
+}
+
+#[async_trait]
+impl AuthZHandler for CedarAuthZHandler {
+    type State = CedarAuthZState;
+
+    async fn check_list_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state.resource(warehouse_id, parent, None, None).await?;
+        state
+            .require(metadata, Action::ListNamespaces, resource)
+            .await
+    }
+
+    async fn check_create_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state.resource(warehouse_id, parent, None, None).await?;
+        state
+            .require(metadata, Action::CreateNamespace, resource)
+            .await
+    }
+
+    async fn check_load_namespace_metadata(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+        state
+            .require(metadata, Action::LoadNamespace, resource)
+            .await
+    }
+
+    async fn check_namespace_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+        state
+            .require(metadata, Action::LoadNamespace, resource)
+            .await
+    }
+
+    async fn check_drop_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+        state
+            .require(metadata, Action::DropNamespace, resource)
+            .await
+    }
+
+    async fn check_update_namespace_properties(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+        state
+            .require(metadata, Action::UpdateNamespaceProperties, resource)
+            .await
+    }
+
+    async fn check_create_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+        state.require(metadata, Action::CreateTable, resource).await
+    }
+
+    async fn check_list_tables(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+        state.require(metadata, Action::ListTables, resource).await
+    }
+
+    async fn check_load_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, namespace, table.map(Into::into), None)
+            .await?;
+        state.require(metadata, Action::LoadTable, resource).await
+    }
+
+    async fn check_rename_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        source: Option<TableIdentUuid>,
+        destination: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, None, source.map(Into::into), None)
+            .await?;
+        state
+            .require(metadata, Action::RenameTable, resource)
+            .await?;
+        Self::check_create_table(metadata, warehouse_id, destination, state).await
+    }
+
+    async fn check_table_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, namespace, table.map(Into::into), None)
+            .await?;
+        state.require(metadata, Action::LoadTable, resource).await
+    }
+
+    async fn check_drop_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, None, table.map(Into::into), None)
+            .await?;
+        state.require(metadata, Action::DropTable, resource).await
+    }
+
+    async fn check_commit_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        table: Option<TableIdentUuid>,
+        namespace: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, namespace, table.map(Into::into), None)
+            .await?;
+        state.require(metadata, Action::CommitTable, resource).await
+    }
+
+    // ---------------- Management API ----------------
+    async fn check_create_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        _id_requested: bool,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = PolicyResource {
+            project_id: Some(*project_id),
+            ..PolicyResource::default()
+        };
+        state
+            .require(metadata, Action::CreateProject, resource)
+            .await
+    }
+
+    async fn check_rename_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = PolicyResource {
+            project_id: Some(*project_id),
+            ..PolicyResource::default()
+        };
+        state
+            .require(metadata, Action::RenameProject, resource)
+            .await
+    }
+
+    async fn check_delete_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = PolicyResource {
+            project_id: Some(*project_id),
+            ..PolicyResource::default()
+        };
+        state
+            .require(metadata, Action::DeleteProject, resource)
+            .await
+    }
+
+    async fn check_deactivate_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = PolicyResource {
+            project_id: Some(*project_id),
+            ..PolicyResource::default()
+        };
+        state
+            .require(metadata, Action::DeactivateProject, resource)
+            .await
+    }
+
+    async fn check_activate_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = PolicyResource {
+            project_id: Some(*project_id),
+            ..PolicyResource::default()
+        };
+        state
+            .require(metadata, Action::ActivateProject, resource)
+            .await
+    }
+
+    async fn check_create_warehouse(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = PolicyResource {
+            project_id: Some(**project_id),
+            ..PolicyResource::default()
+        };
+        state
+            .require(metadata, Action::CreateWarehouse, resource)
+            .await
+    }
+
+    async fn check_list_projects(
+        metadata: &RequestMetadata,
+        state: Self::State,
+    ) -> Result<Option<HashSet<ProjectIdent>>> {
+        let principal = PolicyPrincipal::from_metadata(metadata, &state.groups_claim)?;
+        let projects = sqlx::query_scalar!(r#"SELECT project_id FROM project"#)
+            .fetch_all(&state.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
+
+        let mut allowed = HashSet::new();
+        for project_id in projects {
+            let resource = PolicyResource {
+                project_id: Some(project_id),
+                ..PolicyResource::default()
+            };
+            if state
+                .is_allowed(&principal, Action::ListProjects, &resource)
+                .await?
+            {
+                allowed.insert(ProjectIdent::from(project_id));
+            }
+        }
+        Ok(Some(allowed))
+    }
+
+    async fn check_list_warehouse_in_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<Option<HashSet<WarehouseIdent>>> {
+        let principal = PolicyPrincipal::from_metadata(metadata, &state.groups_claim)?;
+        let project = PolicyResource {
+            project_id: Some(*project_id),
+            ..PolicyResource::default()
+        };
+        if state
+            .is_allowed(&principal, Action::ListWarehouses, &project)
+            .await?
+        {
+            return Ok(None);
+        }
+
+        // Without permission on the project, only warehouses that may be read are listed
+        let warehouses = sqlx::query_scalar!(
+            r#"SELECT warehouse_id FROM warehouse WHERE project_id = $1"#,
+            *project_id
+        )
+        .fetch_all(&state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching warehouses".into()))?;
+
+        let mut allowed = HashSet::new();
+        for warehouse_id in warehouses {
+            let resource = PolicyResource {
+                project_id: Some(*project_id),
+                warehouse_id: Some(warehouse_id),
+                ..PolicyResource::default()
+            };
+            if state
+                .is_allowed(&principal, Action::GetWarehouse, &resource)
+                .await?
+            {
+                allowed.insert(WarehouseIdent::from(warehouse_id));
+            }
+        }
+        Ok(Some(allowed))
+    }
+
+    async fn check_delete_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state.warehouse_resource(warehouse_id).await?;
+        state
+            .require(metadata, Action::DeleteWarehouse, resource)
+            .await
+    }
+
+    async fn check_get_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state.warehouse_resource(warehouse_id).await?;
+        state
+            .require(metadata, Action::GetWarehouse, resource)
+            .await
+    }
+
+    async fn check_rename_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state.warehouse_resource(warehouse_id).await?;
+        state
+            .require(metadata, Action::RenameWarehouse, resource)
+            .await
+    }
+
+    async fn check_deactivate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state.warehouse_resource(warehouse_id).await?;
+        state
+            .require(metadata, Action::DeactivateWarehouse, resource)
+            .await
+    }
+
+    async fn check_activate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state.warehouse_resource(warehouse_id).await?;
+        state
+            .require(metadata, Action::ActivateWarehouse, resource)
+            .await
+    }
+
+    async fn check_update_storage(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state.warehouse_resource(warehouse_id).await?;
+        state
+            .require(metadata, Action::UpdateStorage, resource)
+            .await
+    }
+
+    async fn check_create_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+        state.require(metadata, Action::CreateView, resource).await
+    }
+
+    async fn check_drop_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, None, None, view.map(|v| **v))
+            .await?;
+        state.require(metadata, Action::DropView, resource).await
+    }
+
+    async fn check_load_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, namespace, None, view.map(|v| **v))
+            .await?;
+        state.require(metadata, Action::LoadView, resource).await
+    }
+
+    async fn check_commit_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        view: Option<&TableIdentUuid>,
+        namespace: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, namespace, None, view.map(|v| **v))
+            .await?;
+        state.require(metadata, Action::CommitView, resource).await
+    }
+
+    async fn check_rename_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        source: Option<&TableIdentUuid>,
+        destination: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, None, None, source.map(|v| **v))
+            .await?;
+        state
+            .require(metadata, Action::RenameView, resource)
+            .await?;
+        Self::check_create_view(metadata, warehouse_id, destination, state).await
+    }
+
+    async fn check_list_views(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+        state.require(metadata, Action::ListViews, resource).await
+    }
+
+    async fn check_view_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let resource = state
+            .resource(warehouse_id, namespace, None, view.map(|v| **v))
+            .await?;
+        state.require(metadata, Action::LoadView, resource).await
+    }
+
+    // ---------------- Visibility ----------------
+    async fn filter_visible_tabulars(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        tabulars: Vec<TabularIdentUuid>,
+        state: Self::State,
+    ) -> Result<HashSet<TabularIdentUuid>> {
+        let principal = PolicyPrincipal::from_metadata(metadata, &state.groups_claim)?;
+        let parent = state
+            .resource(warehouse_id, Some(namespace), None, None)
+            .await?;
+
+        let mut visible = HashSet::new();
+        for tabular in tabulars {
+            let (action, resource) = match tabular {
+                TabularIdentUuid::Table(table_id) => (
+                    Action::LoadTable,
+                    PolicyResource {
+                        table_id: Some(table_id),
+                        ..parent.clone()
+                    },
+                ),
+                TabularIdentUuid::View(view_id) => (
+                    Action::LoadView,
+                    PolicyResource {
+                        view_id: Some(view_id),
+                        ..parent.clone()
+                    },
+                ),
+            };
+            if state.is_allowed(&principal, action, &resource).await? {
+                visible.insert(tabular);
+            }
+        }
+        Ok(visible)
+    }
+
+    async fn filter_visible_namespaces(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespaces: Vec<NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<HashSet<NamespaceIdent>> {
+        let principal = PolicyPrincipal::from_metadata(metadata, &state.groups_claim)?;
+        let warehouse = state.warehouse_resource(warehouse_id).await?;
+
+        let mut visible = HashSet::new();
+        for namespace in namespaces {
+            let resource = PolicyResource {
+                namespace: Some(namespace.clone().inner()),
+                ..warehouse.clone()
+            };
+            if state
+                .is_allowed(&principal, Action::LoadNamespace, &resource)
+                .await?
+            {
+                visible.insert(namespace);
+            }
+        }
+        Ok(visible)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICIES: &str = r#"
+        @id("analysts-read")
+        permit (
+            principal in Iceberg::Group::"analysts",
+            action in Iceberg::Action::"Read",
+            resource in Iceberg::Warehouse::"00000000-0000-0000-0000-000000000001"
+        );
+
+        @id("finance-write")
+        permit (principal, action in Iceberg::Action::"Write", resource)
+        when { principal has department && principal.department == "finance" };
+
+        @id("no-secrets")
+        forbid (principal, action, resource is Iceberg::Namespace)
+        when { resource.path.contains("secret") };
+    "#;
+
+    fn warehouse_id() -> Uuid {
+        Uuid::from_u128(1)
+    }
+
+    fn principal_id(subject: &str) -> PrincipalId {
+        PrincipalId::new("https://idp.example.com", subject)
+    }
+
+    fn table(namespace: &[&str]) -> PolicyResource {
+        PolicyResource {
+            project_id: Some(DEFAULT_PROJECT_ID),
+            warehouse_id: Some(warehouse_id()),
+            namespace: Some(namespace.iter().map(ToString::to_string).collect()),
+            table_id: Some(Uuid::now_v7()),
+            view_id: None,
+        }
+    }
+
+    #[test]
+    fn test_principal_from_claims() {
+        let principal = PolicyPrincipal::new(
+            &principal_id("alice"),
+            &json!({
+                "groups": ["analysts", "analysts", "admins"],
+                "department": "finance",
+                "level": 3,
+                "score": 0.5,
+                "manager": null
+            }),
+            "groups",
+        );
+        assert_eq!(
+            principal.groups,
+            BTreeSet::from(["admins".to_string(), "analysts".to_string()])
+        );
+        assert_eq!(principal.attributes["department"], json!("finance"));
+        assert_eq!(principal.attributes["level"], json!(3));
+        assert_eq!(principal.id, "https://idp.example.com|alice");
+        assert_eq!(principal.attributes["sub"], json!("alice"));
+        assert_eq!(
+            principal.attributes["iss"],
+            json!("https://idp.example.com")
+        );
+        assert!(!principal.attributes.contains_key("score"));
+        assert!(!principal.attributes.contains_key("manager"));
+    }
+
+    #[test]
+    fn test_group_permissions_are_inherited() {
+        let engine = PolicyEngine::parse(POLICIES).unwrap();
+        let analyst = PolicyPrincipal::new(
+            &principal_id("alice"),
+            &json!({"groups": ["analysts"]}),
+            "groups",
+        );
+
+        let decision = engine
+            .evaluate(&analyst, Action::LoadTable, &table(&["sales"]))
+            .unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.reasons, vec!["analysts-read".to_string()]);
+
+        let decision = engine
+            .evaluate(&analyst, Action::DropTable, &table(&["sales"]))
+            .unwrap();
+        assert!(!decision.allowed);
+
+        let other_warehouse = PolicyResource {
+            warehouse_id: Some(Uuid::from_u128(2)),
+            ..table(&["sales"])
+        };
+        let decision = engine
+            .evaluate(&analyst, Action::LoadTable, &other_warehouse)
+            .unwrap();
+        assert!(!decision.allowed);
+    }
+
+    #[test]
+    fn test_attribute_conditions_and_forbid() {
+        let engine = PolicyEngine::parse(POLICIES).unwrap();
+        let finance = PolicyPrincipal::new(
+            &principal_id("bob"),
+            &json!({"department": "finance"}),
+            "groups",
+        );
+        let stranger = PolicyPrincipal::new(&principal_id("carol"), &json!({}), "groups");
+
+        let decision = engine
+            .evaluate(&finance, Action::CommitTable, &table(&["sales"]))
+            .unwrap();
+        assert!(decision.allowed);
+        assert!(decision.errors.is_empty());
+
+        let decision = engine
+            .evaluate(&stranger, Action::CommitTable, &table(&["sales"]))
+            .unwrap();
+        assert!(!decision.allowed);
+
+        // Applies to the namespace itself, tables are only matched by the hierarchy
+        let namespace = PolicyResource {
+            table_id: None,
+            ..table(&["secret"])
+        };
+        let decision = engine
+            .evaluate(&finance, Action::CreateTable, &namespace)
+            .unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.reasons, vec!["no-secrets".to_string()]);
+    }
+
+    #[test]
+    fn test_resource_hierarchy() {
+        let resource = table(&["a", "b"]);
+        let uids = resource
+            .hierarchy()
+            .into_iter()
+            .map(|(uid, _)| uid["type"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            uids,
+            vec![
+                "Iceberg::Project",
+                "Iceberg::Warehouse",
+                "Iceberg::Namespace",
+                "Iceberg::Namespace",
+                "Iceberg::Table"
+            ]
+        );
+        assert_eq!(
+            resource.hierarchy()[3].0["id"],
+            json!(format!("{}/a\u{1f}b", warehouse_id()))
+        );
+
+        let engine = PolicyEngine::parse(POLICIES).unwrap();
+        let principal = PolicyPrincipal::new(&principal_id("alice"), &json!({}), "groups");
+        assert!(engine
+            .evaluate(&principal, Action::LoadTable, &PolicyResource::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid_policies_are_rejected() {
+        assert!(PolicyEngine::parse("permit (principal, action, resource").is_err());
+        assert_eq!(PolicyEngine::parse(POLICIES).unwrap().num_policies(), 3);
+        assert_eq!(Action::from_str("LoadTable").unwrap(), Action::LoadTable);
+    }
+}
@@ -0,0 +1,704 @@
+//! Secret store backed by JSON files in a directory, e.g. a mounted Kubernetes secret.
+//!
+//! Every secret is stored as `<secret-id>.json` in the configured directory.
+//! If writes are enabled, previous values are kept as `.versions/<secret-id>/<version>.json`.
+//! The directory is re-scanned periodically so that changes of the files are picked up
+//! without a restart.
+use crate::api::{ErrorModel, Result};
+use crate::config::FileSecretsConfig;
+use crate::service::health::{Health, HealthExt, HealthStatus};
+use crate::service::secrets::{Secret, SecretIdent, SecretInStorage, SecretStore, SecretVersion};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt as _;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+const VERSIONS_DIR: &str = ".versions";
+
+#[derive(Debug, Clone)]
+pub struct SecretsState {
+    directory: PathBuf,
+    allow_writes: bool,
+    secrets: Arc<RwLock<HashMap<SecretIdent, CachedSecret>>>,
+    health: Arc<RwLock<Vec<Health>>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedSecret {
+    value: serde_json::Value,
+    version: u64,
+    modified: SystemTime,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SecretsState {
+    /// Creates a new `SecretsState` from a `FileSecretsConfig`.
+    ///
+    /// This constructor spawns a background task that reloads changed files.
+    ///
+    /// # Errors
+    /// Fails if the directory can't be read.
+    pub async fn from_config(
+        FileSecretsConfig {
+            directory,
+            allow_writes,
+            reload_interval_seconds,
+        }: &FileSecretsConfig,
+    ) -> anyhow::Result<Self> {
+        let slf = Self::new(directory.clone(), *allow_writes).await?;
+        slf.reload_task(Duration::from_secs(*reload_interval_seconds));
+        Ok(slf)
+    }
+
+    async fn new(directory: PathBuf, allow_writes: bool) -> anyhow::Result<Self> {
+        let slf = Self {
+            directory,
+            allow_writes,
+            secrets: Arc::default(),
+            health: Arc::new(RwLock::new(vec![Health::now(
+                "secret_directory",
+                HealthStatus::Unknown,
+            )])),
+        };
+        slf.reload()
+            .await
+            .with_context(|| format!("Failed to read secrets from {}", slf.directory.display()))?;
+        Ok(slf)
+    }
+
+    fn reload_task(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let slf = self.clone();
+        tokio::task::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = slf.reload().await {
+                    tracing::error!(?e, "Failed to reload secrets: {:?}", e);
+                }
+            }
+        })
+    }
+
+    /// Re-scan the directory. Only files that changed since the last scan are read.
+    /// Files that can't be read or parsed are skipped and keep their previous value.
+    async fn reload(&self) -> anyhow::Result<()> {
+        let mut found = HashMap::new();
+        let mut unreadable = HashSet::new();
+        let mut entries = tokio::fs::read_dir(&self.directory).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let Some(secret_id) = secret_id_from_path(&entry.path()) else {
+                continue;
+            };
+            match file_modified(&entry.path()).await {
+                Ok(Some(modified)) => {
+                    found.insert(secret_id, modified);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::error!(?e, "Failed to read secret {secret_id}: {:?}", e);
+                    unreadable.insert(secret_id);
+                }
+            }
+        }
+
+        let changed = {
+            let secrets = self.secrets.read().await;
+            found
+                .iter()
+                .filter(|(id, modified)| {
+                    secrets
+                        .get(*id)
+                        .map_or(true, |cached| cached.modified != **modified)
+                })
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>()
+        };
+
+        let mut loaded = Vec::with_capacity(changed.len());
+        for secret_id in changed {
+            match self.read_current(secret_id).await {
+                Ok(cached) => loaded.push((secret_id, cached)),
+                Err(e) => {
+                    tracing::error!(?e, "Failed to read secret {secret_id}: {:?}", e);
+                }
+            }
+        }
+
+        let mut secrets = self.secrets.write().await;
+        secrets.retain(|id, _| found.contains_key(id) || unreadable.contains(id));
+        if !loaded.is_empty() {
+            tracing::debug!("Reloaded {} secrets", loaded.len());
+        }
+        secrets.extend(loaded);
+        Ok(())
+    }
+
+    async fn read_current(&self, secret_id: SecretIdent) -> anyhow::Result<CachedSecret> {
+        let path = self.secret_path(secret_id);
+        let metadata = tokio::fs::metadata(&path).await?;
+        let value = serde_json::from_slice(&tokio::fs::read(&path).await?)?;
+        let modified = metadata.modified()?;
+        Ok(CachedSecret {
+            value,
+            version: self.latest_archived_version(secret_id).await? + 1,
+            modified,
+            created_at: metadata.created().unwrap_or(modified).into(),
+        })
+    }
+
+    /// Highest archived version, 0 if there is none.
+    async fn latest_archived_version(&self, secret_id: SecretIdent) -> anyhow::Result<u64> {
+        let mut entries = match tokio::fs::read_dir(self.versions_path(secret_id)).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut latest = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(version) = version_from_path(&entry.path()) {
+                latest = latest.max(version);
+            }
+        }
+        Ok(latest)
+    }
+
+    fn secret_path(&self, secret_id: SecretIdent) -> PathBuf {
+        self.directory.join(format!("{secret_id}.json"))
+    }
+
+    fn versions_path(&self, secret_id: SecretIdent) -> PathBuf {
+        self.directory
+            .join(VERSIONS_DIR)
+            .join(secret_id.to_string())
+    }
+
+    fn version_path(&self, secret_id: SecretIdent, version: u64) -> PathBuf {
+        self.versions_path(secret_id)
+            .join(format!("{version}.json"))
+    }
+
+    fn require_writes(&self) -> Result<()> {
+        if self.allow_writes {
+            Ok(())
+        } else {
+            Err(ErrorModel::not_allowed(
+                "The file secret store is read-only",
+                "SecretStoreReadOnly",
+                None,
+            )
+            .into())
+        }
+    }
+
+    async fn get_cached(&self, secret_id: &SecretIdent) -> Result<CachedSecret> {
+        self.secrets
+            .read()
+            .await
+            .get(secret_id)
+            .cloned()
+            .ok_or_else(|| secret_not_found(*secret_id).into())
+    }
+
+    /// Write `secret` as the current value, atomically replacing the previous one.
+    async fn write_current<S: Serialize>(
+        &self,
+        secret_id: SecretIdent,
+        secret: &S,
+        version: u64,
+    ) -> Result<()> {
+        let value = serde_json::to_value(secret).map_err(|e| {
+            ErrorModel::internal(
+                "Failed to serialize secret",
+                "SecretSerializationFailed",
+                Some(Box::new(e)),
+            )
+        })?;
+        let path = self.secret_path(secret_id);
+        write_json(&path, &value).await?;
+
+        let metadata = tokio::fs::metadata(&path)
+            .await
+            .map_err(|e| io_error("Failed to write secret", e))?;
+        let modified = metadata
+            .modified()
+            .map_err(|e| io_error("Failed to write secret", e))?;
+        let mut secrets = self.secrets.write().await;
+        let created_at = secrets
+            .get(&secret_id)
+            .map_or_else(|| modified.into(), |cached| cached.created_at);
+        secrets.insert(
+            secret_id,
+            CachedSecret {
+                value,
+                version,
+                modified,
+                created_at,
+            },
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SecretStore for SecretsState {
+    async fn get_secret_by_id<S: SecretInStorage + DeserializeOwned>(
+        &self,
+        secret_id: &SecretIdent,
+    ) -> Result<Secret<S>> {
+        let cached = self.get_cached(secret_id).await?;
+        Ok(Secret {
+            secret_id: *secret_id,
+            secret: deserialize_secret(cached.value)?,
+            version: cached.version,
+            created_at: cached.created_at,
+            updated_at: Some(cached.modified.into()),
+        })
+    }
+
+    async fn create_secret<S: SecretInStorage + Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret: S,
+    ) -> Result<SecretIdent> {
+        self.require_writes()?;
+        let secret_id = SecretIdent::from(Uuid::now_v7());
+        self.write_current(secret_id, &secret, 1).await?;
+        Ok(secret_id)
+    }
+
+    async fn get_secret_version<S: SecretInStorage + DeserializeOwned>(
+        &self,
+        secret_id: &SecretIdent,
+        version: u64,
+    ) -> Result<Secret<S>> {
+        let cached = self.get_cached(secret_id).await?;
+        if version == cached.version {
+            return Ok(Secret {
+                secret_id: *secret_id,
+                secret: deserialize_secret(cached.value)?,
+                version,
+                created_at: cached.created_at,
+                updated_at: Some(cached.modified.into()),
+            });
+        }
+
+        let path = self.version_path(*secret_id, version);
+        let content = match tokio::fs::read(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ErrorModel::not_found(
+                    format!("Version {version} of secret {secret_id} not found"),
+                    "SecretVersionNotFound",
+                    None,
+                )
+                .into())
+            }
+            Err(e) => return Err(io_error("Failed to read secret version", e).into()),
+        };
+        let metadata = tokio::fs::metadata(&path)
+            .await
+            .map_err(|e| io_error("Failed to read secret version", e))?;
+        let value = serde_json::from_slice(&content).map_err(|e| {
+            ErrorModel::internal(
+                "Failed to deserialize secret version",
+                "SecretDeserializationFailed",
+                Some(Box::new(e)),
+            )
+        })?;
+        let modified = metadata
+            .modified()
+            .map_err(|e| io_error("Failed to read secret version", e))?;
+        Ok(Secret {
+            secret_id: *secret_id,
+            secret: deserialize_secret(value)?,
+            version,
+            created_at: cached.created_at,
+            updated_at: Some(modified.into()),
+        })
+    }
+
+    async fn update_secret<S: SecretInStorage + Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret_id: &SecretIdent,
+        secret: S,
+    ) -> Result<u64> {
+        self.require_writes()?;
+        let cached = self.get_cached(secret_id).await?;
+
+        // Archive the current value before replacing it
+        tokio::fs::create_dir_all(self.versions_path(*secret_id))
+            .await
+            .map_err(|e| io_error("Failed to archive secret version", e))?;
+        write_json(
+            &self.version_path(*secret_id, cached.version),
+            &cached.value,
+        )
+        .await?;
+
+        let version = cached.version + 1;
+        self.write_current(*secret_id, &secret, version).await?;
+        Ok(version)
+    }
+
+    async fn list_secret_versions(&self, secret_id: &SecretIdent) -> Result<Vec<SecretVersion>> {
+        let cached = self.get_cached(secret_id).await?;
+        let mut versions = vec![SecretVersion {
+            version: cached.version,
+            created_at: cached.modified.into(),
+            current: true,
+        }];
+
+        let mut entries = match tokio::fs::read_dir(self.versions_path(*secret_id)).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(versions),
+            Err(e) => return Err(io_error("Failed to list secret versions", e).into()),
+        };
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| io_error("Failed to list secret versions", e))?
+        {
+            let Some(version) = version_from_path(&entry.path()) else {
+                continue;
+            };
+            let modified = entry
+                .metadata()
+                .await
+                .and_then(|m| m.modified())
+                .map_err(|e| io_error("Failed to list secret versions", e))?;
+            versions.push(SecretVersion {
+                version,
+                created_at: modified.into(),
+                current: false,
+            });
+        }
+        versions.sort_by(|a, b| b.version.cmp(&a.version));
+
+        Ok(versions)
+    }
+
+    async fn delete_secret(&self, secret_id: &SecretIdent) -> Result<()> {
+        self.require_writes()?;
+        match tokio::fs::remove_file(self.secret_path(*secret_id)).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(secret_not_found(*secret_id).into())
+            }
+            Err(e) => return Err(io_error("Failed to delete secret", e).into()),
+        }
+        match tokio::fs::remove_dir_all(self.versions_path(*secret_id)).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(io_error("Failed to delete secret versions", e).into()),
+        }
+        self.secrets.write().await.remove(secret_id);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HealthExt for SecretsState {
+    async fn health(&self) -> Vec<Health> {
+        self.health.read().await.clone()
+    }
+
+    async fn update_health(&self) {
+        let status = match tokio::fs::read_dir(&self.directory).await {
+            Ok(_) => HealthStatus::Healthy,
+            Err(err) => {
+                tracing::error!(?err, "Secret directory is not readable");
+                HealthStatus::Unhealthy
+            }
+        };
+        let mut lock = self.health.write().await;
+        lock.clear();
+        lock.push(Health::now("secret_directory", status));
+    }
+}
+
+fn secret_id_from_path(path: &Path) -> Option<SecretIdent> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    path.file_stem()?
+        .to_str()?
+        .parse::<Uuid>()
+        .ok()
+        .map(SecretIdent::from)
+}
+
+/// Modification time of the file at `path`, `None` if it is not a file.
+async fn file_modified(path: &Path) -> std::io::Result<Option<SystemTime>> {
+    // Follows symlinks, mounted Kubernetes secrets are symlinks to the current data.
+    let metadata = tokio::fs::metadata(path).await?;
+    if metadata.is_file() {
+        metadata.modified().map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn version_from_path(path: &Path) -> Option<u64> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Write `value` to `path` atomically, readers see either the previous or the new file.
+/// Files are only accessible by the owner, as they contain credentials.
+async fn write_json(path: &Path, value: &serde_json::Value) -> Result<()> {
+    let content = serde_json::to_vec_pretty(value).map_err(|e| {
+        ErrorModel::internal(
+            "Failed to serialize secret",
+            "SecretSerializationFailed",
+            Some(Box::new(e)),
+        )
+    })?;
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    // Not a `.json` file, so that it is never loaded as a secret
+    let tmp_path = path.with_file_name(format!(".{file_name}.{}.tmp", Uuid::now_v7()));
+    let result = match write_private_file(&tmp_path, &content).await {
+        Ok(()) => tokio::fs::rename(&tmp_path, path).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tokio::fs::remove_file(&tmp_path).await.ok();
+        return Err(io_error("Failed to write secret", e).into());
+    }
+    Ok(())
+}
+
+async fn write_private_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path).await?;
+    file.write_all(content).await?;
+    file.sync_all().await
+}
+
+fn deserialize_secret<S: DeserializeOwned>(value: serde_json::Value) -> Result<S> {
+    Ok(serde_json::from_value(value).map_err(|e| {
+        ErrorModel::internal(
+            "Failed to deserialize secret",
+            "SecretDeserializationFailed",
+            Some(Box::new(e)),
+        )
+    })?)
+}
+
+fn secret_not_found(secret_id: SecretIdent) -> ErrorModel {
+    ErrorModel::not_found(
+        format!("Secret {secret_id} not found"),
+        "SecretNotFound",
+        None,
+    )
+}
+
+fn io_error(message: &str, e: std::io::Error) -> ErrorModel {
+    ErrorModel::internal(message, "SecretStoreIOError", Some(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::storage::{S3Credential, StorageCredential};
+    use http::StatusCode;
+
+    async fn test_state(allow_writes: bool) -> SecretsState {
+        let directory = std::env::temp_dir().join(format!("secrets-{}", Uuid::now_v7()));
+        tokio::fs::create_dir_all(&directory).await.unwrap();
+        SecretsState::new(directory, allow_writes).await.unwrap()
+    }
+
+    fn credential(key: &str) -> StorageCredential {
+        S3Credential::AccessKey {
+            aws_access_key_id: key.to_string(),
+            aws_secret_access_key: "my secret key".to_string(),
+        }
+        .into()
+    }
+
+    #[tokio::test]
+    async fn test_write_read_secret() {
+        let state = test_state(true).await;
+        let secret = credential("my access key");
+
+        let secret_id = state.create_secret(secret.clone()).await.unwrap();
+        let read_secret = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read_secret.secret, secret);
+        assert_eq!(read_secret.version, 1);
+
+        // A fresh state reads the same file
+        let state = SecretsState::new(state.directory.clone(), false)
+            .await
+            .unwrap();
+        let read_secret = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read_secret.secret, secret);
+    }
+
+    #[tokio::test]
+    async fn test_update_secret_keeps_versions() {
+        let state = test_state(true).await;
+        let first = credential("first access key");
+        let second = credential("second access key");
+
+        let secret_id = state.create_secret(first.clone()).await.unwrap();
+        let version = state
+            .update_secret(&secret_id, second.clone())
+            .await
+            .unwrap();
+        assert_eq!(version, 2);
+
+        let current = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(current.secret, second);
+        assert_eq!(current.version, 2);
+
+        let old = state
+            .get_secret_version::<StorageCredential>(&secret_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(old.secret, first);
+
+        let versions = state.list_secret_versions(&secret_id).await.unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert!(versions[0].current);
+        assert!(!versions[1].current);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_secret_files_are_private() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let state = test_state(true).await;
+        let secret_id = state
+            .create_secret(credential("first access key"))
+            .await
+            .unwrap();
+        state
+            .update_secret(&secret_id, credential("second access key"))
+            .await
+            .unwrap();
+
+        for path in [
+            state.secret_path(secret_id),
+            state.version_path(secret_id, 1),
+        ] {
+            let mode = tokio::fs::metadata(&path)
+                .await
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600, "{}", path.display());
+        }
+        // No temporary files are left behind
+        let mut entries = tokio::fs::read_dir(&state.directory).await.unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            assert!(!entry.file_name().to_string_lossy().ends_with(".tmp"));
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_reload_skips_unreadable_files() {
+        let state = test_state(false).await;
+        let secret_id = SecretIdent::from(Uuid::now_v7());
+        write_json(
+            &state.secret_path(secret_id),
+            &serde_json::to_value(credential("my access key")).unwrap(),
+        )
+        .await
+        .unwrap();
+        // A dangling symlink, i.e. a Kubernetes secret that is being updated
+        tokio::fs::symlink(
+            state.directory.join("missing"),
+            state.secret_path(SecretIdent::from(Uuid::now_v7())),
+        )
+        .await
+        .unwrap();
+
+        state.reload().await.unwrap();
+        let read_secret = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read_secret.secret, credential("my access key"));
+    }
+
+    #[tokio::test]
+    async fn test_read_only_store_rejects_writes() {
+        let state = test_state(false).await;
+        let err = state
+            .create_secret(credential("my access key"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn test_reload_picks_up_changed_files() {
+        let state = test_state(false).await;
+        let secret_id = SecretIdent::from(Uuid::now_v7());
+        let path = state.secret_path(secret_id);
+
+        write_json(
+            &path,
+            &serde_json::to_value(credential("first access key")).unwrap(),
+        )
+        .await
+        .unwrap();
+        state.reload().await.unwrap();
+        let read_secret = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read_secret.secret, credential("first access key"));
+
+        // Make sure the modification time changes
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        write_json(
+            &path,
+            &serde_json::to_value(credential("second access key")).unwrap(),
+        )
+        .await
+        .unwrap();
+        state.reload().await.unwrap();
+        let read_secret = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read_secret.secret, credential("second access key"));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+        state.reload().await.unwrap();
+        let err = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+}
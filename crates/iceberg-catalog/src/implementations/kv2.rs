@@ -1,6 +1,6 @@
 use crate::api::{ErrorModel, Result};
 use crate::service::health::{Health, HealthExt, HealthStatus};
-use crate::service::secrets::{Secret, SecretIdent, SecretStore};
+use crate::service::secrets::{Secret, SecretIdent, SecretStore, SecretVersion};
 use std::fmt::Formatter;
 
 use async_trait::async_trait;
@@ -11,13 +11,10 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tokio::time::Sleep;
 use uuid::Uuid;
 use vaultrs::client::{Client, VaultClient};
 
-use crate::config::KV2Config;
-use vaultrs_login::engines::userpass::UserpassLogin;
-use vaultrs_login::LoginMethod;
+use crate::config::{KV2Auth, KV2Config};
 
 #[async_trait::async_trait]
 impl SecretStore for SecretsState {
@@ -28,7 +25,137 @@ impl SecretStore for SecretsState {
     ) -> Result<Secret<S>> {
         // it seems there is no atomic get for metadata and secret so we read_metadata, and then
         // read the secret with the current version defined in the previously read metadata
-        let metadata = vaultrs::kv2::read_metadata(
+        let metadata = self.read_metadata(secret_id).await?;
+        self.read_version(secret_id, metadata.current_version, &metadata)
+            .await
+    }
+
+    /// Create a new secret
+    async fn create_secret<S: Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret: S,
+    ) -> Result<SecretIdent> {
+        let secret_id = SecretIdent::from(Uuid::now_v7());
+        vaultrs::kv2::set(
+            &*self.vault_client.read().await,
+            self.secret_mount.as_str(),
+            &secret_ident_to_key(secret_id),
+            &secret,
+        )
+        .await
+        .map_err(|err| {
+            ErrorModel::internal(
+                "secret creation failure",
+                "SecretCreationFailed",
+                Some(Box::new(err)),
+            )
+        })?;
+        Ok(secret_id)
+    }
+
+    async fn get_secret_version<S: DeserializeOwned>(
+        &self,
+        secret_id: &SecretIdent,
+        version: u64,
+    ) -> Result<Secret<S>> {
+        let metadata = self.read_metadata(secret_id).await?;
+        if !metadata.versions.contains_key(&version.to_string()) {
+            return Err(ErrorModel::not_found(
+                format!("Version {version} of secret {secret_id} not found"),
+                "SecretVersionNotFound",
+                None,
+            )
+            .into());
+        }
+        self.read_version(secret_id, version, &metadata).await
+    }
+
+    /// KV2 versions secrets natively, every write creates a new version.
+    async fn update_secret<S: Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret_id: &SecretIdent,
+        secret: S,
+    ) -> Result<u64> {
+        // Writing to a non-existing key would silently create a new secret
+        self.read_metadata(secret_id).await?;
+
+        let version = vaultrs::kv2::set(
+            &*self.vault_client.read().await,
+            self.secret_mount.as_str(),
+            &secret_ident_to_key(*secret_id),
+            &secret,
+        )
+        .await
+        .map_err(|err| {
+            ErrorModel::internal(
+                "secret update failure",
+                "SecretUpdateFailed",
+                Some(Box::new(err)),
+            )
+        })?;
+        Ok(version.version)
+    }
+
+    async fn list_secret_versions(&self, secret_id: &SecretIdent) -> Result<Vec<SecretVersion>> {
+        let metadata = self.read_metadata(secret_id).await?;
+
+        let mut versions = metadata
+            .versions
+            .iter()
+            // Destroyed versions can't be read anymore
+            .filter(|(_, v)| !v.destroyed && v.deletion_time.is_empty())
+            .map(|(version, v)| {
+                Ok(SecretVersion {
+                    version: version.parse().map_err(|err| {
+                        ErrorModel::internal(
+                            "secret metadata read failure",
+                            "SecretReadFailed",
+                            Some(Box::new(err)),
+                        )
+                    })?,
+                    created_at: v.created_time.parse().map_err(|err| {
+                        ErrorModel::internal(
+                            "secret metadata read failure",
+                            "SecretReadFailed",
+                            Some(Box::new(err)),
+                        )
+                    })?,
+                    current: false,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        versions.sort_by(|a, b| b.version.cmp(&a.version));
+        for v in &mut versions {
+            v.current = v.version == metadata.current_version;
+        }
+
+        Ok(versions)
+    }
+
+    /// Delete a secret
+    async fn delete_secret(&self, secret_id: &SecretIdent) -> Result<()> {
+        Ok(vaultrs::kv2::delete_metadata(
+            &*self.vault_client.read().await,
+            self.secret_mount.as_str(),
+            &secret_ident_to_key(*secret_id),
+        )
+        .await
+        .map_err(|err| {
+            ErrorModel::internal(
+                "secret deletion failure",
+                "SecretDeletionFailed",
+                Some(Box::new(err)),
+            )
+        })?)
+    }
+}
+
+impl SecretsState {
+    async fn read_metadata(
+        &self,
+        secret_id: &SecretIdent,
+    ) -> Result<vaultrs::api::kv2::responses::ReadSecretMetadataResponse> {
+        Ok(vaultrs::kv2::read_metadata(
             &*self.vault_client.read().await,
             self.secret_mount.as_str(),
             &secret_ident_to_key(*secret_id),
@@ -40,15 +167,22 @@ impl SecretStore for SecretsState {
                 "SecretReadFailed",
                 Some(Box::new(err)),
             ))
-        })?;
+        })?)
+    }
 
+    async fn read_version<S: DeserializeOwned>(
+        &self,
+        secret_id: &SecretIdent,
+        version: u64,
+        metadata: &vaultrs::api::kv2::responses::ReadSecretMetadataResponse,
+    ) -> Result<Secret<S>> {
         Ok(Secret {
             secret_id: *secret_id,
             secret: vaultrs::kv2::read_version::<S>(
                 &*self.vault_client.read().await,
                 self.secret_mount.as_str(),
                 &secret_ident_to_key(*secret_id),
-                metadata.current_version,
+                version,
             )
             .await
             .map_err(|err| {
@@ -58,6 +192,7 @@ impl SecretStore for SecretsState {
                     Some(Box::new(err)),
                 ))
             })?,
+            version,
             created_at: metadata.created_time.parse().map_err(|err| {
                 IcebergErrorResponse::from(ErrorModel::internal(
                     "secret metadata read failure",
@@ -74,46 +209,6 @@ impl SecretStore for SecretsState {
             })?),
         })
     }
-
-    /// Create a new secret
-    async fn create_secret<S: Send + Sync + Serialize + std::fmt::Debug>(
-        &self,
-        secret: S,
-    ) -> Result<SecretIdent> {
-        let secret_id = SecretIdent::from(Uuid::now_v7());
-        vaultrs::kv2::set(
-            &*self.vault_client.read().await,
-            self.secret_mount.as_str(),
-            &secret_ident_to_key(secret_id),
-            &secret,
-        )
-        .await
-        .map_err(|err| {
-            ErrorModel::internal(
-                "secret creation failure",
-                "SecretCreationFailed",
-                Some(Box::new(err)),
-            )
-        })?;
-        Ok(secret_id)
-    }
-
-    /// Delete a secret
-    async fn delete_secret(&self, secret_id: &SecretIdent) -> Result<()> {
-        Ok(vaultrs::kv2::delete_metadata(
-            &*self.vault_client.read().await,
-            self.secret_mount.as_str(),
-            &secret_ident_to_key(*secret_id),
-        )
-        .await
-        .map_err(|err| {
-            ErrorModel::internal(
-                "secret deletion failure",
-                "SecretDeletionFailed",
-                Some(Box::new(err)),
-            )
-        })?)
-    }
 }
 
 #[derive(Clone)]
@@ -123,89 +218,185 @@ pub struct SecretsState {
     // bad since we only need to read the client for most operations.
     vault_client: Arc<RwLock<VaultClient>>,
     secret_mount: String,
-    vault_user: String,
-    vault_password: String,
+    auth: KV2Auth,
+    token_lease: Arc<RwLock<TokenLease>>,
     health: Arc<RwLock<Vec<Health>>>,
 }
 
+/// Lease of the vault token currently set on the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TokenLease {
+    /// `None` if the token never expires, e.g. root tokens.
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    renewable: bool,
+}
+
+impl TokenLease {
+    fn new(lease_duration: u64, renewable: bool) -> Self {
+        // Clamp absurd durations instead of overflowing the timestamp
+        let lease_duration = u32::try_from(lease_duration).unwrap_or(u32::MAX);
+        Self {
+            expires_at: (lease_duration > 0)
+                .then(|| chrono::Utc::now() + chrono::Duration::seconds(i64::from(lease_duration))),
+            renewable,
+        }
+    }
+
+    fn remaining(&self) -> Option<std::time::Duration> {
+        self.expires_at.map(|expires_at| {
+            (expires_at - chrono::Utc::now())
+                .to_std()
+                .unwrap_or_default()
+        })
+    }
+
+    fn is_expired(&self) -> bool {
+        self.remaining().is_some_and(|r| r.is_zero())
+    }
+
+    /// A renewal that results in less than this is considered capped by the max TTL
+    /// of the token, we login again instead.
+    fn expires_soon(&self) -> bool {
+        self.remaining().is_some_and(|r| r < MIN_TOKEN_LEASE)
+    }
+
+    /// Refresh after two thirds of the remaining lease.
+    fn refresh_in(&self) -> Option<std::time::Duration> {
+        self.remaining()
+            .map(|r| (r * 2 / 3).max(std::time::Duration::from_secs(1)))
+    }
+}
+
+const MIN_TOKEN_LEASE: std::time::Duration = std::time::Duration::from_secs(30);
+const REFRESH_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
 impl SecretsState {
     /// Creates a new `SecretsState` from a `VaultConfig`
     ///
-    /// This constructor spawns a background task that refreshes the login token.
+    /// This constructor spawns a background task that renews the token and
+    /// logs in again once the token can't be renewed anymore.
     ///
     /// # Errors
     /// Fails if the initial login fails
-    pub async fn from_config(
-        KV2Config {
-            url,
-            user,
-            password,
-            secret_mount,
-        }: &KV2Config,
-    ) -> anyhow::Result<Self> {
+    pub async fn from_config(config: &KV2Config) -> anyhow::Result<Self> {
         let slf = Self {
             vault_client: Arc::new(RwLock::new(VaultClient::new(
                 vaultrs::client::VaultClientSettingsBuilder::default()
-                    .address(url.clone())
+                    .address(config.url.clone())
                     .build()?,
             )?)),
-            secret_mount: secret_mount.clone(),
-            vault_user: user.clone(),
-            vault_password: password.clone(),
-            health: Arc::default(),
+            secret_mount: config.secret_mount.clone(),
+            auth: config.auth_method()?,
+            token_lease: Arc::new(RwLock::new(TokenLease::new(0, false))),
+            health: Arc::new(RwLock::new(vec![
+                Health::now("vault", HealthStatus::Unknown),
+                Health::now("vault_token", HealthStatus::Unknown),
+            ])),
         };
-        slf.login_task().await?;
+        let lease = slf.login().await.context("Failed to get initial login")?;
+        *slf.token_lease.write().await = lease;
+        slf.token_refresh_task();
         Ok(slf)
     }
 
-    async fn login_task(&self) -> anyhow::Result<tokio::task::JoinHandle<()>> {
-        let login = UserpassLogin::new(self.vault_user.as_str(), self.vault_password.as_str());
-        let client_handle = self.vault_client.clone();
-        let mut sleep = Self::refresh_login(&login, client_handle.clone())
-            .await
-            .context("Failed to get initial login")?;
-
-        Ok(tokio::task::spawn(async move {
+    fn token_refresh_task(&self) -> tokio::task::JoinHandle<()> {
+        let slf = self.clone();
+        tokio::task::spawn(async move {
             loop {
-                tracing::debug!("Refreshing token");
-                sleep.await;
-                sleep = Self::refresh_login(&login, client_handle.clone())
-                    .await
-                    .unwrap_or_else(|e| {
+                let lease = *slf.token_lease.read().await;
+                let Some(refresh_in) = lease.refresh_in() else {
+                    tracing::debug!("Vault token does not expire, stopping token refresh");
+                    return;
+                };
+                tokio::time::sleep(refresh_in).await;
+
+                match slf.refresh_token(lease).await {
+                    Ok(lease) => {
+                        *slf.token_lease.write().await = lease;
+                        tracing::debug!("Token refreshed");
+                    }
+                    Err(e) => {
                         tracing::error!(?e, "Failed to refresh token: {:?}", e);
-                        tokio::time::sleep(std::time::Duration::from_secs(1))
-                    });
-                tracing::debug!("Token refreshed");
+                        tokio::time::sleep(REFRESH_RETRY_INTERVAL).await;
+                    }
+                }
             }
-        }))
+        })
     }
 
-    async fn refresh_login(
-        login: &UserpassLogin,
-        client_handle: Arc<RwLock<VaultClient>>,
-    ) -> anyhow::Result<Sleep> {
-        tracing::debug!("Refreshing token");
+    /// Renew the token if possible, login again otherwise.
+    async fn refresh_token(&self, lease: TokenLease) -> anyhow::Result<TokenLease> {
+        if lease.renewable {
+            let renewed = {
+                let handle = self.vault_client.read().await;
+                vaultrs::token::renew_self(&*handle, None).await
+            };
+            match renewed {
+                Ok(auth) => {
+                    let renewed = TokenLease::new(auth.lease_duration, auth.renewable);
+                    if !renewed.expires_soon() {
+                        return Ok(renewed);
+                    }
+                    tracing::debug!("Token reached its max TTL, logging in again");
+                }
+                Err(e) => {
+                    tracing::warn!(?e, "Failed to renew token, logging in again: {:?}", e);
+                }
+            }
+        }
+        self.login().await
+    }
 
-        let login_result = {
-            let handle = client_handle.read().await;
-            login.login(&*handle, "userpass").await
+    /// Login with the configured auth method and set the new token on the client.
+    async fn login(&self) -> anyhow::Result<TokenLease> {
+        tracing::debug!("Logging in to vault");
+
+        let auth = {
+            let handle = self.vault_client.read().await;
+            match &self.auth {
+                KV2Auth::Userpass {
+                    user,
+                    password,
+                    mount,
+                } => vaultrs::auth::userpass::login(&*handle, mount, user, password).await?,
+                KV2Auth::Kubernetes {
+                    role,
+                    jwt_path,
+                    mount,
+                } => {
+                    // Projected service account tokens are rotated by the kubelet,
+                    // so the token is read on every login.
+                    let jwt = tokio::fs::read_to_string(jwt_path).await.with_context(|| {
+                        format!(
+                            "Failed to read kubernetes service account token from {}",
+                            jwt_path.display()
+                        )
+                    })?;
+                    vaultrs::auth::kubernetes::login(&*handle, mount, role, jwt.trim()).await?
+                }
+                KV2Auth::AppRole {
+                    role_id,
+                    secret_id,
+                    mount,
+                } => vaultrs::auth::approle::login(&*handle, mount, role_id, secret_id).await?,
+                KV2Auth::Token { token } => {
+                    drop(handle);
+                    self.vault_client.write().await.set_token(token);
+                    let handle = self.vault_client.read().await;
+                    let lookup = vaultrs::token::lookup_self(&*handle)
+                        .await
+                        .context("Failed to lookup vault token")?;
+                    return Ok(TokenLease::new(lookup.ttl, lookup.renewable));
+                }
+            }
         };
 
-        match login_result {
-            Ok(token) => {
-                let sleep_duration =
-                    std::time::Duration::from_secs(token.lease_duration.saturating_sub(10));
-                let sleep = tokio::time::sleep(sleep_duration);
-                let mut handle = client_handle.write().await;
-                handle.set_token(token.client_token.as_str());
-                tracing::debug!("Token refreshed");
-                Ok(sleep)
-            }
-            Err(e) => {
-                tracing::error!("Failed to refresh token: {:?}", e);
-                Err(e.into())
-            }
-        }
+        self.vault_client
+            .write()
+            .await
+            .set_token(auth.client_token.as_str());
+        tracing::debug!("Logged in to vault");
+        Ok(TokenLease::new(auth.lease_duration, auth.renewable))
     }
 }
 
@@ -216,24 +407,32 @@ impl HealthExt for SecretsState {
     }
 
     async fn update_health(&self) {
-        let handle = self.vault_client.read().await;
-        let t = vaultrs::sys::health(&*handle).await;
-        match t {
-            Ok(_) => {
-                tracing::debug!("Vault is healthy");
-                self.health
-                    .write()
-                    .await
-                    .push(Health::now("vault", HealthStatus::Healthy));
+        let vault = {
+            let handle = self.vault_client.read().await;
+            match vaultrs::sys::health(&*handle).await {
+                Ok(_) => {
+                    tracing::debug!("Vault is healthy");
+                    HealthStatus::Healthy
+                }
+                Err(err) => {
+                    tracing::error!(?err, "Vault is unhealthy");
+                    HealthStatus::Unhealthy
+                }
             }
-            Err(err) => {
-                tracing::error!(?err, "Vault is unhealthy");
-                self.health
-                    .write()
-                    .await
-                    .push(Health::now("vault", HealthStatus::Unhealthy));
-            }
-        }
+        };
+        let token = if self.token_lease.read().await.is_expired() {
+            tracing::error!("Vault token is expired");
+            HealthStatus::Unhealthy
+        } else {
+            HealthStatus::Healthy
+        };
+
+        let mut lock = self.health.write().await;
+        lock.clear();
+        lock.extend([
+            Health::now("vault", vault),
+            Health::now("vault_token", token),
+        ]);
     }
 }
 
@@ -242,8 +441,8 @@ impl std::fmt::Debug for SecretsState {
         f.debug_struct("SecretsState")
             .field("vault_client", &"VaultClient")
             .field("secret_mount", &self.secret_mount)
-            .field("vault_user", &self.vault_user)
-            .field("vault_password", &"REDACTED")
+            .field("auth", &self.auth)
+            .field("token_lease", &self.token_lease)
             .field("health", &self.health)
             .finish()
     }
@@ -255,8 +454,34 @@ fn secret_ident_to_key(secret_id: SecretIdent) -> String {
 
 #[cfg(test)]
 mod tests {
+    use super::TokenLease;
     use needs_env_var::needs_env_var;
 
+    #[test]
+    fn test_token_lease() {
+        let lease = TokenLease::new(0, false);
+        assert!(lease.expires_at.is_none());
+        assert!(!lease.is_expired());
+        assert!(lease.refresh_in().is_none());
+
+        let lease = TokenLease::new(3600, true);
+        assert!(!lease.is_expired());
+        assert!(!lease.expires_soon());
+        let refresh_in = lease.refresh_in().unwrap();
+        assert!(refresh_in <= std::time::Duration::from_secs(2400));
+        assert!(refresh_in > std::time::Duration::from_secs(2300));
+
+        let lease = TokenLease::new(10, true);
+        assert!(lease.expires_soon());
+
+        let lease = TokenLease {
+            expires_at: Some(chrono::Utc::now() - chrono::Duration::seconds(1)),
+            renewable: true,
+        };
+        assert!(lease.is_expired());
+        assert_eq!(lease.refresh_in(), Some(std::time::Duration::from_secs(1)));
+    }
+
     #[needs_env_var(TEST_KV2 = 1)]
     mod kv2 {
         use crate::service::storage::{S3Credential, StorageCredential};
@@ -286,6 +511,47 @@ mod tests {
             assert_eq!(read_secret.secret, secret);
         }
 
+        #[tokio::test]
+        async fn test_update_secret_keeps_versions() {
+            let state = SecretsState::from_config(CONFIG.kv2.as_ref().unwrap())
+                .await
+                .unwrap();
+
+            let first: StorageCredential = S3Credential::AccessKey {
+                aws_access_key_id: "first access key".to_string(),
+                aws_secret_access_key: "first secret key".to_string(),
+            }
+            .into();
+            let second: StorageCredential = S3Credential::AccessKey {
+                aws_access_key_id: "second access key".to_string(),
+                aws_secret_access_key: "second secret key".to_string(),
+            }
+            .into();
+
+            let secret_id = state.create_secret(first.clone()).await.unwrap();
+            let version = state
+                .update_secret(&secret_id, second.clone())
+                .await
+                .unwrap();
+            assert_eq!(version, 2);
+
+            let current = state
+                .get_secret_by_id::<StorageCredential>(&secret_id)
+                .await
+                .unwrap();
+            assert_eq!(current.secret, second);
+
+            let old = state
+                .get_secret_version::<StorageCredential>(&secret_id, 1)
+                .await
+                .unwrap();
+            assert_eq!(old.secret, first);
+
+            let versions = state.list_secret_versions(&secret_id).await.unwrap();
+            assert_eq!(versions.len(), 2);
+            assert!(versions[0].current);
+        }
+
         #[tokio::test]
         async fn test_delete_secret() {
             let state = SecretsState::from_config(CONFIG.kv2.as_ref().expect("vault cfg missing"))
@@ -5,6 +5,9 @@ pub const DEFAULT_PROJECT_ID: uuid::Uuid = uuid::uuid!("00000000-0000-0000-0000-
 pub mod postgres;
 
 mod authz;
+pub mod cedar;
+pub mod file;
 pub mod kv2;
+pub mod openfga;
 
 pub use authz::{AllowAllAuthState, AllowAllAuthZHandler};
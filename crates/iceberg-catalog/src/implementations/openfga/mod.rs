@@ -0,0 +1,1462 @@
+//! `AuthZHandler` backed by an [OpenFGA](https://openfga.dev) store.
+//!
+//! Every check is translated into an OpenFGA `Check` request against the reference
+//! model in `model.fga`. The catalog maintains the object hierarchy - warehouses,
+//! namespaces and tabulars with their parents - by writing tuples when objects are
+//! created or dropped. All objects are keyed by their id, so grants never apply to
+//! another object with the same name. Permissions of users and groups are managed in
+//! OpenFGA directly.
+use crate::api::iceberg::v1::NamespaceIdent;
+use crate::api::{ErrorModel, IcebergErrorResponse, Result};
+use crate::config::OpenFGAConfig;
+use crate::implementations::postgres::dbutils::DBErrorHandler as _;
+use crate::implementations::DEFAULT_PROJECT_ID;
+use crate::request_metadata::RequestMetadata;
+use crate::service::auth::{AuthConfigHandler, AuthZHandler, UserWarehouse};
+use crate::service::health::{Health, HealthExt, HealthStatus};
+use crate::service::tabular_idents::TabularIdentUuid;
+use crate::service::{NamespaceIdentUuid, TableIdentUuid};
+use crate::{ProjectIdent, WarehouseIdent};
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use url::Url;
+
+/// Reference authorization model in the OpenFGA DSL.
+pub const AUTHORIZATION_MODEL_DSL: &str = include_str!("model.fga");
+/// Reference authorization model in the JSON format of the OpenFGA API.
+pub const AUTHORIZATION_MODEL_JSON: &str = include_str!("model.json");
+
+/// Maximum number of concurrent `Check` requests when filtering listings.
+const CHECK_CONCURRENCY: usize = 10;
+
+#[derive(Debug, Clone, Copy, strum_macros::Display)]
+#[strum(serialize_all = "snake_case")]
+enum Relation {
+    Reader,
+    Writer,
+    Admin,
+    Owner,
+    Project,
+    Warehouse,
+    Parent,
+    Namespace,
+    Member,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct TupleKey {
+    user: String,
+    relation: String,
+    object: String,
+}
+
+impl TupleKey {
+    fn new(user: impl Into<String>, relation: Relation, object: impl Into<String>) -> Self {
+        Self {
+            user: user.into(),
+            relation: relation.to_string(),
+            object: object.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TupleKeys {
+    tuple_keys: Vec<TupleKey>,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckRequest<'a> {
+    tuple_key: TupleKey,
+    contextual_tuples: TupleKeys,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorization_model_id: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckResponse {
+    allowed: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct WriteRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    writes: Option<TupleKeys>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deletes: Option<TupleKeys>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorization_model_id: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReadRequest {
+    tuple_key: ReadTupleKey,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    continuation_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReadTupleKey {
+    object: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadResponse {
+    tuples: Vec<ReadTuple>,
+    #[serde(default)]
+    continuation_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadTuple {
+    key: TupleKey,
+}
+
+#[derive(Debug, Serialize)]
+struct ListObjectsRequest<'a> {
+    r#type: &'a str,
+    relation: String,
+    user: String,
+    contextual_tuples: TupleKeys,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorization_model_id: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListObjectsResponse {
+    objects: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenFGAState {
+    client: reqwest::Client,
+    endpoint: Url,
+    store_id: String,
+    authorization_model_id: Option<String>,
+    groups_claim: String,
+    read_pool: PgPool,
+    health: Arc<RwLock<Vec<Health>>>,
+}
+
+impl OpenFGAState {
+    /// The pool is used to resolve the ids of namespaces in checks.
+    ///
+    /// # Errors
+    /// Fails if the client cannot be built or the endpoint is not a valid base URL.
+    pub fn from_config(config: &OpenFGAConfig, read_pool: PgPool) -> anyhow::Result<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(token) = &config.api_token {
+            let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}"))?;
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()?;
+
+        Ok(Self {
+            client,
+            endpoint: config.endpoint.clone(),
+            store_id: config.store_id.clone(),
+            authorization_model_id: config.authorization_model_id.clone(),
+            groups_claim: config.groups_claim.clone(),
+            read_pool,
+            health: Arc::new(RwLock::new(vec![Health::now(
+                "openfga",
+                HealthStatus::Unknown,
+            )])),
+        })
+    }
+
+    fn store_url(&self, path: &str) -> std::result::Result<Url, url::ParseError> {
+        self.endpoint
+            .join(&format!("stores/{}{path}", self.store_id))
+    }
+
+    async fn post<B: Serialize + Sync, R: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<R> {
+        let url = self.store_url(path).map_err(|e| {
+            ErrorModel::internal("Invalid OpenFGA URL", "OpenFGAError", Some(Box::new(e)))
+        })?;
+        let response = self.client.post(url).json(body).send().await.map_err(|e| {
+            ErrorModel::internal("Failed to reach OpenFGA", "OpenFGAError", Some(Box::new(e)))
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ErrorModel::internal(
+                format!("OpenFGA request failed with status {status}"),
+                "OpenFGAError",
+                None,
+            )
+            .append_detail(body)
+            .into());
+        }
+
+        response.json().await.map_err(|e| {
+            ErrorModel::internal(
+                "Failed to parse OpenFGA response",
+                "OpenFGAError",
+                Some(Box::new(e)),
+            )
+            .into()
+        })
+    }
+
+    async fn check(&self, user: &User, relation: Relation, object: &str) -> Result<bool> {
+        let response: CheckResponse = self
+            .post(
+                "/check",
+                &CheckRequest {
+                    tuple_key: TupleKey::new(user.id(), relation, object),
+                    contextual_tuples: user.contextual_tuples(),
+                    authorization_model_id: self.authorization_model_id.as_deref(),
+                },
+            )
+            .await?;
+        Ok(response.allowed)
+    }
+
+    /// Checks the relation on many objects concurrently, returns the items that are allowed.
+    async fn check_many<T: Send>(
+        &self,
+        user: &User,
+        relation: Relation,
+        objects: Vec<(T, String)>,
+    ) -> Result<Vec<T>> {
+        futures::stream::iter(objects)
+            .map(|(item, object)| async move {
+                let allowed = self.check(user, relation, &object).await?;
+                Ok::<_, IcebergErrorResponse>(allowed.then_some(item))
+            })
+            .buffer_unordered(CHECK_CONCURRENCY)
+            .try_filter_map(|item| async move { Ok(item) })
+            .try_collect()
+            .await
+    }
+
+    async fn list_objects(
+        &self,
+        user: &User,
+        relation: Relation,
+        object_type: &str,
+    ) -> Result<Vec<String>> {
+        let response: ListObjectsResponse = self
+            .post(
+                "/list-objects",
+                &ListObjectsRequest {
+                    r#type: object_type,
+                    relation: relation.to_string(),
+                    user: user.id(),
+                    contextual_tuples: user.contextual_tuples(),
+                    authorization_model_id: self.authorization_model_id.as_deref(),
+                },
+            )
+            .await?;
+        Ok(response.objects)
+    }
+
+    async fn write(&self, writes: Vec<TupleKey>, deletes: Vec<TupleKey>) -> Result<()> {
+        if writes.is_empty() && deletes.is_empty() {
+            return Ok(());
+        }
+
+        let _: serde_json::Value = self
+            .post(
+                "/write",
+                &WriteRequest {
+                    writes: (!writes.is_empty()).then_some(TupleKeys { tuple_keys: writes }),
+                    deletes: (!deletes.is_empty()).then_some(TupleKeys {
+                        tuple_keys: deletes,
+                    }),
+                    authorization_model_id: self.authorization_model_id.as_deref(),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// All tuples with the given object.
+    async fn read(&self, object: &str) -> Result<Vec<TupleKey>> {
+        let mut tuples = vec![];
+        let mut continuation_token = None;
+        loop {
+            let response: ReadResponse = self
+                .post(
+                    "/read",
+                    &ReadRequest {
+                        tuple_key: ReadTupleKey {
+                            object: object.to_string(),
+                        },
+                        continuation_token,
+                    },
+                )
+                .await?;
+            tuples.extend(response.tuples.into_iter().map(|t| t.key));
+            if response.continuation_token.is_empty() {
+                return Ok(tuples);
+            }
+            continuation_token = Some(response.continuation_token);
+        }
+    }
+
+    /// Deletes all tuples of an object that is gone.
+    async fn delete_object(&self, object: &str) -> Result<()> {
+        let tuples = self.read(object).await?;
+        // OpenFGA limits the number of tuples per write request
+        for chunk in tuples.chunks(100) {
+            self.write(vec![], chunk.to_vec()).await?;
+        }
+        Ok(())
+    }
+
+    async fn namespace_id(
+        &self,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+    ) -> Result<Option<NamespaceIdentUuid>> {
+        let namespace_id = sqlx::query_scalar!(
+            r#"
+            SELECT namespace_id
+            FROM namespace
+            WHERE warehouse_id = $1 AND namespace_name = $2
+            "#,
+            *warehouse_id,
+            &**namespace
+        )
+        .fetch_optional(&self.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching namespace".to_string()))?;
+        Ok(namespace_id.map(NamespaceIdentUuid::from))
+    }
+
+    /// The object to check for a namespace. Namespaces that don't exist are checked on
+    /// their warehouse, so that missing namespaces result in a 404 for authorized users.
+    async fn namespace_or_warehouse_object(
+        &self,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+    ) -> Result<String> {
+        let namespace_id = match namespace {
+            Some(namespace) => self.namespace_id(warehouse_id, namespace).await?,
+            None => None,
+        };
+        Ok(namespace_id.map_or_else(|| warehouse_object(warehouse_id), namespace_object))
+    }
+
+    /// The object to check for tables and views: the tabular itself if it exists, otherwise
+    /// its namespace or warehouse, so that missing tabulars result in a 404 for authorized users.
+    async fn tabular_or_parent_object(
+        &self,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        tabular_id: Option<TableIdentUuid>,
+    ) -> Result<String> {
+        match tabular_id {
+            Some(tabular_id) => Ok(tabular_object(tabular_id)),
+            None => {
+                self.namespace_or_warehouse_object(warehouse_id, namespace)
+                    .await
+            }
+        }
+    }
+
+    /// The tuple linking a namespace to its parent namespace or warehouse.
+    async fn namespace_parent(
+        &self,
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        namespace: &NamespaceIdent,
+    ) -> Result<TupleKey> {
+        let object = namespace_object(namespace_id);
+        match namespace.parent() {
+            Some(parent) => {
+                let parent_id =
+                    self.namespace_id(warehouse_id, &parent)
+                        .await?
+                        .ok_or_else(|| {
+                            ErrorModel::not_found(
+                                format!("Parent namespace {parent:?} does not exist"),
+                                "NamespaceNotFound",
+                                None,
+                            )
+                        })?;
+                Ok(TupleKey::new(
+                    namespace_object(parent_id),
+                    Relation::Parent,
+                    object,
+                ))
+            }
+            None => Ok(TupleKey::new(
+                warehouse_object(warehouse_id),
+                Relation::Warehouse,
+                object,
+            )),
+        }
+    }
+
+    async fn require(
+        &self,
+        metadata: &RequestMetadata,
+        relation: Relation,
+        object: &str,
+    ) -> Result<()> {
+        let user = User::from_metadata(metadata, &self.groups_claim)?;
+        if self.check(&user, relation, object).await? {
+            Ok(())
+        } else {
+            Err(ErrorModel::forbidden(
+                format!("Relation '{relation}' on '{object}' is required for this operation"),
+                "Forbidden",
+                None,
+            )
+            .into())
+        }
+    }
+}
+
+#[async_trait]
+impl HealthExt for OpenFGAState {
+    async fn health(&self) -> Vec<Health> {
+        self.health.read().await.clone()
+    }
+
+    async fn update_health(&self) {
+        let url = match self.store_url("") {
+            Ok(url) => url,
+            Err(e) => {
+                tracing::warn!(?e, "Invalid OpenFGA URL");
+                return;
+            }
+        };
+        let status = match self.client.get(url).send().await {
+            Ok(response) if response.status().is_success() => HealthStatus::Healthy,
+            Ok(response) => {
+                tracing::warn!(status = %response.status(), "OpenFGA store is unhealthy");
+                HealthStatus::Unhealthy
+            }
+            Err(e) => {
+                tracing::warn!(?e, "OpenFGA is unreachable");
+                HealthStatus::Unhealthy
+            }
+        };
+        let mut lock = self.health.write().await;
+        lock.clear();
+        lock.push(Health::now("openfga", status));
+    }
+}
+
+/// The caller of a request as an OpenFGA user.
+#[derive(Debug, Clone)]
+struct User {
+    /// Formatted as `<issuer>|<subject>`.
+    principal: String,
+    groups: Vec<String>,
+}
+
+impl User {
+    fn from_metadata(metadata: &RequestMetadata, groups_claim: &str) -> Result<Self> {
+        match &metadata.auth_details {
+            Some(auth_details) => {
+                let claims = auth_details.claims();
+                let groups = match claims.other.get(groups_claim) {
+                    Some(serde_json::Value::Array(groups)) => groups
+                        .iter()
+                        .filter_map(|g| g.as_str().map(str::to_string))
+                        .collect(),
+                    Some(serde_json::Value::String(group)) => vec![group.clone()],
+                    _ => vec![],
+                };
+                Ok(Self {
+                    principal: auth_details.principal_id().to_string(),
+                    groups,
+                })
+            }
+            None => Err(ErrorModel::builder()
+                .code(StatusCode::UNAUTHORIZED.into())
+                .message("Authentication is required".to_string())
+                .r#type("UnauthorizedError".to_string())
+                .build()
+                .into()),
+        }
+    }
+
+    fn id(&self) -> String {
+        format!("user:{}", self.principal)
+    }
+
+    /// Group memberships are taken from the token, not from the store.
+    fn contextual_tuples(&self) -> TupleKeys {
+        TupleKeys {
+            tuple_keys: self
+                .groups
+                .iter()
+                .map(|group| {
+                    TupleKey::new(
+                        self.id(),
+                        Relation::Member,
+                        format!("group:{}", urlencoding::encode(group)),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+fn project_object(project_id: ProjectIdent) -> String {
+    format!("project:{project_id}")
+}
+
+fn warehouse_object(warehouse_id: WarehouseIdent) -> String {
+    format!("warehouse:{warehouse_id}")
+}
+
+/// Namespaces are keyed by id, so that grants don't survive when a namespace is
+/// dropped and recreated with the same name.
+fn namespace_object(namespace_id: NamespaceIdentUuid) -> String {
+    format!("namespace:{namespace_id}")
+}
+
+fn tabular_object(tabular_id: TableIdentUuid) -> String {
+    format!("tabular:{tabular_id}")
+}
+
+fn ids_from_objects<T: FromStr>(objects: Vec<String>, object_type: &str) -> HashSet<T> {
+    let prefix = format!("{object_type}:");
+    objects
+        .into_iter()
+        .filter_map(|o| o.strip_prefix(&prefix).and_then(|id| id.parse().ok()))
+        .collect()
+}
+
+#[derive(Clone, Debug, Default)]
+/// Authorization decisions are delegated to OpenFGA.
+pub struct OpenFGAAuthZHandler;
+
+#[async_trait]
+impl AuthConfigHandler<OpenFGAAuthZHandler> for OpenFGAAuthZHandler {
+    async fn get_and_validate_user_warehouse(
+        state: OpenFGAState,
+        metadata: &RequestMetadata,
+    ) -> Result<UserWarehouse> {
+        User::from_metadata(metadata, &state.groups_claim)?;
+        if let Some(user_warehouse) = UserWarehouse::from_catalog_token(metadata) {
+            // Permissions might have been revoked since the token was issued
+            if let Some(warehouse_id) = user_warehouse.warehouse_id {
+                Self::check_user_get_config_for_warehouse(state, warehouse_id, metadata).await?;
+            }
+            return Ok(user_warehouse);
+        }
+        Ok(UserWarehouse {
+            project_id: Some(
+                UserWarehouse::project_from_token(metadata)
+                    .unwrap_or(ProjectIdent::from(DEFAULT_PROJECT_ID)),
+            ),
+            warehouse_id: None,
+        })
+    }
+
+    async fn exchange_token_for_warehouse(
+        _: OpenFGAState,
+        _: &RequestMetadata,
+        _: &ProjectIdent,
+        _: WarehouseIdent,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn check_list_warehouse_in_project(
+        state: OpenFGAState,
+        project_id: &ProjectIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        <Self as AuthZHandler>::check_list_warehouse_in_project(metadata, *project_id, state)
+            .await
+            .map(|_| ())
+    }
+
+    async fn check_user_get_config_for_warehouse(
+        state: OpenFGAState,
+        warehouse_id: WarehouseIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        let object = warehouse_object(warehouse_id);
+        state.require(metadata, Relation::Reader, &object).await
+    }
+}
+
+#[async_trait]
+impl AuthZHandler for OpenFGAAuthZHandler {
+    type State = OpenFGAState;
+
+    async fn check_list_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, parent)
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_create_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, parent)
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    async fn check_load_namespace_metadata(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, Some(namespace))
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_namespace_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, Some(namespace))
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_drop_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, Some(namespace))
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    async fn check_update_namespace_properties(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, Some(namespace))
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    async fn check_create_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, Some(namespace))
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    async fn check_list_tables(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, Some(namespace))
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_load_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, namespace, table)
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_rename_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        source: Option<TableIdentUuid>,
+        destination: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, None, source)
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await?;
+        Self::check_create_table(metadata, warehouse_id, destination, state).await
+    }
+
+    async fn check_table_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, namespace, table)
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_drop_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, None, table)
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    async fn check_commit_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        table: Option<TableIdentUuid>,
+        namespace: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, namespace, table)
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    // ---------------- Management API ----------------
+    /// Any authenticated user may create a project and becomes its admin.
+    /// The ID is always generated by the catalog, otherwise users could claim
+    /// projects that are referenced elsewhere but don't exist yet.
+    async fn check_create_project(
+        metadata: &RequestMetadata,
+        _project_id: ProjectIdent,
+        id_requested: bool,
+        state: Self::State,
+    ) -> Result<()> {
+        User::from_metadata(metadata, &state.groups_claim)?;
+        if id_requested {
+            return Err(ErrorModel::forbidden(
+                "The ID of a project can't be chosen with the openfga authorization backend",
+                "ProjectIdNotAllowed",
+                None,
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    async fn check_rename_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = project_object(project_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_delete_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = project_object(project_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_deactivate_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = project_object(project_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_activate_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = project_object(project_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_create_warehouse(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = project_object(*project_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_list_projects(
+        metadata: &RequestMetadata,
+        state: Self::State,
+    ) -> Result<Option<HashSet<ProjectIdent>>> {
+        let user = User::from_metadata(metadata, &state.groups_claim)?;
+        let projects = state
+            .list_objects(&user, Relation::Reader, "project")
+            .await?;
+        Ok(Some(
+            ids_from_objects::<uuid::Uuid>(projects, "project")
+                .into_iter()
+                .map(ProjectIdent::from)
+                .collect(),
+        ))
+    }
+
+    async fn check_list_warehouse_in_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<Option<HashSet<WarehouseIdent>>> {
+        let user = User::from_metadata(metadata, &state.groups_claim)?;
+        if state
+            .check(&user, Relation::Reader, &project_object(project_id))
+            .await?
+        {
+            return Ok(None);
+        }
+
+        // Warehouses of other projects are filtered by the catalog
+        let warehouses = state
+            .list_objects(&user, Relation::Reader, "warehouse")
+            .await?;
+        Ok(Some(
+            ids_from_objects::<uuid::Uuid>(warehouses, "warehouse")
+                .into_iter()
+                .map(WarehouseIdent::from)
+                .collect(),
+        ))
+    }
+
+    async fn check_delete_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = warehouse_object(warehouse_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_get_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = warehouse_object(warehouse_id);
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_rename_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = warehouse_object(warehouse_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_deactivate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = warehouse_object(warehouse_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_activate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = warehouse_object(warehouse_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_update_storage(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = warehouse_object(warehouse_id);
+        state.require(metadata, Relation::Admin, &object).await
+    }
+
+    async fn check_create_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, Some(namespace))
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    async fn check_drop_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, None, view.copied())
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    async fn check_load_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, namespace, view.copied())
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_commit_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        view: Option<&TableIdentUuid>,
+        namespace: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, namespace, view.copied())
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await
+    }
+
+    async fn check_rename_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        source: Option<&TableIdentUuid>,
+        destination: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, None, source.copied())
+            .await?;
+        state.require(metadata, Relation::Writer, &object).await?;
+        Self::check_create_view(metadata, warehouse_id, destination, state).await
+    }
+
+    async fn check_list_views(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .namespace_or_warehouse_object(warehouse_id, Some(namespace))
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    async fn check_view_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = state
+            .tabular_or_parent_object(warehouse_id, namespace, view.copied())
+            .await?;
+        state.require(metadata, Relation::Reader, &object).await
+    }
+
+    // ---------------- Visibility ----------------
+    async fn filter_visible_tabulars(
+        metadata: &RequestMetadata,
+        _warehouse_id: WarehouseIdent,
+        _namespace: &NamespaceIdent,
+        tabulars: Vec<TabularIdentUuid>,
+        state: Self::State,
+    ) -> Result<HashSet<TabularIdentUuid>> {
+        let user = User::from_metadata(metadata, &state.groups_claim)?;
+        let objects = tabulars
+            .into_iter()
+            .map(|tabular| (tabular, tabular_object(TableIdentUuid::from(*tabular))))
+            .collect();
+        let visible = state.check_many(&user, Relation::Reader, objects).await?;
+        Ok(visible.into_iter().collect())
+    }
+
+    async fn filter_visible_namespaces(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespaces: Vec<NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<HashSet<NamespaceIdent>> {
+        let user = User::from_metadata(metadata, &state.groups_claim)?;
+        // Namespaces dropped since they were listed are left out
+        let objects = futures::stream::iter(namespaces)
+            .map(|namespace| {
+                let state = &state;
+                async move {
+                    let namespace_id = state.namespace_id(warehouse_id, &namespace).await?;
+                    Ok::<_, IcebergErrorResponse>(
+                        namespace_id.map(|id| (namespace, namespace_object(id))),
+                    )
+                }
+            })
+            .buffer_unordered(CHECK_CONCURRENCY)
+            .try_filter_map(|item| async move { Ok(item) })
+            .try_collect()
+            .await?;
+        let visible = state.check_many(&user, Relation::Reader, objects).await?;
+        Ok(visible.into_iter().collect())
+    }
+
+    // ---------------- Lifecycle ----------------
+    async fn on_project_created(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let user = User::from_metadata(metadata, &state.groups_claim)?;
+        let admin = TupleKey::new(user.id(), Relation::Admin, project_object(project_id));
+        state.write(vec![admin], vec![]).await
+    }
+
+    async fn on_project_deleted(project_id: ProjectIdent, state: Self::State) -> Result<()> {
+        state.delete_object(&project_object(project_id)).await
+    }
+
+    async fn on_warehouse_created(
+        _metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let tuple = TupleKey::new(
+            project_object(project_id),
+            Relation::Project,
+            warehouse_object(warehouse_id),
+        );
+        state.write(vec![tuple], vec![]).await
+    }
+
+    async fn on_warehouse_deleted(warehouse_id: WarehouseIdent, state: Self::State) -> Result<()> {
+        state.delete_object(&warehouse_object(warehouse_id)).await
+    }
+
+    async fn on_namespace_created(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let user = User::from_metadata(metadata, &state.groups_claim)?;
+        let parent = state
+            .namespace_parent(warehouse_id, namespace_id, namespace)
+            .await?;
+        let owner = TupleKey::new(user.id(), Relation::Owner, namespace_object(namespace_id));
+        state.write(vec![parent, owner], vec![]).await
+    }
+
+    async fn on_namespace_dropped(
+        _warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        state: Self::State,
+    ) -> Result<()> {
+        state.delete_object(&namespace_object(namespace_id)).await
+    }
+
+    async fn on_tabular_created(
+        metadata: &RequestMetadata,
+        _warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        tabular_id: TableIdentUuid,
+        state: Self::State,
+    ) -> Result<()> {
+        let user = User::from_metadata(metadata, &state.groups_claim)?;
+        let object = tabular_object(tabular_id);
+        let parent = TupleKey::new(
+            namespace_object(namespace_id),
+            Relation::Namespace,
+            object.clone(),
+        );
+        let owner = TupleKey::new(user.id(), Relation::Owner, object);
+        state.write(vec![parent, owner], vec![]).await
+    }
+
+    async fn on_tabular_moved(
+        _warehouse_id: WarehouseIdent,
+        tabular_id: TableIdentUuid,
+        source: NamespaceIdentUuid,
+        destination: NamespaceIdentUuid,
+        state: Self::State,
+    ) -> Result<()> {
+        let object = tabular_object(tabular_id);
+        let old = TupleKey::new(
+            namespace_object(source),
+            Relation::Namespace,
+            object.clone(),
+        );
+        let new = TupleKey::new(namespace_object(destination), Relation::Namespace, object);
+        state.write(vec![new], vec![old]).await
+    }
+
+    async fn on_tabular_dropped(
+        _warehouse_id: WarehouseIdent,
+        tabular_id: TableIdentUuid,
+        state: Self::State,
+    ) -> Result<()> {
+        state.delete_object(&tabular_object(tabular_id)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_ids() {
+        let namespace_id = NamespaceIdentUuid::from(uuid::Uuid::nil());
+        assert_eq!(
+            namespace_object(namespace_id),
+            format!("namespace:{namespace_id}")
+        );
+        let tabular_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+        assert_eq!(tabular_object(tabular_id), format!("tabular:{tabular_id}"));
+
+        let project_id = uuid::Uuid::now_v7();
+        let projects = ids_from_objects::<uuid::Uuid>(
+            vec![
+                format!("project:{project_id}"),
+                "project:invalid".to_string(),
+            ],
+            "project",
+        );
+        assert_eq!(projects, HashSet::from([project_id]));
+    }
+
+    #[test]
+    fn test_model_json_is_valid() {
+        let model: serde_json::Value = serde_json::from_str(AUTHORIZATION_MODEL_JSON).unwrap();
+        let types = model["type_definitions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["type"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                "user",
+                "group",
+                "project",
+                "warehouse",
+                "namespace",
+                "tabular"
+            ]
+        );
+    }
+
+    #[needs_env_var::needs_env_var(TEST_OPENFGA = 1)]
+    mod openfga {
+        use super::super::*;
+        use crate::implementations::postgres::namespace::tests::initialize_namespace;
+        use crate::implementations::postgres::warehouse::test::initialize_warehouse;
+        use crate::implementations::postgres::{
+            Catalog as PostgresCatalog, CatalogState, PostgresTransaction,
+        };
+        use crate::service::token_verification::{Aud, AuthDetails, Claims};
+        use crate::service::{Catalog as _, Transaction as _};
+
+        /// Creates a new store with the reference model in the OpenFGA at `OPENFGA_ENDPOINT`.
+        async fn new_state(pool: PgPool) -> OpenFGAState {
+            let endpoint = Url::parse(
+                &std::env::var("OPENFGA_ENDPOINT")
+                    .unwrap_or_else(|_| "http://localhost:8080".to_string()),
+            )
+            .unwrap();
+            let client = reqwest::Client::new();
+            let store: serde_json::Value = client
+                .post(endpoint.join("stores").unwrap())
+                .json(&serde_json::json!({"name": format!("test-{}", uuid::Uuid::now_v7())}))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            let store_id = store["id"].as_str().unwrap().to_string();
+            let model: serde_json::Value = client
+                .post(
+                    endpoint
+                        .join(&format!("stores/{store_id}/authorization-models"))
+                        .unwrap(),
+                )
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(AUTHORIZATION_MODEL_JSON)
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+
+            OpenFGAState::from_config(
+                &OpenFGAConfig {
+                    endpoint,
+                    store_id,
+                    authorization_model_id: model["authorization_model_id"]
+                        .as_str()
+                        .map(str::to_string),
+                    api_token: None,
+                    groups_claim: "groups".to_string(),
+                },
+                pool,
+            )
+            .unwrap()
+        }
+
+        fn metadata(subject: &str, groups: &[&str]) -> RequestMetadata {
+            RequestMetadata {
+                request_id: uuid::Uuid::now_v7(),
+                auth_details: Some(AuthDetails::JWT(Claims {
+                    sub: subject.to_string(),
+                    iss: "test".to_string(),
+                    aud: Aud::String("test".to_string()),
+                    exp: 0,
+                    iat: 0,
+                    other: serde_json::json!({ "groups": groups }),
+                })),
+            }
+        }
+
+        #[sqlx::test]
+        async fn test_hierarchy_and_group_grants(pool: PgPool) {
+            let state = new_state(pool.clone()).await;
+            let catalog_state = CatalogState::from_pools(pool.clone(), pool);
+            let project_id = ProjectIdent::from(uuid::Uuid::now_v7());
+            let warehouse_id =
+                initialize_warehouse(catalog_state.clone(), None, Some(&project_id)).await;
+            let namespace = NamespaceIdent::new("finance".to_string());
+            initialize_namespace(catalog_state.clone(), warehouse_id, &namespace, None).await;
+            let namespace_id = state
+                .namespace_id(warehouse_id, &namespace)
+                .await
+                .unwrap()
+                .unwrap();
+            let tabular_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+            let creator = metadata("creator", &[]);
+            let analyst = metadata("alice", &["analysts"]);
+            let stranger = metadata("bob", &[]);
+
+            // Members of the "analysts" group may read everything in the project
+            state
+                .write(
+                    vec![TupleKey::new(
+                        "group:analysts#member",
+                        Relation::Reader,
+                        project_object(project_id),
+                    )],
+                    vec![],
+                )
+                .await
+                .unwrap();
+
+            OpenFGAAuthZHandler::on_warehouse_created(
+                &creator,
+                project_id,
+                warehouse_id,
+                state.clone(),
+            )
+            .await
+            .unwrap();
+            OpenFGAAuthZHandler::on_namespace_created(
+                &creator,
+                warehouse_id,
+                namespace_id,
+                &namespace,
+                state.clone(),
+            )
+            .await
+            .unwrap();
+            OpenFGAAuthZHandler::on_tabular_created(
+                &creator,
+                warehouse_id,
+                namespace_id,
+                tabular_id,
+                state.clone(),
+            )
+            .await
+            .unwrap();
+
+            OpenFGAAuthZHandler::check_load_table(
+                &analyst,
+                warehouse_id,
+                Some(&namespace),
+                Some(tabular_id),
+                state.clone(),
+            )
+            .await
+            .unwrap();
+            let err = OpenFGAAuthZHandler::check_drop_table(
+                &analyst,
+                warehouse_id,
+                Some(tabular_id),
+                state.clone(),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+            let err = OpenFGAAuthZHandler::check_load_table(
+                &stranger,
+                warehouse_id,
+                Some(&namespace),
+                Some(tabular_id),
+                state.clone(),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+
+            let tabulars = vec![TabularIdentUuid::Table(*tabular_id)];
+            let visible = OpenFGAAuthZHandler::filter_visible_tabulars(
+                &analyst,
+                warehouse_id,
+                &namespace,
+                tabulars.clone(),
+                state.clone(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(visible, tabulars.iter().copied().collect());
+            let visible = OpenFGAAuthZHandler::filter_visible_tabulars(
+                &stranger,
+                warehouse_id,
+                &namespace,
+                tabulars,
+                state.clone(),
+            )
+            .await
+            .unwrap();
+            assert!(visible.is_empty());
+
+            // Owners may drop what they created
+            OpenFGAAuthZHandler::check_drop_table(
+                &creator,
+                warehouse_id,
+                Some(tabular_id),
+                state.clone(),
+            )
+            .await
+            .unwrap();
+
+            let projects = OpenFGAAuthZHandler::check_list_projects(&analyst, state.clone())
+                .await
+                .unwrap();
+            assert_eq!(projects, Some(HashSet::from([project_id])));
+
+            OpenFGAAuthZHandler::on_tabular_dropped(warehouse_id, tabular_id, state.clone())
+                .await
+                .unwrap();
+            assert!(state
+                .read(&tabular_object(tabular_id))
+                .await
+                .unwrap()
+                .is_empty());
+
+            // Grants on a namespace don't apply to a namespace recreated with the same name,
+            // even if they were not cleaned up
+            state
+                .write(
+                    vec![TupleKey::new(
+                        User::from_metadata(&stranger, "groups").unwrap().id(),
+                        Relation::Reader,
+                        namespace_object(namespace_id),
+                    )],
+                    vec![],
+                )
+                .await
+                .unwrap();
+            OpenFGAAuthZHandler::check_load_namespace_metadata(
+                &stranger,
+                warehouse_id,
+                &namespace,
+                state.clone(),
+            )
+            .await
+            .unwrap();
+            let mut transaction = PostgresTransaction::begin_write(catalog_state.clone())
+                .await
+                .unwrap();
+            PostgresCatalog::drop_namespace(warehouse_id, &namespace, transaction.transaction())
+                .await
+                .unwrap();
+            transaction.commit().await.unwrap();
+            initialize_namespace(catalog_state, warehouse_id, &namespace, None).await;
+            let err = OpenFGAAuthZHandler::check_load_namespace_metadata(
+                &stranger,
+                warehouse_id,
+                &namespace,
+                state.clone(),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+
+            OpenFGAAuthZHandler::on_namespace_dropped(warehouse_id, namespace_id, state.clone())
+                .await
+                .unwrap();
+            assert!(state
+                .read(&namespace_object(namespace_id))
+                .await
+                .unwrap()
+                .is_empty());
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use super::dbutils::DBErrorHandler;
+use super::CatalogState;
+use crate::api::{ErrorModel, Result};
+use crate::service::api_key::ApiKey;
+use crate::service::{ProjectIdent, WarehouseIdent};
+use http::StatusCode;
+
+#[derive(sqlx::FromRow, Debug)]
+struct ApiKeyRecord {
+    api_key_id: uuid::Uuid,
+    name: String,
+    owner: String,
+    project_id: Option<uuid::Uuid>,
+    warehouse_id: Option<uuid::Uuid>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<ApiKeyRecord> for ApiKey {
+    fn from(record: ApiKeyRecord) -> Self {
+        Self {
+            api_key_id: record.api_key_id,
+            name: record.name,
+            owner: record.owner,
+            project_id: record.project_id.map(ProjectIdent::from),
+            warehouse_id: record.warehouse_id.map(WarehouseIdent::from),
+            created_at: record.created_at,
+            expires_at: record.expires_at,
+            revoked_at: record.revoked_at,
+        }
+    }
+}
+
+pub(crate) async fn create_api_key(
+    name: &str,
+    owner: &str,
+    warehouse_id: Option<WarehouseIdent>,
+    key_hash: &[u8],
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    catalog_state: CatalogState,
+) -> Result<ApiKey> {
+    let record = sqlx::query_as!(
+        ApiKeyRecord,
+        r#"
+        WITH inserted AS (
+            INSERT INTO api_key (name, owner, warehouse_id, key_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+        )
+        SELECT
+            i.api_key_id,
+            i.name,
+            i.owner,
+            w.project_id as "project_id?",
+            i.warehouse_id,
+            i.created_at,
+            i.expires_at,
+            i.revoked_at
+        FROM inserted i
+        LEFT JOIN warehouse w ON w.warehouse_id = i.warehouse_id
+        "#,
+        name,
+        owner,
+        warehouse_id.map(|w| *w),
+        key_hash,
+        expires_at
+    )
+    .fetch_one(&catalog_state.write_pool())
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_error) if db_error.is_foreign_key_violation() => {
+            ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message("Warehouse not found".to_string())
+                .r#type("WarehouseNotFound".to_string())
+                .build()
+        }
+        _ => e.into_error_model("Error creating API key".into()),
+    })?;
+
+    Ok(record.into())
+}
+
+pub(crate) async fn list_api_keys(owner: &str, catalog_state: CatalogState) -> Result<Vec<ApiKey>> {
+    let records = sqlx::query_as!(
+        ApiKeyRecord,
+        r#"
+        SELECT
+            k.api_key_id,
+            k.name,
+            k.owner,
+            w.project_id as "project_id?",
+            k.warehouse_id,
+            k.created_at,
+            k.expires_at,
+            k.revoked_at
+        FROM api_key k
+        LEFT JOIN warehouse w ON w.warehouse_id = k.warehouse_id
+        WHERE k.owner = $1
+        ORDER BY k.created_at
+        "#,
+        owner
+    )
+    .fetch_all(&catalog_state.read_pool())
+    .await
+    .map_err(|e| e.into_error_model("Error fetching API keys".into()))?;
+
+    Ok(records.into_iter().map(Into::into).collect())
+}
+
+pub(crate) async fn get_api_key_by_hash(
+    key_hash: &[u8],
+    catalog_state: CatalogState,
+) -> Result<Option<ApiKey>> {
+    let record = sqlx::query_as!(
+        ApiKeyRecord,
+        r#"
+        SELECT
+            k.api_key_id,
+            k.name,
+            k.owner,
+            w.project_id as "project_id?",
+            k.warehouse_id,
+            k.created_at,
+            k.expires_at,
+            k.revoked_at
+        FROM api_key k
+        LEFT JOIN warehouse w ON w.warehouse_id = k.warehouse_id
+        WHERE k.key_hash = $1
+        "#,
+        key_hash
+    )
+    .fetch_optional(&catalog_state.read_pool())
+    .await
+    .map_err(|e| e.into_error_model("Error fetching API key".into()))?;
+
+    Ok(record.map(Into::into))
+}
+
+pub(crate) async fn set_api_key_expiry(
+    api_key_id: uuid::Uuid,
+    owner: &str,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    catalog_state: CatalogState,
+) -> Result<ApiKey> {
+    let record = sqlx::query_as!(
+        ApiKeyRecord,
+        r#"
+        WITH updated AS (
+            UPDATE api_key
+            SET expires_at = $3
+            WHERE api_key_id = $1 AND owner = $2 AND revoked_at IS NULL
+            RETURNING *
+        )
+        SELECT
+            u.api_key_id,
+            u.name,
+            u.owner,
+            w.project_id as "project_id?",
+            u.warehouse_id,
+            u.created_at,
+            u.expires_at,
+            u.revoked_at
+        FROM updated u
+        LEFT JOIN warehouse w ON w.warehouse_id = u.warehouse_id
+        "#,
+        api_key_id,
+        owner,
+        expires_at
+    )
+    .fetch_optional(&catalog_state.write_pool())
+    .await
+    .map_err(|e| e.into_error_model("Error updating API key".into()))?
+    .ok_or_else(|| api_key_not_found(api_key_id))?;
+
+    Ok(record.into())
+}
+
+pub(crate) async fn revoke_api_key(
+    api_key_id: uuid::Uuid,
+    owner: &str,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        WITH revoked AS (
+            UPDATE api_key
+            SET revoked_at = now()
+            WHERE api_key_id = $1 AND owner = $2 AND revoked_at IS NULL
+            RETURNING *
+        )
+
+        SELECT count(*) FROM revoked
+        "#,
+        api_key_id,
+        owner
+    )
+    .fetch_one(&catalog_state.write_pool())
+    .await
+    .map_err(|e| e.into_error_model("Error revoking API key".into()))?;
+
+    if row_count == Some(0) {
+        return Err(api_key_not_found(api_key_id).into());
+    }
+
+    Ok(())
+}
+
+// Keys of other owners are reported as not found to not disclose their existence.
+fn api_key_not_found(api_key_id: uuid::Uuid) -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message(format!("API key {api_key_id} not found"))
+        .r#type("ApiKeyNotFound".to_string())
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::implementations::postgres::warehouse::test::initialize_warehouse;
+    use crate::service::api_key::hash_api_key;
+
+    #[sqlx::test]
+    async fn test_create_and_authenticate_api_key(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let key_hash = hash_api_key("icat_secret");
+        let created = create_api_key(
+            "ci",
+            "alice",
+            Some(warehouse_id),
+            &key_hash,
+            None,
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(created.warehouse_id, Some(warehouse_id));
+        assert!(created.project_id.is_some());
+
+        let found = get_api_key_by_hash(&key_hash, state.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found, created);
+
+        assert!(
+            get_api_key_by_hash(&hash_api_key("icat_other"), state.clone())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_create_api_key_unknown_warehouse(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let err = create_api_key(
+            "ci",
+            "alice",
+            Some(WarehouseIdent::from(uuid::Uuid::now_v7())),
+            &hash_api_key("icat_secret"),
+            None,
+            state,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    async fn test_list_expire_and_revoke_api_keys(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let key = create_api_key(
+            "ci",
+            "alice",
+            None,
+            &hash_api_key("icat_a"),
+            None,
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        create_api_key(
+            "tool",
+            "bob",
+            None,
+            &hash_api_key("icat_b"),
+            None,
+            state.clone(),
+        )
+        .await
+        .unwrap();
+
+        let keys = list_api_keys("alice", state.clone()).await.unwrap();
+        assert_eq!(keys, vec![key.clone()]);
+
+        // Keys of other owners can't be modified
+        set_api_key_expiry(key.api_key_id, "bob", None, state.clone())
+            .await
+            .unwrap_err();
+        revoke_api_key(key.api_key_id, "bob", state.clone())
+            .await
+            .unwrap_err();
+
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(1);
+        let updated = set_api_key_expiry(key.api_key_id, "alice", Some(expires_at), state.clone())
+            .await
+            .unwrap();
+        assert!(updated.expires_at.is_some());
+
+        revoke_api_key(key.api_key_id, "alice", state.clone())
+            .await
+            .unwrap();
+        let keys = list_api_keys("alice", state.clone()).await.unwrap();
+        assert!(keys[0].revoked_at.is_some());
+
+        // Revoking twice fails
+        revoke_api_key(key.api_key_id, "alice", state)
+            .await
+            .unwrap_err();
+    }
+}
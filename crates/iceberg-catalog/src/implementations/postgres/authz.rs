@@ -0,0 +1,1644 @@
+//! Role based `AuthZHandler` stored in Postgres.
+//!
+//! Roles have members - principals or groups of the token - and grants of a
+//! [`Privilege`] on a project, warehouse, namespace or tabular. Grants are inherited
+//! by all objects contained in the granted object.
+use super::dbutils::DBErrorHandler;
+use super::ReadWrite;
+use crate::api::iceberg::v1::NamespaceIdent;
+use crate::api::management::v1::role::{
+    CreateRoleRequest, Grant, GrantObjectType, Privilege, Role, RoleMember, RoleMemberType,
+};
+use crate::api::{ApiContext, ErrorModel, Result};
+use crate::implementations::DEFAULT_PROJECT_ID;
+use crate::request_metadata::RequestMetadata;
+use crate::service::auth::{AuthConfigHandler, AuthZHandler, UserWarehouse};
+use crate::service::health::{Health, HealthExt};
+use crate::service::tabular_idents::TabularIdentUuid;
+use crate::service::token_verification::PrincipalId;
+use crate::service::{Catalog, SecretStore, State, TableIdentUuid};
+use crate::{ProjectIdent, WarehouseIdent, CONFIG};
+use async_trait::async_trait;
+use http::StatusCode;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct PostgresAuthZState {
+    read_write: ReadWrite,
+}
+
+impl PostgresAuthZState {
+    #[must_use]
+    pub fn from_pools(read_pool: PgPool, write_pool: PgPool) -> Self {
+        Self {
+            read_write: ReadWrite::from_pools(read_pool, write_pool),
+        }
+    }
+}
+
+#[async_trait]
+impl HealthExt for PostgresAuthZState {
+    async fn health(&self) -> Vec<Health> {
+        self.read_write.health().await
+    }
+
+    async fn update_health(&self) {
+        self.read_write.update_health().await;
+    }
+}
+
+/// The authenticated caller of a request.
+#[derive(Debug, Clone)]
+pub(crate) struct Principal {
+    /// Formatted as `<issuer>|<subject>`.
+    id: String,
+    groups: Vec<String>,
+}
+
+impl Principal {
+    /// # Errors
+    /// Fails with 401 if the request is not authenticated.
+    pub(crate) fn from_metadata(metadata: &RequestMetadata) -> Result<Self> {
+        match &metadata.auth_details {
+            Some(auth_details) => {
+                let claims = auth_details.claims();
+                let groups = match claims.other.get(&CONFIG.rbac.groups_claim) {
+                    Some(serde_json::Value::Array(groups)) => groups
+                        .iter()
+                        .filter_map(|g| g.as_str().map(str::to_string))
+                        .collect(),
+                    Some(serde_json::Value::String(group)) => vec![group.clone()],
+                    _ => vec![],
+                };
+                Ok(Self {
+                    id: auth_details.principal_id().to_string(),
+                    groups,
+                })
+            }
+            None => Err(ErrorModel::builder()
+                .code(StatusCode::UNAUTHORIZED.into())
+                .message("Authentication is required".to_string())
+                .r#type("UnauthorizedError".to_string())
+                .build()
+                .into()),
+        }
+    }
+
+    fn is_admin(&self) -> bool {
+        CONFIG.rbac.admin_subjects.contains(&self.id)
+            || self
+                .groups
+                .iter()
+                .any(|g| CONFIG.rbac.admin_groups.contains(g))
+    }
+
+    /// # Errors
+    /// Fails with 403 if the principal is not an admin.
+    pub(crate) fn require_admin(&self) -> Result<()> {
+        if self.is_admin() {
+            Ok(())
+        } else {
+            Err(ErrorModel::forbidden(
+                "Only admins are allowed to perform this operation",
+                "Forbidden",
+                None,
+            )
+            .into())
+        }
+    }
+}
+
+/// The object a privilege is checked on, including its ancestors.
+#[derive(Debug, Clone, Default)]
+struct Target {
+    project_id: Option<uuid::Uuid>,
+    warehouse_id: Option<uuid::Uuid>,
+    namespace: Option<Vec<String>>,
+    tabular_id: Option<uuid::Uuid>,
+}
+
+impl Target {
+    fn project(project_id: ProjectIdent) -> Self {
+        Self {
+            project_id: Some(*project_id),
+            ..Self::default()
+        }
+    }
+
+    fn warehouse(warehouse_id: WarehouseIdent) -> Self {
+        Self {
+            warehouse_id: Some(*warehouse_id),
+            ..Self::default()
+        }
+    }
+
+    fn namespace(warehouse_id: WarehouseIdent, namespace: Option<&NamespaceIdent>) -> Self {
+        Self {
+            warehouse_id: Some(*warehouse_id),
+            namespace: namespace.map(|n| n.as_ref().clone()),
+            ..Self::default()
+        }
+    }
+
+    fn tabular(
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        tabular_id: Option<TableIdentUuid>,
+    ) -> Self {
+        Self {
+            warehouse_id: Some(*warehouse_id),
+            namespace: namespace.map(|n| n.as_ref().clone()),
+            tabular_id: tabular_id.map(uuid::Uuid::from),
+            ..Self::default()
+        }
+    }
+}
+
+/// Highest privilege of the principal on the target, considering grants on all its ancestors.
+async fn effective_privilege(
+    principal: &Principal,
+    target: &Target,
+    state: &PostgresAuthZState,
+) -> Result<Option<Privilege>> {
+    let privilege = sqlx::query_scalar!(
+        r#"
+        WITH roles AS (
+            SELECT role_id FROM role_member
+            WHERE (member_type = 'user' AND member = $1)
+                OR (member_type = 'group' AND member = ANY($2::text[]))
+        ),
+        target_namespace AS (
+            SELECT n.namespace_name FROM namespace n
+            WHERE n.warehouse_id = $4
+                AND (
+                    n.namespace_name = $5
+                    OR n.namespace_id = (SELECT namespace_id FROM tabular WHERE tabular_id = $6)
+                )
+            LIMIT 1
+        )
+        SELECT max(g.privilege) as "privilege: Privilege"
+        FROM role_grant g
+        WHERE g.role_id IN (SELECT role_id FROM roles)
+            AND (
+                (g.object_type = 'project' AND g.object_id = COALESCE(
+                    $3,
+                    (SELECT project_id FROM warehouse WHERE warehouse_id = $4)
+                ))
+                OR (g.object_type = 'warehouse' AND g.object_id = $4)
+                OR (g.object_type = 'namespace' AND g.object_id IN (
+                    SELECT n.namespace_id FROM namespace n, target_namespace t
+                    WHERE n.warehouse_id = $4
+                        AND cardinality(n.namespace_name) <= cardinality(t.namespace_name)
+                        AND t.namespace_name[1:cardinality(n.namespace_name)] = n.namespace_name
+                ))
+                OR (g.object_type = 'tabular' AND g.object_id = $6)
+            )
+        "#,
+        principal.id,
+        &principal.groups,
+        target.project_id,
+        target.warehouse_id,
+        target.namespace.as_deref(),
+        target.tabular_id,
+    )
+    .fetch_one(&state.read_write.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error checking privileges".to_string()))?;
+
+    Ok(privilege)
+}
+
+async fn require_privilege(
+    metadata: &RequestMetadata,
+    target: Target,
+    privilege: Privilege,
+    state: &PostgresAuthZState,
+) -> Result<()> {
+    let principal = Principal::from_metadata(metadata)?;
+    if principal.is_admin() {
+        return Ok(());
+    }
+
+    match effective_privilege(&principal, &target, state).await? {
+        Some(granted) if granted >= privilege => Ok(()),
+        _ => Err(ErrorModel::forbidden(
+            format!("The '{privilege}' privilege is required for this operation"),
+            "Forbidden",
+            None,
+        )
+        .into()),
+    }
+}
+
+/// A namespace the principal has a grant on, or that contains a tabular the
+/// principal has a grant on.
+#[derive(Debug, Clone)]
+struct NamespaceGrant {
+    namespace: Vec<String>,
+    /// Grants on the namespace itself are inherited by its children.
+    inherited: bool,
+}
+
+impl NamespaceGrant {
+    /// Namespaces on the path to a grant are visible, so that the granted
+    /// objects can be reached by listing.
+    fn makes_visible(&self, namespace: &[String]) -> bool {
+        self.namespace.starts_with(namespace)
+            || (self.inherited && namespace.starts_with(&self.namespace))
+    }
+}
+
+async fn namespace_grants(
+    principal: &Principal,
+    warehouse_id: WarehouseIdent,
+    state: &PostgresAuthZState,
+) -> Result<Vec<NamespaceGrant>> {
+    let grants = sqlx::query!(
+        r#"
+        WITH roles AS (
+            SELECT role_id FROM role_member
+            WHERE (member_type = 'user' AND member = $1)
+                OR (member_type = 'group' AND member = ANY($2::text[]))
+        ),
+        grants AS (
+            SELECT object_type, object_id FROM role_grant
+            WHERE role_id IN (SELECT role_id FROM roles)
+        )
+        SELECT n.namespace_name as "namespace_name!", true as "inherited!" FROM grants g
+        INNER JOIN namespace n ON g.object_type = 'namespace' AND n.namespace_id = g.object_id
+        WHERE n.warehouse_id = $3
+        UNION ALL
+        SELECT n.namespace_name, false FROM grants g
+        INNER JOIN tabular t ON g.object_type = 'tabular' AND t.tabular_id = g.object_id
+        INNER JOIN namespace n ON n.namespace_id = t.namespace_id
+        WHERE n.warehouse_id = $3
+        "#,
+        principal.id,
+        &principal.groups,
+        *warehouse_id,
+    )
+    .fetch_all(&state.read_write.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching namespace grants".to_string()))?;
+
+    Ok(grants
+        .into_iter()
+        .map(|g| NamespaceGrant {
+            namespace: g.namespace_name,
+            inherited: g.inherited,
+        })
+        .collect())
+}
+
+/// Listing the content of a namespace, or of the warehouse if `namespace` is `None`,
+/// requires a privilege on it or a grant on an object inside of it.
+/// The listing itself is filtered by the visibility filters.
+async fn require_visible_namespace(
+    metadata: &RequestMetadata,
+    warehouse_id: WarehouseIdent,
+    namespace: Option<&NamespaceIdent>,
+    state: &PostgresAuthZState,
+) -> Result<()> {
+    let principal = Principal::from_metadata(metadata)?;
+    if principal.is_admin()
+        || effective_privilege(
+            &principal,
+            &Target::namespace(warehouse_id, namespace),
+            state,
+        )
+        .await?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    let namespace = namespace.map(|n| n.as_ref().as_slice()).unwrap_or_default();
+    let grants = namespace_grants(&principal, warehouse_id, state).await?;
+    if grants.iter().any(|g| g.makes_visible(namespace)) {
+        Ok(())
+    } else {
+        Err(ErrorModel::forbidden(
+            "The 'read' privilege is required for this operation",
+            "Forbidden",
+            None,
+        )
+        .into())
+    }
+}
+
+/// # Errors
+/// Fails with 403 if the principal does not have the `manage` privilege on the object,
+/// and with 404 if the object does not exist.
+pub(crate) async fn require_manage_object(
+    principal: &Principal,
+    object_type: GrantObjectType,
+    object_id: uuid::Uuid,
+    state: &PostgresAuthZState,
+) -> Result<()> {
+    if principal.is_admin() {
+        return Ok(());
+    }
+
+    let target = match object_type {
+        GrantObjectType::Project => Target {
+            project_id: Some(object_id),
+            ..Target::default()
+        },
+        GrantObjectType::Warehouse => Target {
+            warehouse_id: Some(object_id),
+            ..Target::default()
+        },
+        GrantObjectType::Namespace => {
+            let namespace = sqlx::query!(
+                "SELECT warehouse_id, namespace_name FROM namespace WHERE namespace_id = $1",
+                object_id
+            )
+            .fetch_optional(&state.read_write.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching namespace".to_string()))?
+            .ok_or_else(|| object_not_found(object_type, object_id))?;
+            Target {
+                warehouse_id: Some(namespace.warehouse_id),
+                namespace: Some(namespace.namespace_name),
+                ..Target::default()
+            }
+        }
+        GrantObjectType::Tabular => {
+            let warehouse_id = sqlx::query_scalar!(
+                r#"
+                SELECT n.warehouse_id FROM tabular t
+                INNER JOIN namespace n ON n.namespace_id = t.namespace_id
+                WHERE t.tabular_id = $1
+                "#,
+                object_id
+            )
+            .fetch_optional(&state.read_write.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching tabular".to_string()))?
+            .ok_or_else(|| object_not_found(object_type, object_id))?;
+            Target {
+                warehouse_id: Some(warehouse_id),
+                tabular_id: Some(object_id),
+                ..Target::default()
+            }
+        }
+    };
+
+    match effective_privilege(principal, &target, state).await? {
+        Some(Privilege::Manage) => Ok(()),
+        _ => Err(ErrorModel::forbidden(
+            format!("The 'manage' privilege on {object_type} {object_id} is required to change its grants"),
+            "Forbidden",
+            None,
+        )
+        .into()),
+    }
+}
+
+fn object_not_found(object_type: GrantObjectType, object_id: uuid::Uuid) -> ErrorModel {
+    ErrorModel::not_found(
+        format!("{object_type} {object_id} not found"),
+        "GrantObjectNotFound",
+        None,
+    )
+}
+
+fn role_not_found(role_id: uuid::Uuid) -> ErrorModel {
+    ErrorModel::not_found(format!("Role {role_id} not found"), "RoleNotFound", None)
+}
+
+// ---------------- Role management ----------------
+
+pub(crate) async fn create_role(
+    request: CreateRoleRequest,
+    state: &PostgresAuthZState,
+) -> Result<Role> {
+    let mut transaction = state
+        .read_write
+        .write_pool
+        .begin()
+        .await
+        .map_err(|e| e.into_error_model("Error starting transaction".to_string()))?;
+
+    let role_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO role (role_name, description)
+        VALUES ($1, $2)
+        RETURNING role_id
+        "#,
+        request.name,
+        request.description,
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ErrorModel::conflict(
+            format!("Role '{}' already exists", request.name),
+            "RoleAlreadyExists",
+            Some(Box::new(e)),
+        ),
+        _ => e.into_error_model("Error creating role".to_string()),
+    })?;
+    insert_role_members(role_id, &request.members, &mut transaction).await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|e| e.into_error_model("Error committing transaction".to_string()))?;
+
+    get_role(role_id, state).await
+}
+
+async fn insert_role_members(
+    role_id: uuid::Uuid,
+    members: &[RoleMember],
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    for member in members {
+        if member.member_type == RoleMemberType::User {
+            PrincipalId::from_str(&member.name)?;
+        }
+    }
+    let (member_types, names): (Vec<RoleMemberType>, Vec<String>) = members
+        .iter()
+        .map(|m| (m.member_type, m.name.clone()))
+        .unzip();
+    sqlx::query!(
+        r#"
+        INSERT INTO role_member (role_id, member_type, member)
+        SELECT $1, m.member_type, m.member
+        FROM unnest($2::role_member_type[], $3::text[]) AS m(member_type, member)
+        ON CONFLICT DO NOTHING
+        "#,
+        role_id,
+        member_types as Vec<RoleMemberType>,
+        &names,
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error adding role members".to_string()))?;
+    Ok(())
+}
+
+pub(crate) async fn list_roles(state: &PostgresAuthZState) -> Result<Vec<Role>> {
+    let roles = sqlx::query!(
+        r#"
+        SELECT role_id, role_name, description, created_at, updated_at
+        FROM role
+        ORDER BY role_name
+        "#
+    )
+    .fetch_all(&state.read_write.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching roles".to_string()))?;
+
+    let mut members = list_role_members(None, state).await?;
+    Ok(roles
+        .into_iter()
+        .map(|r| Role {
+            role_id: r.role_id,
+            name: r.role_name,
+            description: r.description,
+            members: members.remove(&r.role_id).unwrap_or_default(),
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        })
+        .collect())
+}
+
+pub(crate) async fn get_role(role_id: uuid::Uuid, state: &PostgresAuthZState) -> Result<Role> {
+    let role = sqlx::query!(
+        r#"
+        SELECT role_id, role_name, description, created_at, updated_at
+        FROM role
+        WHERE role_id = $1
+        "#,
+        role_id
+    )
+    .fetch_optional(&state.read_write.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching role".to_string()))?
+    .ok_or_else(|| role_not_found(role_id))?;
+
+    let mut members = list_role_members(Some(role_id), state).await?;
+    Ok(Role {
+        role_id: role.role_id,
+        name: role.role_name,
+        description: role.description,
+        members: members.remove(&role.role_id).unwrap_or_default(),
+        created_at: role.created_at,
+        updated_at: role.updated_at,
+    })
+}
+
+async fn list_role_members(
+    role_id: Option<uuid::Uuid>,
+    state: &PostgresAuthZState,
+) -> Result<HashMap<uuid::Uuid, Vec<RoleMember>>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT role_id, member_type as "member_type: RoleMemberType", member
+        FROM role_member
+        WHERE $1::uuid IS NULL OR role_id = $1
+        ORDER BY member_type, member
+        "#,
+        role_id
+    )
+    .fetch_all(&state.read_write.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching role members".to_string()))?;
+
+    let mut members: HashMap<uuid::Uuid, Vec<RoleMember>> = HashMap::new();
+    for row in rows {
+        members.entry(row.role_id).or_default().push(RoleMember {
+            member_type: row.member_type,
+            name: row.member,
+        });
+    }
+    Ok(members)
+}
+
+pub(crate) async fn delete_role(role_id: uuid::Uuid, state: &PostgresAuthZState) -> Result<()> {
+    let deleted = sqlx::query!("DELETE FROM role WHERE role_id = $1", role_id)
+        .execute(&state.read_write.write_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error deleting role".to_string()))?;
+    if deleted.rows_affected() == 0 {
+        return Err(role_not_found(role_id).into());
+    }
+    Ok(())
+}
+
+pub(crate) async fn set_role_members(
+    role_id: uuid::Uuid,
+    members: Vec<RoleMember>,
+    state: &PostgresAuthZState,
+) -> Result<Role> {
+    let mut transaction = state
+        .read_write
+        .write_pool
+        .begin()
+        .await
+        .map_err(|e| e.into_error_model("Error starting transaction".to_string()))?;
+
+    // Locks the role, so that concurrent updates of its members don't interleave
+    sqlx::query_scalar!(
+        "UPDATE role SET updated_at = now() WHERE role_id = $1 RETURNING role_id",
+        role_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error updating role".to_string()))?
+    .ok_or_else(|| role_not_found(role_id))?;
+
+    sqlx::query!("DELETE FROM role_member WHERE role_id = $1", role_id)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error removing role members".to_string()))?;
+    insert_role_members(role_id, &members, &mut transaction).await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|e| e.into_error_model("Error committing transaction".to_string()))?;
+
+    get_role(role_id, state).await
+}
+
+pub(crate) async fn list_grants(
+    role_id: uuid::Uuid,
+    state: &PostgresAuthZState,
+) -> Result<Vec<Grant>> {
+    // Fails with 404 for unknown roles instead of returning an empty list
+    get_role(role_id, state).await?;
+
+    sqlx::query_as!(
+        Grant,
+        r#"
+        SELECT
+            object_type as "object_type: GrantObjectType",
+            object_id,
+            privilege as "privilege: Privilege"
+        FROM role_grant
+        WHERE role_id = $1
+        ORDER BY object_type, object_id
+        "#,
+        role_id
+    )
+    .fetch_all(&state.read_write.read_pool)
+    .await
+    .map_err(|e| {
+        e.into_error_model("Error fetching grants".to_string())
+            .into()
+    })
+}
+
+pub(crate) async fn grant(
+    role_id: uuid::Uuid,
+    grant: Grant,
+    state: &PostgresAuthZState,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO role_grant (role_id, object_type, object_id, privilege)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (role_id, object_type, object_id)
+        DO UPDATE SET privilege = EXCLUDED.privilege
+        "#,
+        role_id,
+        grant.object_type as GrantObjectType,
+        grant.object_id,
+        grant.privilege as Privilege,
+    )
+    .execute(&state.read_write.write_pool)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => role_not_found(role_id),
+        _ => e.into_error_model("Error granting privilege".to_string()),
+    })?;
+    Ok(())
+}
+
+pub(crate) async fn revoke(
+    role_id: uuid::Uuid,
+    object_type: GrantObjectType,
+    object_id: uuid::Uuid,
+    state: &PostgresAuthZState,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM role_grant
+        WHERE role_id = $1 AND object_type = $2 AND object_id = $3
+        "#,
+        role_id,
+        object_type as GrantObjectType,
+        object_id,
+    )
+    .execute(&state.read_write.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error revoking privilege".to_string()))?;
+    Ok(())
+}
+
+// ---------------- AuthZHandler ----------------
+
+#[derive(Clone, Debug, Default)]
+/// Role based access control. Roles, their members and grants are stored in Postgres.
+pub struct PostgresAuthZHandler;
+
+#[async_trait]
+impl AuthConfigHandler<PostgresAuthZHandler> for PostgresAuthZHandler {
+    async fn get_and_validate_user_warehouse(
+        state: PostgresAuthZState,
+        metadata: &RequestMetadata,
+    ) -> Result<UserWarehouse> {
+        Principal::from_metadata(metadata)?;
+        if let Some(user_warehouse) = UserWarehouse::from_catalog_token(metadata) {
+            // Permissions might have been revoked since the token was issued
+            if let Some(warehouse_id) = user_warehouse.warehouse_id {
+                Self::check_user_get_config_for_warehouse(state, warehouse_id, metadata).await?;
+            }
+            return Ok(user_warehouse);
+        }
+        Ok(UserWarehouse {
+            project_id: Some(
+                UserWarehouse::project_from_token(metadata)
+                    .unwrap_or(ProjectIdent::from(DEFAULT_PROJECT_ID)),
+            ),
+            warehouse_id: None,
+        })
+    }
+
+    async fn exchange_token_for_warehouse(
+        _: PostgresAuthZState,
+        _: &RequestMetadata,
+        _: &ProjectIdent,
+        _: WarehouseIdent,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn check_list_warehouse_in_project(
+        state: PostgresAuthZState,
+        project_id: &ProjectIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        <Self as AuthZHandler>::check_list_warehouse_in_project(metadata, *project_id, state)
+            .await
+            .map(|_| ())
+    }
+
+    async fn check_user_get_config_for_warehouse(
+        state: PostgresAuthZState,
+        warehouse_id: WarehouseIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        require_privilege(
+            metadata,
+            Target::warehouse(warehouse_id),
+            Privilege::Read,
+            &state,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl AuthZHandler for PostgresAuthZHandler {
+    type State = PostgresAuthZState;
+
+    async fn check_list_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        require_visible_namespace(metadata, warehouse_id, parent, &state).await
+    }
+
+    async fn check_create_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::namespace(warehouse_id, parent);
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    async fn check_load_namespace_metadata(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::namespace(warehouse_id, Some(namespace));
+        require_privilege(metadata, target, Privilege::Read, &state).await
+    }
+
+    async fn check_namespace_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::namespace(warehouse_id, Some(namespace));
+        require_privilege(metadata, target, Privilege::Read, &state).await
+    }
+
+    async fn check_drop_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::namespace(warehouse_id, Some(namespace));
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    async fn check_update_namespace_properties(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::namespace(warehouse_id, Some(namespace));
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    async fn check_create_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::namespace(warehouse_id, Some(namespace));
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    async fn check_list_tables(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        require_visible_namespace(metadata, warehouse_id, Some(namespace), &state).await
+    }
+
+    async fn check_load_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, namespace, table);
+        require_privilege(metadata, target, Privilege::Read, &state).await
+    }
+
+    async fn check_rename_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        source: Option<TableIdentUuid>,
+        destination: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, None, source);
+        require_privilege(metadata, target, Privilege::Write, &state).await?;
+        Self::check_create_table(metadata, warehouse_id, destination, state).await
+    }
+
+    async fn check_table_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, namespace, table);
+        require_privilege(metadata, target, Privilege::Read, &state).await
+    }
+
+    async fn check_drop_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        table: Option<TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, None, table);
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    async fn check_commit_table(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        table: Option<TableIdentUuid>,
+        namespace: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, namespace, table);
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    // ---------------- Management API ----------------
+    async fn check_create_project(
+        metadata: &RequestMetadata,
+        _project_id: ProjectIdent,
+        _id_requested: bool,
+        _state: Self::State,
+    ) -> Result<()> {
+        Principal::from_metadata(metadata)?.require_admin()
+    }
+
+    async fn check_rename_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::project(project_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_delete_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::project(project_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_deactivate_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::project(project_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_activate_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::project(project_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_create_warehouse(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::project(*project_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_list_projects(
+        metadata: &RequestMetadata,
+        state: Self::State,
+    ) -> Result<Option<HashSet<ProjectIdent>>> {
+        let principal = Principal::from_metadata(metadata)?;
+        if principal.is_admin() {
+            return Ok(None);
+        }
+
+        // Every project the principal has any grant in
+        let projects = sqlx::query_scalar!(
+            r#"
+            WITH roles AS (
+                SELECT role_id FROM role_member
+                WHERE (member_type = 'user' AND member = $1)
+                    OR (member_type = 'group' AND member = ANY($2::text[]))
+            ),
+            grants AS (
+                SELECT object_type, object_id FROM role_grant
+                WHERE role_id IN (SELECT role_id FROM roles)
+            )
+            SELECT object_id as "project_id!" FROM grants WHERE object_type = 'project'
+            UNION
+            SELECT w.project_id FROM grants g
+            INNER JOIN warehouse w ON g.object_type = 'warehouse' AND w.warehouse_id = g.object_id
+            UNION
+            SELECT w.project_id FROM grants g
+            INNER JOIN namespace n ON g.object_type = 'namespace' AND n.namespace_id = g.object_id
+            INNER JOIN warehouse w ON w.warehouse_id = n.warehouse_id
+            UNION
+            SELECT w.project_id FROM grants g
+            INNER JOIN tabular t ON g.object_type = 'tabular' AND t.tabular_id = g.object_id
+            INNER JOIN namespace n ON n.namespace_id = t.namespace_id
+            INNER JOIN warehouse w ON w.warehouse_id = n.warehouse_id
+            "#,
+            principal.id,
+            &principal.groups,
+        )
+        .fetch_all(&state.read_write.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching projects".to_string()))?;
+
+        Ok(Some(projects.into_iter().map(ProjectIdent::from).collect()))
+    }
+
+    async fn check_list_warehouse_in_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<Option<HashSet<WarehouseIdent>>> {
+        let principal = Principal::from_metadata(metadata)?;
+        if principal.is_admin()
+            || effective_privilege(&principal, &Target::project(project_id), &state)
+                .await?
+                .is_some()
+        {
+            return Ok(None);
+        }
+
+        // Every warehouse of the project the principal has any grant in
+        let warehouses = sqlx::query_scalar!(
+            r#"
+            WITH roles AS (
+                SELECT role_id FROM role_member
+                WHERE (member_type = 'user' AND member = $1)
+                    OR (member_type = 'group' AND member = ANY($2::text[]))
+            ),
+            grants AS (
+                SELECT object_type, object_id FROM role_grant
+                WHERE role_id IN (SELECT role_id FROM roles)
+            )
+            SELECT w.warehouse_id as "warehouse_id!" FROM warehouse w
+            WHERE w.project_id = $3 AND (
+                EXISTS (
+                    SELECT 1 FROM grants g
+                    WHERE g.object_type = 'warehouse' AND g.object_id = w.warehouse_id
+                )
+                OR EXISTS (
+                    SELECT 1 FROM grants g
+                    INNER JOIN namespace n ON n.namespace_id = g.object_id
+                    WHERE g.object_type = 'namespace' AND n.warehouse_id = w.warehouse_id
+                )
+                OR EXISTS (
+                    SELECT 1 FROM grants g
+                    INNER JOIN tabular t ON t.tabular_id = g.object_id
+                    INNER JOIN namespace n ON n.namespace_id = t.namespace_id
+                    WHERE g.object_type = 'tabular' AND n.warehouse_id = w.warehouse_id
+                )
+            )
+            "#,
+            principal.id,
+            &principal.groups,
+            *project_id,
+        )
+        .fetch_all(&state.read_write.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching warehouses".to_string()))?;
+
+        if warehouses.is_empty() {
+            return Err(ErrorModel::forbidden(
+                format!("No privileges in project {project_id}"),
+                "Forbidden",
+                None,
+            )
+            .into());
+        }
+
+        Ok(Some(
+            warehouses.into_iter().map(WarehouseIdent::from).collect(),
+        ))
+    }
+
+    async fn check_delete_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::warehouse(warehouse_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_get_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::warehouse(warehouse_id);
+        require_privilege(metadata, target, Privilege::Read, &state).await
+    }
+
+    async fn check_rename_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::warehouse(warehouse_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_deactivate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::warehouse(warehouse_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_activate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::warehouse(warehouse_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_update_storage(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::warehouse(warehouse_id);
+        require_privilege(metadata, target, Privilege::Manage, &state).await
+    }
+
+    async fn check_create_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::namespace(warehouse_id, Some(namespace));
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    async fn check_drop_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, None, view.copied());
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    async fn check_load_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, namespace, view.copied());
+        require_privilege(metadata, target, Privilege::Read, &state).await
+    }
+
+    async fn check_commit_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        view: Option<&TableIdentUuid>,
+        namespace: Option<&NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, namespace, view.copied());
+        require_privilege(metadata, target, Privilege::Write, &state).await
+    }
+
+    async fn check_rename_view(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        source: Option<&TableIdentUuid>,
+        destination: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, None, source.copied());
+        require_privilege(metadata, target, Privilege::Write, &state).await?;
+        Self::check_create_view(metadata, warehouse_id, destination, state).await
+    }
+
+    async fn check_list_views(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()> {
+        require_visible_namespace(metadata, warehouse_id, Some(namespace), &state).await
+    }
+
+    async fn check_view_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        view: Option<&TableIdentUuid>,
+        state: Self::State,
+    ) -> Result<()> {
+        let target = Target::tabular(warehouse_id, namespace, view.copied());
+        require_privilege(metadata, target, Privilege::Read, &state).await
+    }
+
+    // ---------------- Visibility ----------------
+    // Grants are inherited, so everything is visible if the parent is.
+    async fn filter_visible_tabulars(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespace: &NamespaceIdent,
+        tabulars: Vec<TabularIdentUuid>,
+        state: Self::State,
+    ) -> Result<HashSet<TabularIdentUuid>> {
+        let principal = Principal::from_metadata(metadata)?;
+        if principal.is_admin()
+            || effective_privilege(
+                &principal,
+                &Target::namespace(warehouse_id, Some(namespace)),
+                &state,
+            )
+            .await?
+            .is_some()
+        {
+            return Ok(tabulars.into_iter().collect());
+        }
+
+        let ids = tabulars.iter().map(|t| **t).collect::<Vec<_>>();
+        let granted = sqlx::query_scalar!(
+            r#"
+            WITH roles AS (
+                SELECT role_id FROM role_member
+                WHERE (member_type = 'user' AND member = $1)
+                    OR (member_type = 'group' AND member = ANY($2::text[]))
+            )
+            SELECT object_id FROM role_grant
+            WHERE role_id IN (SELECT role_id FROM roles)
+                AND object_type = 'tabular'
+                AND object_id = ANY($3)
+            "#,
+            principal.id,
+            &principal.groups,
+            &ids,
+        )
+        .fetch_all(&state.read_write.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching tabular grants".to_string()))?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+        Ok(tabulars
+            .into_iter()
+            .filter(|t| granted.contains(&**t))
+            .collect())
+    }
+
+    async fn filter_visible_namespaces(
+        metadata: &RequestMetadata,
+        warehouse_id: WarehouseIdent,
+        namespaces: Vec<NamespaceIdent>,
+        state: Self::State,
+    ) -> Result<HashSet<NamespaceIdent>> {
+        let principal = Principal::from_metadata(metadata)?;
+        if principal.is_admin()
+            || effective_privilege(&principal, &Target::warehouse(warehouse_id), &state)
+                .await?
+                .is_some()
+        {
+            return Ok(namespaces.into_iter().collect());
+        }
+
+        let grants = namespace_grants(&principal, warehouse_id, &state).await?;
+        Ok(namespaces
+            .into_iter()
+            .filter(|n| grants.iter().any(|g| g.makes_visible(n.as_ref())))
+            .collect())
+    }
+
+    fn management_router<C: Catalog, S: SecretStore>() -> axum::Router<ApiContext<State<Self, C, S>>>
+    {
+        crate::api::management::v1::role::new_v1_router::<C, S>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::implementations::postgres::namespace::tests::initialize_namespace;
+    use crate::implementations::postgres::tabular::table::tests::initialize_table;
+    use crate::implementations::postgres::warehouse::test::initialize_warehouse;
+    use crate::implementations::postgres::CatalogState;
+    use crate::service::token_verification::{Aud, AuthDetails, Claims};
+
+    fn principal(subject: &str, groups: &[&str]) -> Principal {
+        Principal {
+            id: PrincipalId::new("test", subject).to_string(),
+            groups: groups.iter().map(|g| (*g).to_string()).collect(),
+        }
+    }
+
+    fn metadata(subject: &str) -> RequestMetadata {
+        RequestMetadata {
+            request_id: uuid::Uuid::now_v7(),
+            auth_details: Some(AuthDetails::JWT(Claims {
+                sub: subject.to_string(),
+                iss: "test".to_string(),
+                aud: Aud::String("test".to_string()),
+                exp: 0,
+                iat: 0,
+                other: serde_json::json!({"groups": ["analysts"]}),
+            })),
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_grants_are_inherited(pool: sqlx::PgPool) {
+        let catalog_state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let state = PostgresAuthZState::from_pools(pool.clone(), pool);
+        let warehouse_id = initialize_warehouse(catalog_state.clone(), None, None).await;
+        let parent = NamespaceIdent::from_vec(vec!["parent".to_string()]).unwrap();
+        let child =
+            NamespaceIdent::from_vec(vec!["parent".to_string(), "child".to_string()]).unwrap();
+        let other = NamespaceIdent::from_vec(vec!["other".to_string()]).unwrap();
+        initialize_namespace(catalog_state.clone(), warehouse_id, &parent, None).await;
+        initialize_namespace(catalog_state.clone(), warehouse_id, &child, None).await;
+        initialize_namespace(catalog_state, warehouse_id, &other, None).await;
+        let parent_id = sqlx::query_scalar!(
+            "SELECT namespace_id FROM namespace WHERE warehouse_id = $1 AND namespace_name = $2",
+            *warehouse_id,
+            parent.as_ref()
+        )
+        .fetch_one(&state.read_write.read_pool)
+        .await
+        .unwrap();
+
+        let role = create_role(
+            CreateRoleRequest {
+                name: "analysts".to_string(),
+                description: None,
+                members: vec![RoleMember {
+                    member_type: RoleMemberType::Group,
+                    name: "analysts".to_string(),
+                }],
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+        grant(
+            role.role_id,
+            Grant {
+                object_type: GrantObjectType::Namespace,
+                object_id: parent_id,
+                privilege: Privilege::Write,
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+
+        let analyst = principal("alice", &["analysts"]);
+        let privilege = |namespace: &NamespaceIdent| {
+            let target = Target::namespace(warehouse_id, Some(namespace));
+            let analyst = analyst.clone();
+            let state = state.clone();
+            async move {
+                effective_privilege(&analyst, &target, &state)
+                    .await
+                    .unwrap()
+            }
+        };
+        assert_eq!(privilege(&parent).await, Some(Privilege::Write));
+        assert_eq!(privilege(&child).await, Some(Privilege::Write));
+        assert_eq!(privilege(&other).await, None);
+        assert_eq!(
+            effective_privilege(&analyst, &Target::warehouse(warehouse_id), &state)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            effective_privilege(
+                &principal("bob", &[]),
+                &Target::namespace(warehouse_id, Some(&child)),
+                &state
+            )
+            .await
+            .unwrap(),
+            None
+        );
+
+        let metadata = metadata("alice");
+        PostgresAuthZHandler::check_create_table(&metadata, warehouse_id, &child, state.clone())
+            .await
+            .unwrap();
+        let err =
+            PostgresAuthZHandler::check_delete_warehouse(&metadata, warehouse_id, state.clone())
+                .await
+                .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+
+        let projects = PostgresAuthZHandler::check_list_projects(&metadata, state.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+        let warehouses = PostgresAuthZHandler::check_list_warehouse_in_project(
+            &metadata,
+            *projects.iter().next().unwrap(),
+            state.clone(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(warehouses, HashSet::from([warehouse_id]));
+
+        revoke(role.role_id, GrantObjectType::Namespace, parent_id, &state)
+            .await
+            .unwrap();
+        assert_eq!(privilege(&child).await, None);
+    }
+
+    #[sqlx::test]
+    async fn test_rename_requires_write_on_destination(pool: sqlx::PgPool) {
+        let catalog_state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let state = PostgresAuthZState::from_pools(pool.clone(), pool);
+        let warehouse_id = initialize_warehouse(catalog_state.clone(), None, None).await;
+        let source = NamespaceIdent::from_vec(vec!["source".to_string()]).unwrap();
+        let other = NamespaceIdent::from_vec(vec!["other".to_string()]).unwrap();
+        initialize_namespace(catalog_state.clone(), warehouse_id, &source, None).await;
+        initialize_namespace(catalog_state.clone(), warehouse_id, &other, None).await;
+        let table = initialize_table(
+            warehouse_id,
+            catalog_state,
+            false,
+            Some(source.clone()),
+            None,
+        )
+        .await;
+
+        let role = create_role(
+            CreateRoleRequest {
+                name: "analysts".to_string(),
+                description: None,
+                members: vec![RoleMember {
+                    member_type: RoleMemberType::Group,
+                    name: "analysts".to_string(),
+                }],
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+        grant(
+            role.role_id,
+            Grant {
+                object_type: GrantObjectType::Namespace,
+                object_id: *table.namespace_id,
+                privilege: Privilege::Write,
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+
+        let metadata = metadata("alice");
+        PostgresAuthZHandler::check_rename_table(
+            &metadata,
+            warehouse_id,
+            Some(table.table_id),
+            &source,
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        let err = PostgresAuthZHandler::check_rename_table(
+            &metadata,
+            warehouse_id,
+            Some(table.table_id),
+            &other,
+            state,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+    }
+
+    #[sqlx::test]
+    async fn test_listings_are_filtered_by_tabular_grants(pool: sqlx::PgPool) {
+        let catalog_state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let state = PostgresAuthZState::from_pools(pool.clone(), pool);
+        let warehouse_id = initialize_warehouse(catalog_state.clone(), None, None).await;
+        let parent = NamespaceIdent::from_vec(vec!["parent".to_string()]).unwrap();
+        let child =
+            NamespaceIdent::from_vec(vec!["parent".to_string(), "child".to_string()]).unwrap();
+        let other = NamespaceIdent::from_vec(vec!["other".to_string()]).unwrap();
+        initialize_namespace(catalog_state.clone(), warehouse_id, &parent, None).await;
+        initialize_namespace(catalog_state.clone(), warehouse_id, &child, None).await;
+        initialize_namespace(catalog_state.clone(), warehouse_id, &other, None).await;
+        let visible = initialize_table(
+            warehouse_id,
+            catalog_state.clone(),
+            false,
+            Some(child.clone()),
+            Some("visible".to_string()),
+        )
+        .await;
+        let hidden = initialize_table(
+            warehouse_id,
+            catalog_state,
+            false,
+            Some(child.clone()),
+            Some("hidden".to_string()),
+        )
+        .await;
+
+        let role = create_role(
+            CreateRoleRequest {
+                name: "analysts".to_string(),
+                description: None,
+                members: vec![RoleMember {
+                    member_type: RoleMemberType::Group,
+                    name: "analysts".to_string(),
+                }],
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+        grant(
+            role.role_id,
+            Grant {
+                object_type: GrantObjectType::Tabular,
+                object_id: *visible.table_id,
+                privilege: Privilege::Read,
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+
+        // Namespaces on the path to the table can be listed
+        let metadata = metadata("alice");
+        PostgresAuthZHandler::check_list_namespace(&metadata, warehouse_id, None, state.clone())
+            .await
+            .unwrap();
+        let namespaces = PostgresAuthZHandler::filter_visible_namespaces(
+            &metadata,
+            warehouse_id,
+            vec![parent.clone(), other.clone()],
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(namespaces, HashSet::from([parent.clone()]));
+        PostgresAuthZHandler::check_list_namespace(
+            &metadata,
+            warehouse_id,
+            Some(&parent),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        let namespaces = PostgresAuthZHandler::filter_visible_namespaces(
+            &metadata,
+            warehouse_id,
+            vec![child.clone()],
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(namespaces, HashSet::from([child.clone()]));
+
+        // Only the granted table is listed
+        PostgresAuthZHandler::check_list_tables(&metadata, warehouse_id, &child, state.clone())
+            .await
+            .unwrap();
+        let tabulars = PostgresAuthZHandler::filter_visible_tabulars(
+            &metadata,
+            warehouse_id,
+            &child,
+            vec![
+                TabularIdentUuid::Table(*visible.table_id),
+                TabularIdentUuid::Table(*hidden.table_id),
+            ],
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            tabulars,
+            HashSet::from([TabularIdentUuid::Table(*visible.table_id)])
+        );
+
+        let err = PostgresAuthZHandler::check_list_tables(&metadata, warehouse_id, &other, state)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+    }
+
+    #[sqlx::test]
+    async fn test_role_members(pool: sqlx::PgPool) {
+        let state = PostgresAuthZState::from_pools(pool.clone(), pool);
+        let role = create_role(
+            CreateRoleRequest {
+                name: "engineers".to_string(),
+                description: Some("Data engineers".to_string()),
+                members: vec![RoleMember {
+                    member_type: RoleMemberType::User,
+                    name: "https://idp.example.com|alice".to_string(),
+                }],
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+        assert_eq!(role.members.len(), 1);
+
+        // Users must be qualified by their issuer
+        let err = create_role(
+            CreateRoleRequest {
+                name: "operators".to_string(),
+                description: None,
+                members: vec![RoleMember {
+                    member_type: RoleMemberType::User,
+                    name: "alice".to_string(),
+                }],
+            },
+            &state,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::BAD_REQUEST);
+
+        let err = create_role(
+            CreateRoleRequest {
+                name: "Engineers".to_string(),
+                description: None,
+                members: vec![],
+            },
+            &state,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+
+        let members = vec![
+            RoleMember {
+                member_type: RoleMemberType::User,
+                name: "https://idp.example.com|bob".to_string(),
+            },
+            RoleMember {
+                member_type: RoleMemberType::Group,
+                name: "engineering".to_string(),
+            },
+        ];
+        let role = set_role_members(role.role_id, members.clone(), &state)
+            .await
+            .unwrap();
+        assert_eq!(
+            role.members.into_iter().collect::<HashSet<_>>(),
+            members.into_iter().collect::<HashSet<_>>()
+        );
+
+        assert_eq!(list_roles(&state).await.unwrap().len(), 1);
+        delete_role(role.role_id, &state).await.unwrap();
+        let err = get_role(role.role_id, &state).await.unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,26 +1,39 @@
 use super::{
+    api_key::{
+        create_api_key, get_api_key_by_hash, list_api_keys, revoke_api_key, set_api_key_expiry,
+    },
+    event_outbox::{
+        delete_sent_events, enqueue_events, list_due_events, lock_event_outbox, mark_event_failed,
+        mark_event_sent, reschedule_events,
+    },
     namespace::{
-        create_namespace, drop_namespace, get_namespace, list_namespaces, namespace_ident_to_id,
+        create_namespace, drop_namespace, get_namespace, get_namespace_by_id, list_namespaces,
+        namespace_contains_tabulars, namespace_ident_to_id, set_namespace_storage_profile,
         update_namespace_properties,
     },
+    project::{
+        create_project, delete_project, get_project, list_projects, rename_project,
+        set_project_status,
+    },
     tabular::table::{
         commit_table_transaction, create_table, drop_table, get_table_metadata_by_id,
         get_table_metadata_by_s3_location, list_tables, load_tables, rename_table,
         table_ident_to_id, table_idents_to_ids,
     },
     warehouse::{
-        create_warehouse, delete_warehouse, get_warehouse, list_projects, list_warehouses,
-        rename_warehouse, set_warehouse_status, update_storage_profile,
+        create_warehouse, delete_warehouse, get_warehouse, list_warehouses, rename_warehouse,
+        set_warehouse_status, update_storage_profile,
     },
     CatalogState, PostgresTransaction,
 };
 use crate::implementations::postgres::tabular::view::{
     create_view, drop_view, list_views, load_view, rename_view, view_ident_to_id,
 };
+use crate::service::event_publisher::{OutboxEvent, Payload};
 use crate::service::{
-    CreateNamespaceRequest, CreateNamespaceResponse, CreateTableRequest, GetWarehouseResponse,
-    ListNamespacesQuery, ListNamespacesResponse, NamespaceIdent, Result, TableIdent,
-    WarehouseStatus,
+    api_key::ApiKey, CreateNamespaceRequest, CreateNamespaceResponse, CreateTableRequest,
+    GetProjectResponse, GetWarehouseResponse, ListNamespacesQuery, ListNamespacesResponse,
+    NamespaceIdent, ProjectStatus, Result, TableIdent, WarehouseStatus,
 };
 use crate::{
     api::iceberg::v1::{PaginatedTabulars, PaginationQuery},
@@ -74,6 +87,14 @@ impl Catalog for super::Catalog {
         get_namespace(warehouse_id, namespace, transaction).await
     }
 
+    async fn get_namespace_by_id<'a>(
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<GetNamespaceResponse> {
+        get_namespace_by_id(warehouse_id, namespace_id, transaction).await
+    }
+
     async fn list_namespaces(
         warehouse_id: WarehouseIdent,
         query: &ListNamespacesQuery,
@@ -116,6 +137,31 @@ impl Catalog for super::Catalog {
         update_namespace_properties(warehouse_id, namespace, properties, transaction).await
     }
 
+    async fn set_namespace_storage_profile<'a>(
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        storage_profile: Option<StorageProfile>,
+        storage_secret_id: Option<SecretIdent>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_namespace_storage_profile(
+            warehouse_id,
+            namespace_id,
+            storage_profile,
+            storage_secret_id,
+            transaction,
+        )
+        .await
+    }
+
+    async fn namespace_contains_tabulars<'a>(
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<bool> {
+        namespace_contains_tabulars(warehouse_id, namespace_id, transaction).await
+    }
+
     async fn create_table<'a>(
         namespace_id: NamespaceIdentUuid,
         table: &TableIdent,
@@ -226,8 +272,50 @@ impl Catalog for super::Catalog {
     }
 
     // ---------------- Management API ----------------
-    async fn list_projects(catalog_state: Self::State) -> Result<HashSet<ProjectIdent>> {
-        list_projects(catalog_state).await
+    async fn create_project<'a>(
+        project_id: ProjectIdent,
+        project_name: String,
+        description: Option<String>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        create_project(project_id, project_name, description, transaction).await
+    }
+
+    async fn get_project<'a>(
+        project_id: ProjectIdent,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<Option<GetProjectResponse>> {
+        get_project(project_id, transaction).await
+    }
+
+    async fn list_projects(
+        project_id_filter: Option<&HashSet<ProjectIdent>>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<GetProjectResponse>> {
+        list_projects(project_id_filter, catalog_state).await
+    }
+
+    async fn rename_project<'a>(
+        project_id: ProjectIdent,
+        new_name: &str,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        rename_project(project_id, new_name, transaction).await
+    }
+
+    async fn delete_project<'a>(
+        project_id: ProjectIdent,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        delete_project(project_id, transaction).await
+    }
+
+    async fn set_project_status<'a>(
+        project_id: ProjectIdent,
+        status: ProjectStatus,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_project_status(project_id, status, transaction).await
     }
 
     async fn list_warehouses(
@@ -300,6 +388,53 @@ impl Catalog for super::Catalog {
         .await
     }
 
+    async fn create_api_key(
+        name: &str,
+        owner: &str,
+        warehouse_id: Option<WarehouseIdent>,
+        key_hash: &[u8],
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        catalog_state: Self::State,
+    ) -> Result<ApiKey> {
+        create_api_key(
+            name,
+            owner,
+            warehouse_id,
+            key_hash,
+            expires_at,
+            catalog_state,
+        )
+        .await
+    }
+
+    async fn list_api_keys(owner: &str, catalog_state: Self::State) -> Result<Vec<ApiKey>> {
+        list_api_keys(owner, catalog_state).await
+    }
+
+    async fn get_api_key_by_hash(
+        key_hash: &[u8],
+        catalog_state: Self::State,
+    ) -> Result<Option<ApiKey>> {
+        get_api_key_by_hash(key_hash, catalog_state).await
+    }
+
+    async fn set_api_key_expiry(
+        api_key_id: uuid::Uuid,
+        owner: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        catalog_state: Self::State,
+    ) -> Result<ApiKey> {
+        set_api_key_expiry(api_key_id, owner, expires_at, catalog_state).await
+    }
+
+    async fn revoke_api_key(
+        api_key_id: uuid::Uuid,
+        owner: &str,
+        catalog_state: Self::State,
+    ) -> Result<()> {
+        revoke_api_key(api_key_id, owner, catalog_state).await
+    }
+
     async fn view_ident_to_id(
         warehouse_id: WarehouseIdent,
         view: &TableIdent,
@@ -369,4 +504,57 @@ impl Catalog for super::Catalog {
         // we want to be able to undrop views, hence hard_delete -> false
         drop_view(table_id, false, transaction).await
     }
+
+    async fn enqueue_events<'a>(
+        events: Vec<Payload>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        enqueue_events(events, transaction).await
+    }
+
+    async fn lock_event_outbox<'a>(
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<bool> {
+        lock_event_outbox(transaction).await
+    }
+
+    async fn list_due_events<'a>(
+        now: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<Vec<OutboxEvent>> {
+        list_due_events(now, limit, transaction).await
+    }
+
+    async fn reschedule_events<'a>(
+        sequence_ids: &[i64],
+        next_attempt_at: chrono::DateTime<chrono::Utc>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        reschedule_events(sequence_ids, next_attempt_at, transaction).await
+    }
+
+    async fn mark_event_sent<'a>(
+        sequence_id: i64,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        mark_event_sent(sequence_id, transaction).await
+    }
+
+    async fn mark_event_failed<'a>(
+        sequence_id: i64,
+        error: &str,
+        sent_to: &[String],
+        next_attempt_at: Option<chrono::DateTime<chrono::Utc>>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        mark_event_failed(sequence_id, error, sent_to, next_attempt_at, transaction).await
+    }
+
+    async fn delete_sent_events<'a>(
+        sent_before: chrono::DateTime<chrono::Utc>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()> {
+        delete_sent_events(sent_before, transaction).await
+    }
 }
@@ -0,0 +1,292 @@
+use super::dbutils::DBErrorHandler as _;
+use crate::api::{ErrorModel, Result};
+use crate::service::event_publisher::{OutboxEvent, Payload};
+
+/// Key of the advisory lock held by a relay while it claims events.
+const EVENT_OUTBOX_LOCK_ID: i64 = 0x6576_656e_745f_6f62;
+
+#[derive(sqlx::FromRow, Debug)]
+struct OutboxRecord {
+    sequence_id: i64,
+    event_id: uuid::Uuid,
+    event_type: String,
+    data: serde_json::Value,
+    metadata: serde_json::Value,
+    attempts: i32,
+    next_attempt_at: chrono::DateTime<chrono::Utc>,
+    sent_to: Vec<String>,
+}
+
+impl TryFrom<OutboxRecord> for OutboxEvent {
+    type Error = ErrorModel;
+
+    fn try_from(record: OutboxRecord) -> std::result::Result<Self, Self::Error> {
+        let metadata = serde_json::from_value(record.metadata).map_err(|e| {
+            ErrorModel::internal(
+                "Error deserializing metadata of event",
+                "EventMetadataDeserializationError",
+                Some(Box::new(e)),
+            )
+        })?;
+
+        Ok(Self {
+            sequence_id: record.sequence_id,
+            payload: Payload {
+                id: record.event_id,
+                typ: record.event_type,
+                data: record.data,
+                metadata,
+            },
+            attempts: record.attempts,
+            next_attempt_at: record.next_attempt_at,
+            sent_to: record.sent_to,
+        })
+    }
+}
+
+pub(crate) async fn enqueue_events(
+    events: Vec<Payload>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    // One statement per event, so that sequence ids follow the order of `events`.
+    for event in events {
+        let ordering_key = event.ordering_key();
+        let metadata = serde_json::to_value(&event.metadata).map_err(|e| {
+            ErrorModel::internal(
+                "Error serializing metadata of event",
+                "EventMetadataSerializationError",
+                Some(Box::new(e)),
+            )
+        })?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO event_outbox (event_id, event_type, ordering_key, data, metadata)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            event.id,
+            event.typ,
+            ordering_key,
+            event.data,
+            metadata
+        )
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error writing event to outbox".into()))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn lock_event_outbox(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<bool> {
+    let locked = sqlx::query_scalar!(
+        r#"SELECT pg_try_advisory_xact_lock($1) as "locked!""#,
+        EVENT_OUTBOX_LOCK_ID
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error locking event outbox".into()))?;
+
+    Ok(locked)
+}
+
+pub(crate) async fn list_due_events(
+    now: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<Vec<OutboxEvent>> {
+    let records = sqlx::query_as!(
+        OutboxRecord,
+        r#"
+        SELECT
+            sequence_id,
+            event_id,
+            event_type,
+            data,
+            metadata,
+            attempts,
+            next_attempt_at,
+            sent_to
+        FROM event_outbox
+        WHERE sent_at IS NULL AND failed_at IS NULL
+            AND ordering_key NOT IN (
+                SELECT ordering_key FROM event_outbox
+                WHERE sent_at IS NULL AND failed_at IS NULL AND next_attempt_at > $1
+            )
+        ORDER BY sequence_id
+        LIMIT $2
+        "#,
+        now,
+        limit
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching events from outbox".into()))?;
+
+    Ok(records
+        .into_iter()
+        .map(OutboxEvent::try_from)
+        .collect::<std::result::Result<_, _>>()?)
+}
+
+pub(crate) async fn reschedule_events(
+    sequence_ids: &[i64],
+    next_attempt_at: chrono::DateTime<chrono::Utc>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE event_outbox
+        SET next_attempt_at = $2
+        WHERE sequence_id = ANY($1)
+        "#,
+        sequence_ids,
+        next_attempt_at
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error rescheduling events".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn mark_event_sent(
+    sequence_id: i64,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE event_outbox
+        SET sent_at = now()
+        WHERE sequence_id = $1
+        "#,
+        sequence_id
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error marking event as sent".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn mark_event_failed(
+    sequence_id: i64,
+    error: &str,
+    sent_to: &[String],
+    next_attempt_at: Option<chrono::DateTime<chrono::Utc>>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE event_outbox
+        SET attempts = attempts + 1,
+            next_attempt_at = COALESCE($2, next_attempt_at),
+            failed_at = CASE WHEN $2::timestamptz IS NULL THEN now() END,
+            last_error = $3,
+            sent_to = $4
+        WHERE sequence_id = $1
+        "#,
+        sequence_id,
+        next_attempt_at,
+        error,
+        sent_to
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error marking event as failed".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn delete_sent_events(
+    sent_before: chrono::DateTime<chrono::Utc>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM event_outbox
+        WHERE sent_at < $1
+        "#,
+        sent_before
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error deleting sent events".into()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{CatalogState, PostgresTransaction};
+    use super::*;
+    use crate::service::event_publisher::{EventEntity, EventMetadata};
+    use crate::service::Transaction as _;
+
+    #[sqlx::test]
+    async fn test_enqueue_send_and_delete_events(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let payload = Payload {
+            id: uuid::Uuid::now_v7(),
+            typ: "createTable".to_string(),
+            data: serde_json::json!({"name": "my_table"}),
+            metadata: EventMetadata {
+                entity: EventEntity::Table(uuid::Uuid::now_v7()),
+                warehouse_id: uuid::Uuid::now_v7(),
+                name: "my_table".to_string(),
+                namespace: "my_namespace".to_string(),
+                prefix: String::new(),
+                num_events: 1,
+                sequence_number: 0,
+                trace_id: uuid::Uuid::now_v7(),
+            },
+        };
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        enqueue_events(vec![payload.clone()], transaction.transaction())
+            .await
+            .unwrap();
+        assert!(lock_event_outbox(transaction.transaction()).await.unwrap());
+        let events = list_due_events(chrono::Utc::now(), 10, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].payload, payload);
+        assert_eq!(events[0].attempts, 0);
+        assert!(events[0].sent_to.is_empty());
+
+        // Another transaction can't relay events while the lock is held
+        let mut other = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        assert!(!lock_event_outbox(other.transaction()).await.unwrap());
+        other.rollback().await.unwrap();
+
+        mark_event_sent(events[0].sequence_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert!(
+            list_due_events(chrono::Utc::now(), 10, transaction.transaction())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        delete_sent_events(
+            chrono::Utc::now() + chrono::Duration::seconds(1),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let count = sqlx::query_scalar!(r#"SELECT count(*) as "count!" FROM event_outbox"#)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -1,7 +1,11 @@
+mod api_key;
+pub(crate) mod authz;
 mod catalog;
 pub(crate) mod dbutils;
+mod event_outbox;
 pub(crate) mod namespace;
 mod pagination;
+mod project;
 pub(crate) mod secrets;
 pub(crate) mod tabular;
 pub(crate) mod warehouse;
@@ -13,7 +17,9 @@ use crate::service::health::{Health, HealthExt, HealthStatus};
 use crate::CONFIG;
 use anyhow::anyhow;
 use async_trait::async_trait;
+pub use authz::{PostgresAuthZHandler, PostgresAuthZState};
 pub use secrets::SecretsState as SecretsStore;
+pub use secrets::{KeyRotationStats, Keyring};
 use sqlx::migrate::{Migrate, MigrateError};
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::{ConnectOptions, Error, Executor, PgPool};
@@ -1,11 +1,15 @@
 use super::{dbutils::DBErrorHandler, CatalogState};
 use crate::api::iceberg::v1::MAX_PAGE_SIZE;
 use crate::implementations::postgres::pagination::{PaginateToken, V1PaginateToken};
+use crate::service::storage::StorageProfile;
 use crate::service::{
     CreateNamespaceRequest, CreateNamespaceResponse, ErrorModel, GetNamespaceResponse,
     ListNamespacesQuery, ListNamespacesResponse, NamespaceIdent, Result,
 };
-use crate::{catalog::namespace::MAX_NAMESPACE_DEPTH, service::NamespaceIdentUuid, WarehouseIdent};
+use crate::{
+    catalog::namespace::MAX_NAMESPACE_DEPTH, service::NamespaceIdentUuid, SecretIdent,
+    WarehouseIdent,
+};
 use chrono::Utc;
 use http::StatusCode;
 use sqlx::types::Json;
@@ -22,9 +26,23 @@ pub(crate) async fn get_namespace(
         SELECT 
             namespace_id,
             n.warehouse_id,
-            namespace_properties as "properties: Json<Option<HashMap<String, String>>>"
+            namespace_properties as "properties: Json<Option<HashMap<String, String>>>",
+            n.storage_profile as "storage_profile: Json<StorageProfile>",
+            n.storage_secret_id,
+            p.storage_profile as "inherited_storage_profile?: Json<StorageProfile>",
+            p.storage_secret_id as "inherited_storage_secret_id?"
         FROM namespace n
         INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        LEFT JOIN LATERAL (
+            SELECT a.storage_profile, a.storage_secret_id
+            FROM namespace a
+            WHERE a.warehouse_id = n.warehouse_id
+            AND a.storage_profile IS NOT NULL
+            AND cardinality(a.namespace_name) < cardinality(n.namespace_name)
+            AND n.namespace_name[1:cardinality(a.namespace_name)] = a.namespace_name
+            ORDER BY cardinality(a.namespace_name) DESC
+            LIMIT 1
+        ) p ON true
         WHERE n.warehouse_id = $1 AND n.namespace_name = $2
         AND w.status = 'active'
         "#,
@@ -47,9 +65,175 @@ pub(crate) async fn get_namespace(
         properties: row.properties.deref().clone(),
         namespace_id: row.namespace_id.into(),
         warehouse_id: row.warehouse_id.into(),
+        storage_profile: row.storage_profile.map(|p| p.deref().clone()),
+        storage_secret_id: row.storage_secret_id.map(SecretIdent::from),
+        inherited_storage_profile: row.inherited_storage_profile.map(|p| p.deref().clone()),
+        inherited_storage_secret_id: row.inherited_storage_secret_id.map(SecretIdent::from),
     })
 }
 
+pub(crate) async fn get_namespace_by_id(
+    warehouse_id: WarehouseIdent,
+    namespace_id: NamespaceIdentUuid,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<GetNamespaceResponse> {
+    let row = sqlx::query!(
+        r#"
+        SELECT 
+            namespace_name as "namespace_name: Vec<String>",
+            n.warehouse_id,
+            namespace_properties as "properties: Json<Option<HashMap<String, String>>>",
+            n.storage_profile as "storage_profile: Json<StorageProfile>",
+            n.storage_secret_id,
+            p.storage_profile as "inherited_storage_profile?: Json<StorageProfile>",
+            p.storage_secret_id as "inherited_storage_secret_id?"
+        FROM namespace n
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        LEFT JOIN LATERAL (
+            SELECT a.storage_profile, a.storage_secret_id
+            FROM namespace a
+            WHERE a.warehouse_id = n.warehouse_id
+            AND a.storage_profile IS NOT NULL
+            AND cardinality(a.namespace_name) < cardinality(n.namespace_name)
+            AND n.namespace_name[1:cardinality(a.namespace_name)] = a.namespace_name
+            ORDER BY cardinality(a.namespace_name) DESC
+            LIMIT 1
+        ) p ON true
+        WHERE n.warehouse_id = $1 AND n.namespace_id = $2
+        AND w.status = 'active'
+        "#,
+        *warehouse_id,
+        *namespace_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::RowNotFound => ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message(format!("Namespace not found: {namespace_id}"))
+            .r#type("NamespaceNotFound".to_string())
+            .build(),
+        _ => e.into_error_model("Error fetching namespace".to_string()),
+    })?;
+
+    Ok(GetNamespaceResponse {
+        namespace: NamespaceIdent::from_vec(row.namespace_name).map_err(|e| {
+            ErrorModel::internal(
+                "Error parsing namespace name from database",
+                "NamespaceParseError",
+                Some(Box::new(e)),
+            )
+        })?,
+        properties: row.properties.deref().clone(),
+        namespace_id,
+        warehouse_id: row.warehouse_id.into(),
+        storage_profile: row.storage_profile.map(|p| p.deref().clone()),
+        storage_secret_id: row.storage_secret_id.map(SecretIdent::from),
+        inherited_storage_profile: row.inherited_storage_profile.map(|p| p.deref().clone()),
+        inherited_storage_secret_id: row.inherited_storage_secret_id.map(SecretIdent::from),
+    })
+}
+
+pub(crate) async fn set_namespace_storage_profile(
+    warehouse_id: WarehouseIdent,
+    namespace_id: NamespaceIdentUuid,
+    storage_profile: Option<StorageProfile>,
+    storage_secret_id: Option<SecretIdent>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let storage_profile_ser = storage_profile
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| {
+            ErrorModel::internal(
+                "Error serializing storage profile",
+                "StorageProfileSerializationError",
+                Some(Box::new(e)),
+            )
+        })?;
+
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE namespace n
+            SET storage_profile = $1, storage_secret_id = $2
+            FROM warehouse w
+            WHERE n.warehouse_id = w.warehouse_id
+            AND n.warehouse_id = $3
+            AND n.namespace_id = $4
+            AND w.status = 'active'
+            RETURNING n.namespace_id
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        storage_profile_ser,
+        storage_secret_id.map(|id| id.into_uuid()),
+        *warehouse_id,
+        *namespace_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error updating namespace storage profile".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message(format!("Namespace not found: {namespace_id}"))
+            .r#type("NamespaceNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn namespace_contains_tabulars(
+    warehouse_id: WarehouseIdent,
+    namespace_id: NamespaceIdentUuid,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<bool> {
+    // Creating or moving a tabular takes a key share lock on its namespace,
+    // which conflicts with this lock.
+    sqlx::query!(
+        r#"
+        SELECT d.namespace_id
+        FROM namespace n
+        INNER JOIN namespace d ON d.warehouse_id = n.warehouse_id
+            AND cardinality(d.namespace_name) >= cardinality(n.namespace_name)
+            AND d.namespace_name[1:cardinality(n.namespace_name)] = n.namespace_name
+        WHERE n.warehouse_id = $1 AND n.namespace_id = $2
+        FOR UPDATE OF d
+        "#,
+        *warehouse_id,
+        *namespace_id
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error locking namespaces".to_string()))?;
+
+    let contains_tabulars = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM tabular t
+            INNER JOIN namespace d ON t.namespace_id = d.namespace_id
+            INNER JOIN namespace n ON d.warehouse_id = n.warehouse_id
+            WHERE n.warehouse_id = $1 AND n.namespace_id = $2
+            AND cardinality(d.namespace_name) >= cardinality(n.namespace_name)
+            AND d.namespace_name[1:cardinality(n.namespace_name)] = n.namespace_name
+        ) as "contains_tabulars!"
+        "#,
+        *warehouse_id,
+        *namespace_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error checking namespace for tabulars".to_string()))?;
+
+    Ok(contains_tabulars)
+}
+
 #[allow(clippy::too_many_lines)]
 pub(crate) async fn list_namespaces(
     warehouse_id: WarehouseIdent,
@@ -666,4 +850,213 @@ pub(crate) mod tests {
         assert_eq!(response.error.code, StatusCode::CONFLICT);
         assert_eq!(response.error.r#type, "NamespaceAlreadyExists");
     }
+
+    #[sqlx::test]
+    async fn test_namespace_storage_profile_override(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let namespace = NamespaceIdent::from_vec(vec!["test".to_string()]).unwrap();
+        let response = initialize_namespace(state.clone(), warehouse_id, &namespace, None).await;
+
+        let mut transaction = PostgresTransaction::begin_read(state.clone())
+            .await
+            .unwrap();
+        let namespace_id =
+            Catalog::get_namespace(warehouse_id, &namespace, transaction.transaction())
+                .await
+                .unwrap()
+                .namespace_id;
+        drop(transaction);
+
+        let storage_profile = StorageProfile::S3(crate::service::storage::S3Profile {
+            bucket: "regulated_bucket".to_string(),
+            endpoint: None,
+            region: "eu-central-1".to_string(),
+            assume_role_arn: None,
+            path_style_access: None,
+            key_prefix: None,
+            sts_role_arn: None,
+            sts_enabled: false,
+            flavor: crate::service::storage::S3Flavor::Aws,
+            server_side_encryption: None,
+        });
+        let secret_id = SecretIdent::from(uuid::Uuid::now_v7());
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::set_namespace_storage_profile(
+            warehouse_id,
+            namespace_id,
+            Some(storage_profile.clone()),
+            Some(secret_id),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let mut transaction = PostgresTransaction::begin_read(state.clone())
+            .await
+            .unwrap();
+        let loaded =
+            Catalog::get_namespace_by_id(warehouse_id, namespace_id, transaction.transaction())
+                .await
+                .unwrap();
+        drop(transaction);
+
+        assert_eq!(loaded.namespace, response.namespace);
+        assert_eq!(loaded.storage_profile, Some(storage_profile));
+        assert_eq!(loaded.storage_secret_id, Some(secret_id));
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::set_namespace_storage_profile(
+            warehouse_id,
+            namespace_id,
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let mut transaction = PostgresTransaction::begin_read(state.clone())
+            .await
+            .unwrap();
+        let loaded = Catalog::get_namespace(warehouse_id, &namespace, transaction.transaction())
+            .await
+            .unwrap();
+        drop(transaction);
+
+        assert_eq!(loaded.storage_profile, None);
+        assert_eq!(loaded.storage_secret_id, None);
+    }
+
+    #[sqlx::test]
+    async fn test_namespace_storage_profile_is_inherited(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let parent = NamespaceIdent::from_vec(vec!["parent".to_string()]).unwrap();
+        let child =
+            NamespaceIdent::from_vec(vec!["parent".to_string(), "child".to_string()]).unwrap();
+        initialize_namespace(state.clone(), warehouse_id, &parent, None).await;
+        initialize_namespace(state.clone(), warehouse_id, &child, None).await;
+        let parent_id = Catalog::namespace_ident_to_id(warehouse_id, &parent, state.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        let child_id = Catalog::namespace_ident_to_id(warehouse_id, &child, state.clone())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let contains_tabulars = Catalog::namespace_contains_tabulars(
+            warehouse_id,
+            parent_id,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        assert!(!contains_tabulars);
+        drop(transaction);
+
+        let table = initialize_table(warehouse_id, state.clone(), false, Some(child), None).await;
+
+        let storage_profile = StorageProfile::S3(crate::service::storage::S3Profile {
+            bucket: "regulated_bucket".to_string(),
+            endpoint: None,
+            region: "eu-central-1".to_string(),
+            assume_role_arn: None,
+            path_style_access: None,
+            key_prefix: None,
+            sts_role_arn: None,
+            sts_enabled: false,
+            flavor: crate::service::storage::S3Flavor::Aws,
+            server_side_encryption: None,
+        });
+        let secret_id = SecretIdent::from(uuid::Uuid::now_v7());
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let contains_tabulars = Catalog::namespace_contains_tabulars(
+            warehouse_id,
+            parent_id,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        assert!(contains_tabulars);
+        Catalog::set_namespace_storage_profile(
+            warehouse_id,
+            parent_id,
+            Some(storage_profile.clone()),
+            Some(secret_id),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let mut transaction = PostgresTransaction::begin_read(state.clone())
+            .await
+            .unwrap();
+        let loaded =
+            Catalog::get_namespace_by_id(warehouse_id, child_id, transaction.transaction())
+                .await
+                .unwrap();
+        let tables = Catalog::load_tables(
+            warehouse_id,
+            vec![table.table_id],
+            false,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        drop(transaction);
+
+        assert_eq!(loaded.storage_profile, None);
+        assert_eq!(
+            loaded.inherited_storage_profile,
+            Some(storage_profile.clone())
+        );
+        assert_eq!(loaded.inherited_storage_secret_id, Some(secret_id));
+        assert_eq!(
+            loaded.storage_override(),
+            Some((&storage_profile, Some(secret_id)))
+        );
+
+        let table = &tables[&table.table_id];
+        assert_eq!(table.storage_profile, storage_profile);
+        assert_eq!(table.storage_secret_ident, Some(secret_id));
+    }
+
+    #[sqlx::test]
+    async fn test_set_storage_profile_of_unknown_namespace(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let result = Catalog::set_namespace_storage_profile(
+            warehouse_id,
+            NamespaceIdentUuid::default(),
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(result.error.code, StatusCode::NOT_FOUND);
+    }
 }
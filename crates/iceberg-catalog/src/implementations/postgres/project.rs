@@ -0,0 +1,416 @@
+use std::collections::HashSet;
+
+use super::dbutils::DBErrorHandler as _;
+use super::CatalogState;
+use crate::api::{ErrorModel, Result};
+use crate::service::{GetProjectResponse, ProjectStatus};
+use crate::ProjectIdent;
+use http::StatusCode;
+
+#[derive(sqlx::FromRow, Debug)]
+struct ProjectRecord {
+    project_id: uuid::Uuid,
+    project_name: String,
+    description: Option<String>,
+    status: ProjectStatus,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ProjectRecord> for GetProjectResponse {
+    fn from(record: ProjectRecord) -> Self {
+        Self {
+            project_id: ProjectIdent::from(record.project_id),
+            name: record.project_name,
+            description: record.description,
+            status: record.status,
+            created_at: record.created_at,
+        }
+    }
+}
+
+pub(crate) async fn create_project(
+    project_id: ProjectIdent,
+    project_name: String,
+    description: Option<String>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    validate_project_name(&project_name)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO project (project_id, project_name, description)
+        VALUES ($1, $2, $3)
+        "#,
+        *project_id,
+        project_name,
+        description
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_err) => match db_err.constraint() {
+            Some("unique_project_name") => ErrorModel::builder()
+                .code(StatusCode::CONFLICT.into())
+                .message("Project with this name already exists.".to_string())
+                .r#type("ProjectNameAlreadyExists".to_string())
+                .build(),
+            Some("project_pkey") => ErrorModel::builder()
+                .code(StatusCode::CONFLICT.into())
+                .message("Project with this id already exists.".to_string())
+                .r#type("ProjectIdAlreadyExists".to_string())
+                .build(),
+            _ => e.into_error_model("Error creating project".into()),
+        },
+        _ => e.into_error_model("Error creating project".into()),
+    })?;
+
+    Ok(())
+}
+
+pub(crate) async fn get_project(
+    project_id: ProjectIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<Option<GetProjectResponse>> {
+    let project = sqlx::query_as!(
+        ProjectRecord,
+        r#"
+        SELECT
+            project_id,
+            project_name,
+            description,
+            status AS "status: ProjectStatus",
+            created_at
+        FROM project
+        WHERE project_id = $1
+        "#,
+        *project_id
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching project".into()))?;
+
+    Ok(project.map(Into::into))
+}
+
+pub(crate) async fn list_projects(
+    project_id_filter: Option<&HashSet<ProjectIdent>>,
+    catalog_state: CatalogState,
+) -> Result<Vec<GetProjectResponse>> {
+    let project_ids: Option<Vec<uuid::Uuid>> =
+        project_id_filter.map(|filter| filter.iter().map(|p| **p).collect());
+
+    let projects = sqlx::query_as!(
+        ProjectRecord,
+        r#"
+        SELECT
+            project_id,
+            project_name,
+            description,
+            status AS "status: ProjectStatus",
+            created_at
+        FROM project
+        WHERE $1::uuid[] IS NULL OR project_id = ANY($1)
+        ORDER BY project_name
+        "#,
+        project_ids.as_deref()
+    )
+    .fetch_all(&catalog_state.read_pool())
+    .await
+    .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
+
+    Ok(projects.into_iter().map(Into::into).collect())
+}
+
+pub(crate) async fn rename_project(
+    project_id: ProjectIdent,
+    new_name: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    validate_project_name(new_name)?;
+
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE project
+            SET project_name = $1
+            WHERE project_id = $2
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        new_name,
+        *project_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ErrorModel::builder()
+            .code(StatusCode::CONFLICT.into())
+            .message("Project with this name already exists.".to_string())
+            .r#type("ProjectNameAlreadyExists".to_string())
+            .build(),
+        _ => e.into_error_model("Error renaming project".into()),
+    })?;
+
+    if row_count == Some(0) {
+        return Err(project_not_found(project_id).into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn delete_project(
+    project_id: ProjectIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        With deleted as (
+            DELETE FROM project
+            WHERE project_id = $1
+            Returning *
+        )
+
+        SELECT count(*) FROM deleted
+        "#,
+        *project_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_error) if db_error.is_foreign_key_violation() => {
+            ErrorModel::builder()
+                .code(StatusCode::CONFLICT.into())
+                .message("Project is not empty, delete all warehouses first.".to_string())
+                .r#type("ProjectNotEmpty".to_string())
+                .build()
+        }
+        _ => e.into_error_model("Error deleting project".into()),
+    })?;
+
+    if row_count == Some(0) {
+        return Err(project_not_found(project_id).into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn set_project_status(
+    project_id: ProjectIdent,
+    status: ProjectStatus,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE project
+            SET status = $1
+            WHERE project_id = $2
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        status as ProjectStatus,
+        *project_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting project status".into()))?;
+
+    if row_count == Some(0) {
+        return Err(project_not_found(project_id).into());
+    }
+
+    Ok(())
+}
+
+pub(crate) fn project_not_found(project_id: ProjectIdent) -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message(format!("Project {project_id} not found"))
+        .r#type("ProjectNotFound".to_string())
+        .build()
+}
+
+fn validate_project_name(project_name: &str) -> Result<()> {
+    if project_name.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Project name cannot be empty".to_string())
+            .r#type("EmptyProjectName".to_string())
+            .build()
+            .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::implementations::postgres::warehouse::test::initialize_warehouse;
+    use crate::implementations::postgres::PostgresTransaction;
+    use crate::service::Transaction as _;
+
+    async fn initialize_project(state: CatalogState, project_name: &str) -> ProjectIdent {
+        let project_id = ProjectIdent::from(uuid::Uuid::now_v7());
+        let mut transaction = PostgresTransaction::begin_write(state).await.unwrap();
+        create_project(
+            project_id,
+            project_name.to_string(),
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+        project_id
+    }
+
+    #[sqlx::test]
+    async fn test_create_and_get_project(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let project_id = initialize_project(state.clone(), "analytics").await;
+
+        let mut transaction = PostgresTransaction::begin_read(state.clone())
+            .await
+            .unwrap();
+        let project = get_project(project_id, transaction.transaction())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(project.name, "analytics");
+        assert_eq!(project.status, ProjectStatus::Active);
+        assert!(get_project(
+            ProjectIdent::from(uuid::Uuid::now_v7()),
+            transaction.transaction()
+        )
+        .await
+        .unwrap()
+        .is_none());
+        transaction.commit().await.unwrap();
+
+        // Names are unique, case-insensitive
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = create_project(
+            ProjectIdent::from(uuid::Uuid::now_v7()),
+            "Analytics".to_string(),
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+    }
+
+    #[sqlx::test]
+    async fn test_list_and_rename_projects(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let project_1 = initialize_project(state.clone(), "project-1").await;
+        let project_2 = initialize_project(state.clone(), "project-2").await;
+
+        // Includes the default project
+        let projects = list_projects(None, state.clone()).await.unwrap();
+        assert_eq!(projects.len(), 3);
+
+        let projects = list_projects(Some(&HashSet::from([project_2])), state.clone())
+            .await
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].project_id, project_2);
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = rename_project(project_1, "project-2", transaction.transaction())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        transaction.rollback().await.unwrap();
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        rename_project(project_1, "renamed", transaction.transaction())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        let projects = list_projects(Some(&HashSet::from([project_1])), state)
+            .await
+            .unwrap();
+        assert_eq!(projects[0].name, "renamed");
+    }
+
+    #[sqlx::test]
+    async fn test_delete_project(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let project_id = initialize_project(state.clone(), "project").await;
+        initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        // Projects with warehouses can't be deleted
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = delete_project(project_id, transaction.transaction())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        transaction.rollback().await.unwrap();
+
+        let empty_project = initialize_project(state.clone(), "empty").await;
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        delete_project(empty_project, transaction.transaction())
+            .await
+            .unwrap();
+        let err = delete_project(empty_project, transaction.transaction())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    async fn test_set_project_status(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let project_id = initialize_project(state.clone(), "project").await;
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        set_project_status(
+            project_id,
+            ProjectStatus::Inactive,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        let project = get_project(project_id, transaction.transaction())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(project.status, ProjectStatus::Inactive);
+
+        set_project_status(project_id, ProjectStatus::Active, transaction.transaction())
+            .await
+            .unwrap();
+        let project = get_project(project_id, transaction.transaction())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(project.status, ProjectStatus::Active);
+
+        let err = set_project_status(
+            ProjectIdent::from(uuid::Uuid::now_v7()),
+            ProjectStatus::Inactive,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+}
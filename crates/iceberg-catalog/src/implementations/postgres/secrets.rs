@@ -1,16 +1,75 @@
 use super::ReadWrite;
 use crate::api::{ErrorModel, Result};
+use crate::config::DynAppConfig;
 use crate::service::health::{Health, HealthExt};
-use crate::service::secrets::{Secret, SecretIdent, SecretStore};
+use crate::service::secrets::{Secret, SecretIdent, SecretStore, SecretVersion};
 use crate::CONFIG;
 use async_trait::async_trait;
 use http::StatusCode;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use veil::Redact;
+
+/// Keys to encrypt and decrypt secrets with.
+/// Every secret row stores the ID of the key it is encrypted with.
+#[derive(Clone, Redact)]
+pub struct Keyring {
+    active_key_id: String,
+    #[redact]
+    keys: HashMap<String, String>,
+}
+
+impl Keyring {
+    /// # Errors
+    /// Fails if `active_key_id` is not part of `keys`.
+    pub fn new(active_key_id: String, keys: HashMap<String, String>) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            keys.contains_key(&active_key_id),
+            "Active encryption key '{active_key_id}' is not part of the keyring"
+        );
+        Ok(Self {
+            active_key_id,
+            keys,
+        })
+    }
+
+    /// Keyring of the configured postgres encryption keys.
+    ///
+    /// # Errors
+    /// Fails if the active encryption key is not configured.
+    pub fn from_config(config: &DynAppConfig) -> anyhow::Result<Self> {
+        Self::new(
+            config.pg_encryption_key_id.clone(),
+            config.pg_encryption_keyring(),
+        )
+    }
+
+    fn active_key(&self) -> (&str, &str) {
+        (&self.active_key_id, &self.keys[&self.active_key_id])
+    }
+
+    /// Key IDs and keys as two aligned arrays, to be passed to `unnest`.
+    fn to_arrays(&self) -> (Vec<String>, Vec<String>) {
+        self.keys
+            .iter()
+            .map(|(id, key)| (id.clone(), key.clone()))
+            .unzip()
+    }
+}
+
+/// Number of re-encrypted rows of a key rotation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyRotationStats {
+    pub secrets: u64,
+    pub secret_versions: u64,
+}
 
 #[derive(Debug, Clone)]
 pub struct SecretsState {
     read_write: ReadWrite,
+    keyring: Arc<Keyring>,
 }
 
 #[async_trait]
@@ -25,11 +84,124 @@ impl HealthExt for SecretsState {
 }
 
 impl SecretsState {
-    #[must_use]
-    pub fn from_pools(read_pool: PgPool, write_pool: PgPool) -> Self {
-        Self {
+    /// # Errors
+    /// Fails if the configured keyring is invalid, see [`Keyring::from_config`].
+    pub fn from_pools(read_pool: PgPool, write_pool: PgPool) -> anyhow::Result<Self> {
+        Ok(Self {
             read_write: ReadWrite::from_pools(read_pool, write_pool),
+            keyring: Arc::new(Keyring::from_config(&CONFIG)?),
+        })
+    }
+
+    #[must_use]
+    pub fn with_keyring(mut self, keyring: Keyring) -> Self {
+        self.keyring = Arc::new(keyring);
+        self
+    }
+
+    /// Re-encrypt all secrets and secret versions that are not encrypted with the
+    /// active key. Rows are updated in batches of `batch_size`, each batch in its
+    /// own transaction, so that the rotation can be interrupted and resumed.
+    ///
+    /// # Errors
+    /// Fails if a query fails or if rows are encrypted with keys that are not
+    /// part of the keyring.
+    pub async fn rotate_encryption_key(&self, batch_size: u32) -> anyhow::Result<KeyRotationStats> {
+        let (active_key_id, active_key) = self.keyring.active_key();
+        let (key_ids, keys) = self.keyring.to_arrays();
+        let batch_size = i64::from(batch_size.max(1));
+        let mut stats = KeyRotationStats::default();
+
+        loop {
+            let rotated = sqlx::query_scalar!(
+                r#"
+                WITH batch AS (
+                    SELECT secret_id FROM secret
+                    WHERE key_id != $1 AND key_id = ANY($3)
+                    LIMIT $5
+                    FOR UPDATE SKIP LOCKED
+                )
+                UPDATE secret s
+                SET secret = pgp_sym_encrypt(
+                        pgp_sym_decrypt(s.secret, k.key, 'cipher-algo=aes256'),
+                        $2,
+                        'cipher-algo=aes256'
+                    ),
+                    key_id = $1
+                FROM batch, unnest($3::text[], $4::text[]) AS k(key_id, key)
+                WHERE s.secret_id = batch.secret_id AND s.key_id = k.key_id
+                RETURNING s.secret_id
+                "#,
+                active_key_id,
+                active_key,
+                &key_ids,
+                &keys,
+                batch_size
+            )
+            .fetch_all(&self.read_write.write_pool)
+            .await?;
+            if rotated.is_empty() {
+                break;
+            }
+            stats.secrets += rotated.len() as u64;
+            tracing::info!("Re-encrypted {} secrets", stats.secrets);
         }
+
+        loop {
+            let rotated = sqlx::query_scalar!(
+                r#"
+                WITH batch AS (
+                    SELECT secret_id, version FROM secret_version
+                    WHERE key_id != $1 AND key_id = ANY($3)
+                    LIMIT $5
+                    FOR UPDATE SKIP LOCKED
+                )
+                UPDATE secret_version v
+                SET secret = pgp_sym_encrypt(
+                        pgp_sym_decrypt(v.secret, k.key, 'cipher-algo=aes256'),
+                        $2,
+                        'cipher-algo=aes256'
+                    ),
+                    key_id = $1
+                FROM batch, unnest($3::text[], $4::text[]) AS k(key_id, key)
+                WHERE v.secret_id = batch.secret_id
+                    AND v.version = batch.version
+                    AND v.key_id = k.key_id
+                RETURNING v.secret_id
+                "#,
+                active_key_id,
+                active_key,
+                &key_ids,
+                &keys,
+                batch_size
+            )
+            .fetch_all(&self.read_write.write_pool)
+            .await?;
+            if rotated.is_empty() {
+                break;
+            }
+            stats.secret_versions += rotated.len() as u64;
+            tracing::info!("Re-encrypted {} secret versions", stats.secret_versions);
+        }
+
+        let unknown_key_ids = sqlx::query_scalar!(
+            r#"
+            SELECT key_id as "key_id!" FROM secret WHERE key_id != $1
+            UNION
+            SELECT key_id FROM secret_version WHERE key_id != $1
+            "#,
+            active_key_id
+        )
+        .fetch_all(&self.read_write.read_pool)
+        .await?;
+        if !unknown_key_ids.is_empty() {
+            anyhow::bail!(
+                "Secrets encrypted with unknown keys remain: {}. Add these keys to the keyring and re-run the rotation.",
+                unknown_key_ids.join(", ")
+            );
+        }
+
+        Ok(stats)
     }
 
     #[must_use]
@@ -50,24 +222,23 @@ impl SecretStore for SecretsState {
         &self,
         secret_id: &SecretIdent,
     ) -> Result<Secret<S>> {
-        struct SecretRow {
-            secret: Option<String>,
-            created_at: chrono::DateTime<chrono::Utc>,
-            updated_at: Option<chrono::DateTime<chrono::Utc>>,
-        }
-
+        let (key_ids, keys) = self.keyring.to_arrays();
         let secret: SecretRow = sqlx::query_as!(
             SecretRow,
             r#"
-            SELECT 
-                pgp_sym_decrypt(secret, $2, 'cipher-algo=aes256') as secret,
-                created_at,
-                updated_at
-            FROM secret
-            WHERE secret_id = $1
+            SELECT
+                pgp_sym_decrypt(s.secret, k.key, 'cipher-algo=aes256') as secret,
+                s.key_id,
+                s.version,
+                s.created_at,
+                s.updated_at
+            FROM secret s
+            LEFT JOIN unnest($2::text[], $3::text[]) AS k(key_id, key) ON k.key_id = s.key_id
+            WHERE s.secret_id = $1
             "#,
             secret_id.as_uuid(),
-            CONFIG.pg_encryption_key
+            &key_ids,
+            &keys
         )
         .fetch_one(&self.read_write.read_pool)
         .await
@@ -86,48 +257,176 @@ impl SecretStore for SecretsState {
                 .build(),
         })?;
 
-        let inner =
-            serde_json::from_str(&secret.secret.unwrap_or("{}".to_string())).map_err(|_e| {
-                ErrorModel::builder()
-                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
-                    .message("Error parsing secret".to_string())
-                    .r#type("SecretParseError".to_string())
-                    // We do not add the error here as it might contain sensitive information
-                    .stack(vec![format!("Secret ID: {}", secret_id)])
-                    .build()
-            })?;
+        secret.into_secret(*secret_id)
+    }
 
-        Ok(Secret {
-            secret_id: *secret_id,
-            secret: inner,
-            created_at: secret.created_at,
-            updated_at: secret.updated_at,
-        })
+    async fn get_secret_version<S: for<'de> Deserialize<'de>>(
+        &self,
+        secret_id: &SecretIdent,
+        version: u64,
+    ) -> Result<Secret<S>> {
+        let (key_ids, keys) = self.keyring.to_arrays();
+        let secret: SecretRow = sqlx::query_as!(
+            SecretRow,
+            r#"
+            SELECT
+                pgp_sym_decrypt(v.secret, k.key, 'cipher-algo=aes256') as secret,
+                v.key_id,
+                v.version,
+                s.created_at,
+                v.created_at as "updated_at?"
+            FROM secret_version v
+            INNER JOIN secret s ON s.secret_id = v.secret_id
+            LEFT JOIN unnest($3::text[], $4::text[]) AS k(key_id, key) ON k.key_id = v.key_id
+            WHERE v.secret_id = $1 AND v.version = $2
+            "#,
+            secret_id.as_uuid(),
+            version_to_db(version)?,
+            &key_ids,
+            &keys
+        )
+        .fetch_one(&self.read_write.read_pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message("Secret version not found".to_string())
+                .r#type("SecretVersionNotFound".to_string())
+                .stack(vec![
+                    format!("secret_id: {secret_id}"),
+                    format!("version: {version}"),
+                ])
+                .build(),
+            _ => ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error fetching secret version".to_string())
+                .r#type("SecretFetchError".to_string())
+                .stack(vec![format!("secret_id: {}", secret_id), e.to_string()])
+                .build(),
+        })?;
+
+        secret.into_secret(*secret_id)
     }
 
-    /// Create a new secret
-    async fn create_secret<S: Send + Sync + Serialize + std::fmt::Debug>(
+    async fn update_secret<S: Send + Sync + Serialize + std::fmt::Debug>(
         &self,
+        secret_id: &SecretIdent,
         secret: S,
-    ) -> Result<SecretIdent> {
-        let secret_str = serde_json::to_string(&secret).map_err(|_e| {
+    ) -> Result<u64> {
+        let secret_str = serialize_secret(&secret)?;
+        let (key_id, key) = self.keyring.active_key();
+
+        // The current value is kept in `secret` for cheap reads,
+        // every value ever set is kept in `secret_version`.
+        let version = sqlx::query_scalar!(
+            r#"
+            WITH updated AS (
+                UPDATE secret
+                SET secret = pgp_sym_encrypt($2, $3, 'cipher-algo=aes256'),
+                    key_id = $4,
+                    version = version + 1
+                WHERE secret_id = $1
+                RETURNING secret_id, secret, key_id, version
+            )
+            INSERT INTO secret_version (secret_id, version, secret, key_id)
+            SELECT secret_id, version, secret, key_id FROM updated
+            RETURNING version
+            "#,
+            secret_id.as_uuid(),
+            secret_str,
+            key,
+            key_id,
+        )
+        .fetch_optional(&self.write_pool())
+        .await
+        .map_err(|e| {
             ErrorModel::builder()
                 .code(StatusCode::INTERNAL_SERVER_ERROR.into())
-                .message("Error serializing secret".to_string())
-                .r#type("SecretSerializeError".to_string())
-                // Redacted by veil
-                .stack(vec![format!("secret: {:?}", secret)])
+                .message("Error updating secret".to_string())
+                .r#type("SecretUpdateError".to_string())
+                .stack(vec![format!("secret_id: {}", secret_id)])
+                .source(Some(Box::new(e)))
+                .build()
+        })?
+        .ok_or_else(|| {
+            ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message("Secret not found".to_string())
+                .r#type("SecretNotFound".to_string())
+                .stack(vec![format!("secret_id: {}", secret_id)])
                 .build()
         })?;
 
+        version_from_db(version)
+    }
+
+    async fn list_secret_versions(&self, secret_id: &SecretIdent) -> Result<Vec<SecretVersion>> {
+        let versions = sqlx::query!(
+            r#"
+            SELECT v.version, v.created_at, v.version = s.version as "current!"
+            FROM secret_version v
+            INNER JOIN secret s ON s.secret_id = v.secret_id
+            WHERE v.secret_id = $1
+            ORDER BY v.version DESC
+            "#,
+            secret_id.as_uuid()
+        )
+        .fetch_all(&self.read_write.read_pool)
+        .await
+        .map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error listing secret versions".to_string())
+                .r#type("SecretFetchError".to_string())
+                .stack(vec![format!("secret_id: {}", secret_id)])
+                .source(Some(Box::new(e)))
+                .build()
+        })?;
+
+        if versions.is_empty() {
+            return Err(ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message("Secret not found".to_string())
+                .r#type("SecretNotFound".to_string())
+                .stack(vec![format!("secret_id: {}", secret_id)])
+                .build()
+                .into());
+        }
+
+        versions
+            .into_iter()
+            .map(|v| {
+                Ok(SecretVersion {
+                    version: version_from_db(v.version)?,
+                    created_at: v.created_at,
+                    current: v.current,
+                })
+            })
+            .collect()
+    }
+
+    /// Create a new secret
+    async fn create_secret<S: Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret: S,
+    ) -> Result<SecretIdent> {
+        let secret_str = serialize_secret(&secret)?;
+        let (key_id, key) = self.keyring.active_key();
+
         let secret_id = sqlx::query_scalar!(
             r#"
-            INSERT INTO secret (secret)
-            VALUES (pgp_sym_encrypt($1, $2, 'cipher-algo=aes256'))
+            WITH inserted AS (
+                INSERT INTO secret (secret, key_id)
+                VALUES (pgp_sym_encrypt($1, $2, 'cipher-algo=aes256'), $3)
+                RETURNING secret_id, secret, key_id, version
+            )
+            INSERT INTO secret_version (secret_id, version, secret, key_id)
+            SELECT secret_id, version, secret, key_id FROM inserted
             RETURNING secret_id
             "#,
             secret_str,
-            CONFIG.pg_encryption_key,
+            key,
+            key_id,
         )
         .fetch_one(&self.write_pool())
         .await
@@ -168,6 +467,83 @@ impl SecretStore for SecretsState {
     }
 }
 
+struct SecretRow {
+    secret: Option<String>,
+    key_id: String,
+    version: i64,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl SecretRow {
+    fn into_secret<S: for<'de> Deserialize<'de>>(
+        self,
+        secret_id: SecretIdent,
+    ) -> Result<Secret<S>> {
+        // Decrypting with an unknown key joins a NULL key, which decrypts to NULL
+        let secret = self.secret.ok_or_else(|| {
+            ErrorModel::internal(
+                format!(
+                    "Secret is encrypted with key '{}' which is not configured",
+                    self.key_id
+                ),
+                "SecretEncryptionKeyUnknown",
+                None,
+            )
+            .append_detail(format!("Secret ID: {secret_id}"))
+        })?;
+        let inner = serde_json::from_str(&secret).map_err(|_e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error parsing secret".to_string())
+                .r#type("SecretParseError".to_string())
+                // We do not add the error here as it might contain sensitive information
+                .stack(vec![format!("Secret ID: {}", secret_id)])
+                .build()
+        })?;
+
+        Ok(Secret {
+            secret_id,
+            secret: inner,
+            version: version_from_db(self.version)?,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+fn serialize_secret<S: Serialize + std::fmt::Debug>(secret: &S) -> Result<String> {
+    Ok(serde_json::to_string(secret).map_err(|_e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error serializing secret".to_string())
+            .r#type("SecretSerializeError".to_string())
+            // Redacted by veil
+            .stack(vec![format!("secret: {:?}", secret)])
+            .build()
+    })?)
+}
+
+fn version_from_db(version: i64) -> Result<u64> {
+    Ok(u64::try_from(version).map_err(|e| {
+        ErrorModel::internal(
+            "Invalid secret version in database",
+            "SecretVersionInvalid",
+            Some(Box::new(e)),
+        )
+    })?)
+}
+
+fn version_to_db(version: u64) -> Result<i64> {
+    Ok(i64::try_from(version).map_err(|e| {
+        ErrorModel::bad_request(
+            format!("Secret version {version} is out of range"),
+            "SecretVersionInvalid",
+            Some(Box::new(e)),
+        )
+    })?)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::service::storage::{S3Credential, StorageCredential};
@@ -176,7 +552,7 @@ mod tests {
 
     #[sqlx::test]
     async fn test_write_read_secret(pool: sqlx::PgPool) {
-        let state = SecretsState::from_pools(pool.clone(), pool);
+        let state = SecretsState::from_pools(pool.clone(), pool).unwrap();
 
         let secret: StorageCredential = S3Credential::AccessKey {
             aws_access_key_id: "my access key".to_string(),
@@ -194,9 +570,72 @@ mod tests {
         assert_eq!(read_secret.secret, secret);
     }
 
+    #[sqlx::test]
+    async fn test_update_secret_keeps_versions(pool: sqlx::PgPool) {
+        let state = SecretsState::from_pools(pool.clone(), pool).unwrap();
+
+        let first: StorageCredential = S3Credential::AccessKey {
+            aws_access_key_id: "first access key".to_string(),
+            aws_secret_access_key: "first secret key".to_string(),
+        }
+        .into();
+        let second: StorageCredential = S3Credential::AccessKey {
+            aws_access_key_id: "second access key".to_string(),
+            aws_secret_access_key: "second secret key".to_string(),
+        }
+        .into();
+
+        let secret_id = state.create_secret(first.clone()).await.unwrap();
+        let version = state
+            .update_secret(&secret_id, second.clone())
+            .await
+            .unwrap();
+        assert_eq!(version, 2);
+
+        let current = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(current.secret, second);
+        assert_eq!(current.version, 2);
+
+        let old = state
+            .get_secret_version::<StorageCredential>(&secret_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(old.secret, first);
+        assert_eq!(old.version, 1);
+
+        let versions = state.list_secret_versions(&secret_id).await.unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|v| (v.version, v.current))
+                .collect::<Vec<_>>(),
+            vec![(2, true), (1, false)]
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_update_unknown_secret_fails(pool: sqlx::PgPool) {
+        let state = SecretsState::from_pools(pool.clone(), pool).unwrap();
+
+        let secret: StorageCredential = S3Credential::AccessKey {
+            aws_access_key_id: "my access key".to_string(),
+            aws_secret_access_key: "my secret key".to_string(),
+        }
+        .into();
+
+        let err = state
+            .update_secret(&uuid::Uuid::now_v7().into(), secret)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+
     #[sqlx::test]
     async fn test_delete_secret(pool: sqlx::PgPool) {
-        let state = SecretsState::from_pools(pool.clone(), pool);
+        let state = SecretsState::from_pools(pool.clone(), pool).unwrap();
 
         let secret: StorageCredential = S3Credential::AccessKey {
             aws_access_key_id: "my access key".to_string(),
@@ -214,4 +653,118 @@ mod tests {
 
         assert!(read_secret.is_err());
     }
+
+    #[sqlx::test]
+    async fn test_rotate_encryption_key(pool: sqlx::PgPool) {
+        let old_keyring = Keyring::new(
+            "old".to_string(),
+            HashMap::from([("old".to_string(), "old-key".to_string())]),
+        )
+        .unwrap();
+        let state = SecretsState::from_pools(pool.clone(), pool.clone())
+            .unwrap()
+            .with_keyring(old_keyring);
+
+        let first: StorageCredential = S3Credential::AccessKey {
+            aws_access_key_id: "first access key".to_string(),
+            aws_secret_access_key: "first secret key".to_string(),
+        }
+        .into();
+        let second: StorageCredential = S3Credential::AccessKey {
+            aws_access_key_id: "second access key".to_string(),
+            aws_secret_access_key: "second secret key".to_string(),
+        }
+        .into();
+
+        let secret_id = state.create_secret(first.clone()).await.unwrap();
+        state
+            .update_secret(&secret_id, second.clone())
+            .await
+            .unwrap();
+
+        let rotation_keyring = Keyring::new(
+            "new".to_string(),
+            HashMap::from([
+                ("old".to_string(), "old-key".to_string()),
+                ("new".to_string(), "new-key".to_string()),
+            ]),
+        )
+        .unwrap();
+        let state = SecretsState::from_pools(pool.clone(), pool.clone())
+            .unwrap()
+            .with_keyring(rotation_keyring);
+        let stats = state.rotate_encryption_key(1).await.unwrap();
+        assert_eq!(
+            stats,
+            KeyRotationStats {
+                secrets: 1,
+                secret_versions: 2
+            }
+        );
+        // Nothing left to rotate
+        let stats = state.rotate_encryption_key(1).await.unwrap();
+        assert_eq!(stats, KeyRotationStats::default());
+
+        let new_keyring = Keyring::new(
+            "new".to_string(),
+            HashMap::from([("new".to_string(), "new-key".to_string())]),
+        )
+        .unwrap();
+        let state = SecretsState::from_pools(pool.clone(), pool)
+            .unwrap()
+            .with_keyring(new_keyring);
+        let read_secret = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read_secret.secret, second);
+        let read_first = state
+            .get_secret_version::<StorageCredential>(&secret_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(read_first.secret, first);
+    }
+
+    #[test]
+    fn test_keyring_requires_active_key() {
+        let keys = HashMap::from([("old".to_string(), "old-key".to_string())]);
+        assert!(Keyring::new("old".to_string(), keys.clone()).is_ok());
+        assert!(Keyring::new("new".to_string(), keys).is_err());
+    }
+
+    #[sqlx::test]
+    async fn test_read_secret_with_unknown_key_fails(pool: sqlx::PgPool) {
+        let old_keyring = Keyring::new(
+            "old".to_string(),
+            HashMap::from([("old".to_string(), "old-key".to_string())]),
+        )
+        .unwrap();
+        let state = SecretsState::from_pools(pool.clone(), pool.clone())
+            .unwrap()
+            .with_keyring(old_keyring);
+
+        let secret: StorageCredential = S3Credential::AccessKey {
+            aws_access_key_id: "my access key".to_string(),
+            aws_secret_access_key: "my secret key".to_string(),
+        }
+        .into();
+        let secret_id = state.create_secret(secret).await.unwrap();
+
+        let new_keyring = Keyring::new(
+            "new".to_string(),
+            HashMap::from([("new".to_string(), "new-key".to_string())]),
+        )
+        .unwrap();
+        let state = SecretsState::from_pools(pool.clone(), pool)
+            .unwrap()
+            .with_keyring(new_keyring);
+        let err = state
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.r#type, "SecretEncryptionKeyUnknown");
+
+        // Rotation cannot re-encrypt secrets with unknown keys
+        assert!(state.rotate_encryption_key(10).await.is_err());
+    }
 }
@@ -7,6 +7,7 @@ use crate::{
     WarehouseIdent,
 };
 use http::StatusCode;
+use iceberg_ext::configs::Location;
 use iceberg_ext::NamespaceIdent;
 
 use crate::api::iceberg::v1::{PaginatedTabulars, PaginationQuery, MAX_PAGE_SIZE};
@@ -17,6 +18,7 @@ use sqlx::{Arguments, Execute, FromRow, PgConnection, Postgres, QueryBuilder};
 use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::fmt::Debug;
+use std::str::FromStr;
 use uuid::Uuid;
 
 const MAX_PARAMETERS: usize = 30000;
@@ -262,7 +264,7 @@ pub(crate) async fn create_tabular<'a>(
     // They can be overwritten in a new create statement as if they wouldn't exist yet.
     // Views do not require this distinction, as `metadata_location` is always set for them
     // (validated by constraint).
-    Ok(sqlx::query_scalar!(
+    let tabular_id = sqlx::query_scalar!(
         r#"
         INSERT INTO tabular (tabular_id, name, namespace_id, typ, metadata_location, location)
         VALUES ($1, $2, $3, $4, $5, $6)
@@ -278,7 +280,7 @@ pub(crate) async fn create_tabular<'a>(
         metadata_location,
         location
     )
-    .fetch_one(conn)
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| match &e {
         sqlx::Error::RowNotFound => {
@@ -290,7 +292,108 @@ pub(crate) async fn create_tabular<'a>(
                 .build()
         }
         _ => e.into_error_model(format!("Error creating {typ}")),
-    })?)
+    })?;
+
+    // Checked after the insert so that a staged table which is overwritten
+    // by this create already carries the new id and is excluded.
+    require_no_location_overlap(namespace_id, tabular_id, location, conn).await?;
+
+    Ok(tabular_id)
+}
+
+/// Fails if `location` overlaps with the location of another tabular in the warehouse
+/// of `namespace_id`, i.e. if one of both locations is a sublocation of the other.
+/// Soft-deleted tabulars are considered as well, as their files are only removed on purge.
+///
+/// Candidates are narrowed down in the database with lookups that can use the
+/// `text_pattern_ops` index on `tabular.location`. The overlap itself is decided by
+/// [`Location::is_sublocation_of`].
+async fn require_no_location_overlap(
+    namespace_id: Uuid,
+    tabular_id: Uuid,
+    location: &str,
+    conn: &mut PgConnection,
+) -> Result<()> {
+    let location = Location::from_str(location).map_err(|e| {
+        ErrorModel::bad_request(
+            format!("Invalid location: {location}"),
+            "InvalidLocation",
+            Some(Box::new(e)),
+        )
+    })?;
+
+    let parent_locations = location
+        .partial_locations()
+        .into_iter()
+        .flat_map(|mut l| {
+            let without_slash = l.to_string();
+            l.with_trailing_slash();
+            [without_slash, l.to_string()]
+        })
+        .collect::<Vec<_>>();
+    let mut child_prefix = location.clone();
+    child_prefix.with_trailing_slash();
+    let child_pattern = format!("{}%", escape_like_pattern(child_prefix.as_str()));
+
+    // Serialize creates within a warehouse, otherwise two concurrent
+    // transactions could both pass the check below.
+    sqlx::query(
+        r#"
+        SELECT pg_advisory_xact_lock(hashtext(warehouse_id::text))
+        FROM namespace WHERE namespace_id = $1
+        "#,
+    )
+    .bind(namespace_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.into_error_model("Error locking warehouse locations".to_string()))?;
+
+    let candidates = sqlx::query!(
+        r#"
+        SELECT t.tabular_id, t.typ as "typ: TabularType", t.location
+        FROM tabular t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        WHERE n.warehouse_id = (SELECT warehouse_id FROM namespace WHERE namespace_id = $1)
+            AND t.tabular_id != $2
+            AND (t.location = ANY($3) OR t.location LIKE $4)
+        "#,
+        namespace_id,
+        tabular_id,
+        &parent_locations[..],
+        child_pattern,
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.into_error_model("Error checking tabular locations".to_string()))?;
+
+    let overlapping = candidates.into_iter().find(|candidate| {
+        Location::from_str(&candidate.location).is_ok_and(|other| {
+            location.is_sublocation_of(&other) || other.is_sublocation_of(&location)
+        })
+    });
+
+    if let Some(overlapping) = overlapping {
+        tracing::debug!(
+            "Location {location} of {tabular_id} overlaps with {} {}",
+            overlapping.typ,
+            overlapping.tabular_id
+        );
+        return Err(ErrorModel::conflict(
+            format!("Location {location} overlaps with the location of an existing Table or View"),
+            "LocationOverlap",
+            None,
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+fn escape_like_pattern(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
 
 pub(crate) async fn list_tabulars(
@@ -403,6 +506,29 @@ pub(crate) async fn rename_tabular(
         name: dest_name,
     } = destination;
 
+    let source_tabular = sqlx::query!(
+        r#"
+        SELECT namespace_id, location
+        FROM tabular
+        WHERE tabular_id = $1 AND typ = $2
+        "#,
+        *source_id,
+        TabularType::from(source_id) as _,
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model(format!("Error fetching {}", source_id.typ_str())))?;
+    // Missing tabulars are reported by the update below
+    if let Some(source_tabular) = source_tabular {
+        require_no_location_overlap(
+            source_tabular.namespace_id,
+            *source_id,
+            &source_tabular.location,
+            &mut **transaction,
+        )
+        .await?;
+    }
+
     if source_namespace == dest_namespace {
         let _ = sqlx::query_scalar!(
             r#"
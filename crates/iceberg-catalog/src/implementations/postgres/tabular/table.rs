@@ -197,12 +197,25 @@ pub(crate) async fn load_tables(
             t."metadata" as "metadata: Json<TableMetadata>",
             ti."metadata_location",
             ti.location as "table_location",
-            w.storage_profile as "storage_profile: Json<StorageProfile>",
-            w."storage_secret_id"
+            COALESCE(s.storage_profile, w.storage_profile) as "storage_profile!: Json<StorageProfile>",
+            CASE WHEN s.storage_profile IS NULL
+                THEN w."storage_secret_id"
+                ELSE s."storage_secret_id"
+            END as "storage_secret_id?"
         FROM "table" t
         INNER JOIN tabular ti ON t.table_id = ti.tabular_id
         INNER JOIN namespace n ON ti.namespace_id = n.namespace_id
         INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        LEFT JOIN LATERAL (
+            SELECT a.storage_profile, a.storage_secret_id
+            FROM namespace a
+            WHERE a.warehouse_id = n.warehouse_id
+            AND a.storage_profile IS NOT NULL
+            AND cardinality(a.namespace_name) <= cardinality(n.namespace_name)
+            AND n.namespace_name[1:cardinality(a.namespace_name)] = a.namespace_name
+            ORDER BY cardinality(a.namespace_name) DESC
+            LIMIT 1
+        ) s ON true
         WHERE w.warehouse_id = $1
         AND w.status = 'active'
         AND (ti.deleted_at IS NULL OR $3)
@@ -284,12 +297,25 @@ pub(crate) async fn get_table_metadata_by_id(
             namespace_name,
             t."metadata" as "metadata: Json<TableMetadata>",
             ti."metadata_location",
-            w.storage_profile as "storage_profile: Json<StorageProfile>",
-            w."storage_secret_id"
+            COALESCE(s.storage_profile, w.storage_profile) as "storage_profile!: Json<StorageProfile>",
+            CASE WHEN s.storage_profile IS NULL
+                THEN w."storage_secret_id"
+                ELSE s."storage_secret_id"
+            END as "storage_secret_id?"
         FROM "table" t
         INNER JOIN tabular ti ON t.table_id = ti.tabular_id
         INNER JOIN namespace n ON ti.namespace_id = n.namespace_id
         INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        LEFT JOIN LATERAL (
+            SELECT a.storage_profile, a.storage_secret_id
+            FROM namespace a
+            WHERE a.warehouse_id = n.warehouse_id
+            AND a.storage_profile IS NOT NULL
+            AND cardinality(a.namespace_name) <= cardinality(n.namespace_name)
+            AND n.namespace_name[1:cardinality(a.namespace_name)] = a.namespace_name
+            ORDER BY cardinality(a.namespace_name) DESC
+            LIMIT 1
+        ) s ON true
         WHERE w.warehouse_id = $1 AND t."table_id" = $2
             AND w.status = 'active'
             AND (ti.deleted_at IS NULL OR $3)
@@ -351,12 +377,25 @@ pub(crate) async fn get_table_metadata_by_s3_location(
             namespace_name,
             t."metadata" as "metadata: Json<TableMetadata>",
             ti."metadata_location",
-            w.storage_profile as "storage_profile: Json<StorageProfile>",
-            w."storage_secret_id"
+            COALESCE(s.storage_profile, w.storage_profile) as "storage_profile!: Json<StorageProfile>",
+            CASE WHEN s.storage_profile IS NULL
+                THEN w."storage_secret_id"
+                ELSE s."storage_secret_id"
+            END as "storage_secret_id?"
         FROM "table" t
         INNER JOIN tabular ti ON t.table_id = ti.tabular_id
         INNER JOIN namespace n ON ti.namespace_id = n.namespace_id
         INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        LEFT JOIN LATERAL (
+            SELECT a.storage_profile, a.storage_secret_id
+            FROM namespace a
+            WHERE a.warehouse_id = n.warehouse_id
+            AND a.storage_profile IS NOT NULL
+            AND cardinality(a.namespace_name) <= cardinality(n.namespace_name)
+            AND n.namespace_name[1:cardinality(a.namespace_name)] = a.namespace_name
+            ORDER BY cardinality(a.namespace_name) DESC
+            LIMIT 1
+        ) s ON true
         WHERE w.warehouse_id = $1
             AND $2 like ti."location" || '%'
             AND LENGTH(ti."location") <= $3
@@ -575,7 +614,7 @@ pub(crate) mod tests {
         (
             CreateTableRequest {
                 name: table_name.unwrap_or("my_table".to_string()),
-                location: Some("s3://my_bucket/my_table".to_string()),
+                location: Some(format!("s3://my_bucket/{}", uuid::Uuid::now_v7())),
                 schema: Schema::builder()
                     .with_fields(vec![
                         NestedField::required(
@@ -626,7 +665,6 @@ pub(crate) mod tests {
     }
 
     pub(crate) struct InitializedTable {
-        #[allow(dead_code)]
         pub(crate) namespace_id: NamespaceIdentUuid,
         pub(crate) namespace: NamespaceIdent,
         pub(crate) table_id: TableIdentUuid,
@@ -1429,4 +1467,110 @@ pub(crate) mod tests {
         .unwrap();
         assert_eq!(ok.table_id, table.table_id);
     }
+
+    #[sqlx::test]
+    async fn test_overlapping_locations_are_rejected(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table = initialize_table(warehouse_id, state.clone(), false, None, None).await;
+        let location = get_table_metadata_by_id(
+            warehouse_id,
+            table.table_id,
+            ListFlags::default(),
+            state.clone(),
+        )
+        .await
+        .unwrap()
+        .location;
+
+        let create_at = |table_location: String, name: &'static str| {
+            let (mut request, metadata_location) = create_request(Some(false), None);
+            request.name = name.to_string();
+            request.location = Some(table_location);
+            let table_ident = TableIdent {
+                namespace: table.namespace.clone(),
+                name: name.to_string(),
+            };
+            let pool = pool.clone();
+            async move {
+                let mut transaction = pool.begin().await.unwrap();
+                let result = create_table(
+                    table.namespace_id,
+                    &table_ident,
+                    uuid::Uuid::now_v7().into(),
+                    request,
+                    metadata_location.as_deref(),
+                    &mut transaction,
+                )
+                .await;
+                if result.is_ok() {
+                    transaction.commit().await.unwrap();
+                }
+                result
+            }
+        };
+
+        // Sublocation
+        let err = create_at(format!("{location}/nested"), "nested")
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        assert_eq!(err.error.r#type, "LocationOverlap");
+
+        // Parent location
+        let err = create_at("s3://my_bucket".to_string(), "parent")
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+
+        // Same location with trailing slash
+        let err = create_at(format!("{location}/"), "same").await.unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+
+        // Sibling sharing a string prefix is fine
+        create_at(format!("{location}_sibling"), "sibling")
+            .await
+            .unwrap();
+
+        // Renames are rejected if the location of the tabular overlaps,
+        // e.g. because it was created before the check existed.
+        sqlx::query("UPDATE tabular SET location = $1 WHERE name = 'sibling'")
+            .bind(format!("{location}/legacy"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        let mut transaction = pool.begin().await.unwrap();
+        let err = rename_table(
+            warehouse_id,
+            table.table_id,
+            &table.table_ident,
+            &TableIdent {
+                namespace: table.namespace.clone(),
+                name: "renamed".to_string(),
+            },
+            &mut transaction,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        assert_eq!(err.error.r#type, "LocationOverlap");
+        transaction.rollback().await.unwrap();
+        sqlx::query("UPDATE tabular SET location = $1 WHERE name = 'sibling'")
+            .bind(format!("{location}_sibling"))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Soft-deleted tables still own their location
+        let mut transaction = pool.begin().await.unwrap();
+        drop_table(table.table_id, false, &mut transaction)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+        let err = create_at(format!("{location}/nested"), "nested")
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+    }
 }
@@ -8,6 +8,7 @@ use crate::{service::storage::StorageProfile, ProjectIdent, SecretIdent, Warehou
 use http::StatusCode;
 
 use super::dbutils::DBErrorHandler as _;
+use super::project::project_not_found;
 
 use super::{Catalog, CatalogState};
 use sqlx::types::Json;
@@ -121,6 +122,7 @@ pub(crate) async fn create_warehouse<'a>(
                 .message("Warehouse with this name already exists in the project.".to_string())
                 .r#type("WarehouseNameAlreadyExists".to_string())
                 .build(),
+            Some("warehouse_project_id_fk") => project_not_found(project_id),
             _ => e.into_error_model("Error creating Warehouse".into()),
         },
         sqlx::Error::RowNotFound => ErrorModel::builder()
@@ -248,24 +250,6 @@ pub(crate) async fn get_warehouse<'a>(
     })
 }
 
-pub(crate) async fn list_projects(catalog_state: CatalogState) -> Result<HashSet<ProjectIdent>> {
-    let projects = sqlx::query!(
-        r#"
-        SELECT DISTINCT
-            project_id
-        FROM warehouse
-        "#,
-    )
-    .fetch_all(&catalog_state.read_pool())
-    .await
-    .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
-
-    Ok(projects
-        .into_iter()
-        .map(|project| ProjectIdent::from(project.project_id))
-        .collect())
-}
-
 pub(crate) async fn delete_warehouse<'a>(
     warehouse_id: WarehouseIdent,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
@@ -465,6 +449,19 @@ pub(crate) mod test {
             .await
             .unwrap();
 
+        sqlx::query!(
+            r#"
+            INSERT INTO project (project_id, project_name)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            *project_id,
+            project_id.to_string()
+        )
+        .execute(&mut **transaction.transaction())
+        .await
+        .unwrap();
+
         let storage_profile = storage_profile.unwrap_or(StorageProfile::S3(S3Profile {
             bucket: "test_bucket".to_string(),
             endpoint: None,
@@ -475,6 +472,7 @@ pub(crate) mod test {
             sts_role_arn: None,
             sts_enabled: false,
             flavor: S3Flavor::Minio,
+            server_side_encryption: None,
         }));
 
         let warehouse_id = Catalog::create_warehouse(
@@ -507,25 +505,6 @@ pub(crate) mod test {
         assert_eq!(warehouse_id, fetched_warehouse_id);
     }
 
-    #[sqlx::test]
-    async fn test_list_projects(pool: sqlx::PgPool) {
-        let state = CatalogState::from_pools(pool.clone(), pool.clone());
-        let project_id_1 = ProjectIdent::from(uuid::Uuid::new_v4());
-        initialize_warehouse(state.clone(), None, Some(&project_id_1)).await;
-
-        let projects = Catalog::list_projects(state.clone()).await.unwrap();
-        assert_eq!(projects.len(), 1);
-        assert!(projects.contains(&project_id_1));
-
-        let project_id_2 = ProjectIdent::from(uuid::Uuid::new_v4());
-        initialize_warehouse(state.clone(), None, Some(&project_id_2)).await;
-
-        let projects = Catalog::list_projects(state.clone()).await.unwrap();
-        assert_eq!(projects.len(), 2);
-        assert!(projects.contains(&project_id_1));
-        assert!(projects.contains(&project_id_2));
-    }
-
     #[sqlx::test]
     async fn test_list_warehouses(pool: sqlx::PgPool) {
         let state = CatalogState::from_pools(pool.clone(), pool.clone());
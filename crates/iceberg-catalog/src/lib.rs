@@ -14,7 +14,7 @@ mod config;
 pub mod service;
 pub use service::{ProjectIdent, SecretIdent, WarehouseIdent};
 
-pub use config::{SecretBackend, CONFIG};
+pub use config::{AuthZBackend, OpenIdProviderConfig, SecretBackend, CONFIG};
 
 pub mod implementations;
 
@@ -0,0 +1,268 @@
+//! Long-lived API keys issued and managed by the catalog.
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use http::StatusCode;
+use iceberg_ext::catalog::rest::ErrorModel;
+use ring::digest;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use super::token_issuer::{
+    check_warehouse_path, CATALOG_TOKEN_AUDIENCE, PROJECT_ID_CLAIM, WAREHOUSE_ID_CLAIM,
+};
+use super::token_verification::{Aud, Claims, PrincipalId};
+use super::{Catalog, ProjectIdent, WarehouseIdent};
+
+/// Prefix of all API keys. Bearer tokens with this prefix are never treated as JWTs.
+pub const API_KEY_PREFIX: &str = "icat_";
+/// Issuer of the claims of API keys.
+pub const API_KEY_ISSUER: &str = "iceberg-catalog";
+
+/// Number of random bytes of an API key.
+const API_KEY_BYTES: usize = 32;
+
+/// An API key as stored in the catalog. The key itself is never stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKey {
+    pub api_key_id: uuid::Uuid,
+    pub name: String,
+    /// Principal the key acts as, formatted as `<issuer>|<subject>`.
+    pub owner: String,
+    /// Project of `warehouse_id`, if the key is scoped to a warehouse.
+    pub project_id: Option<ProjectIdent>,
+    pub warehouse_id: Option<WarehouseIdent>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Details of a request authenticated with an API key.
+#[derive(Debug, Clone)]
+pub struct ApiKeyDetails {
+    pub api_key_id: uuid::Uuid,
+    /// Principal the key acts as.
+    pub owner: PrincipalId,
+    pub warehouse_id: Option<WarehouseIdent>,
+    /// Claims synthesized from the key: `sub` is the owner's subject, scoped keys carry
+    /// the catalog audience and the `project_id` and `warehouse_id` claims.
+    pub claims: Claims,
+}
+
+impl ApiKeyDetails {
+    /// Validate a stored key at `now`.
+    ///
+    /// # Errors
+    /// Fails with 401 if the key is revoked or expired.
+    pub fn try_from_key(
+        key: ApiKey,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Self, ErrorModel> {
+        if key.revoked_at.is_some() {
+            return Err(unauthorized("API key is revoked."));
+        }
+        if key.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(unauthorized("API key is expired."));
+        }
+
+        let (aud, other) = match (key.project_id, key.warehouse_id) {
+            (Some(project_id), Some(warehouse_id)) => (
+                Aud::String(CATALOG_TOKEN_AUDIENCE.to_string()),
+                serde_json::json!({
+                    PROJECT_ID_CLAIM: project_id.to_string(),
+                    WAREHOUSE_ID_CLAIM: warehouse_id.to_string(),
+                }),
+            ),
+            _ => (Aud::Vec(vec![]), serde_json::json!({})),
+        };
+        // Keys created before owners were qualified by their issuer
+        let owner = PrincipalId::from_str(&key.owner)
+            .unwrap_or_else(|_| PrincipalId::new(API_KEY_ISSUER, &key.owner));
+
+        Ok(Self {
+            api_key_id: key.api_key_id,
+            warehouse_id: key.warehouse_id,
+            claims: Claims {
+                sub: owner.subject.clone(),
+                iss: API_KEY_ISSUER.to_string(),
+                aud,
+                exp: key
+                    .expires_at
+                    .map_or(usize::MAX, |e| usize::try_from(e.timestamp()).unwrap_or(0)),
+                iat: usize::try_from(key.created_at.timestamp()).unwrap_or(0),
+                other,
+            },
+            owner,
+        })
+    }
+
+    /// Warehouse-scoped keys may only access the catalog API of their warehouse
+    /// and the config endpoint. Unscoped keys may access everything.
+    ///
+    /// # Errors
+    /// Fails with 403 if the path is outside of the scope of the key.
+    pub fn check_path(&self, path: &str) -> Result<(), ErrorModel> {
+        match self.warehouse_id {
+            Some(warehouse_id) => check_warehouse_path(warehouse_id, path),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Authenticates API keys presented as bearer tokens.
+#[async_trait::async_trait]
+pub trait ApiKeyAuthenticator: std::fmt::Debug + Send + Sync + 'static {
+    /// # Errors
+    /// Fails with 401 if the key is unknown, revoked or expired.
+    async fn authenticate(&self, key: &str) -> Result<ApiKeyDetails, ErrorModel>;
+}
+
+/// Looks up API keys in the catalog.
+pub struct CatalogApiKeys<C: Catalog> {
+    catalog_state: C::State,
+    catalog: PhantomData<C>,
+}
+
+impl<C: Catalog> CatalogApiKeys<C> {
+    #[must_use]
+    pub fn new(catalog_state: C::State) -> Self {
+        Self {
+            catalog_state,
+            catalog: PhantomData,
+        }
+    }
+}
+
+impl<C: Catalog> std::fmt::Debug for CatalogApiKeys<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CatalogApiKeys").finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: Catalog> ApiKeyAuthenticator for CatalogApiKeys<C> {
+    async fn authenticate(&self, key: &str) -> Result<ApiKeyDetails, ErrorModel> {
+        let key = C::get_api_key_by_hash(&hash_api_key(key), self.catalog_state.clone())
+            .await
+            .map_err(|e| e.error)?
+            .ok_or_else(|| unauthorized("Unknown API key."))?;
+        ApiKeyDetails::try_from_key(key, chrono::Utc::now())
+    }
+}
+
+/// Generate a new random API key.
+///
+/// # Errors
+/// Fails if the system random number generator fails.
+pub fn generate_api_key() -> Result<String, ErrorModel> {
+    let mut bytes = [0u8; API_KEY_BYTES];
+    SystemRandom::new().fill(&mut bytes).map_err(|_| {
+        ErrorModel::internal(
+            "Failed to generate API key.",
+            "ApiKeyGenerationFailed",
+            None,
+        )
+    })?;
+    Ok(format!("{API_KEY_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes)))
+}
+
+/// Hash under which an API key is stored.
+#[must_use]
+pub fn hash_api_key(key: &str) -> Vec<u8> {
+    digest::digest(&digest::SHA256, key.as_bytes())
+        .as_ref()
+        .to_vec()
+}
+
+/// Whether a bearer token is an API key.
+#[must_use]
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_PREFIX)
+}
+
+fn unauthorized(message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .message(message)
+        .code(StatusCode::UNAUTHORIZED.into())
+        .r#type("UnauthorizedError")
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::service::token_verification::AuthDetails;
+
+    fn key(warehouse_id: Option<WarehouseIdent>) -> ApiKey {
+        ApiKey {
+            api_key_id: uuid::Uuid::now_v7(),
+            name: "ci".to_string(),
+            owner: "https://idp.example.com|alice".to_string(),
+            project_id: warehouse_id.map(|_| ProjectIdent::from(uuid::Uuid::now_v7())),
+            warehouse_id,
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+            revoked_at: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_api_key() {
+        let a = generate_api_key().unwrap();
+        let b = generate_api_key().unwrap();
+        assert!(is_api_key(&a));
+        assert_ne!(a, b);
+        assert_ne!(hash_api_key(&a), hash_api_key(&b));
+        assert_eq!(hash_api_key(&a), hash_api_key(&a));
+    }
+
+    #[test]
+    fn test_details_from_key() {
+        let now = chrono::Utc::now();
+        let details = ApiKeyDetails::try_from_key(key(None), now).unwrap();
+        assert_eq!(details.claims.sub, "alice");
+        assert_eq!(details.claims.exp, usize::MAX);
+        assert_eq!(
+            AuthDetails::ApiKey(details).principal_id(),
+            PrincipalId::new("https://idp.example.com", "alice")
+        );
+
+        let mut revoked = key(None);
+        revoked.revoked_at = Some(now);
+        assert_eq!(
+            ApiKeyDetails::try_from_key(revoked, now).unwrap_err().code,
+            401
+        );
+
+        let mut expired = key(None);
+        expired.expires_at = Some(now - chrono::Duration::seconds(1));
+        assert_eq!(
+            ApiKeyDetails::try_from_key(expired, now).unwrap_err().code,
+            401
+        );
+    }
+
+    #[test]
+    fn test_check_path() {
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let scoped =
+            ApiKeyDetails::try_from_key(key(Some(warehouse_id)), chrono::Utc::now()).unwrap();
+        assert_eq!(
+            scoped.claims.other[WAREHOUSE_ID_CLAIM],
+            serde_json::json!(warehouse_id.to_string())
+        );
+        scoped.check_path("/catalog/v1/config").unwrap();
+        scoped
+            .check_path(&format!("/catalog/v1/{warehouse_id}/namespaces"))
+            .unwrap();
+        let other = uuid::Uuid::now_v7();
+        scoped
+            .check_path(&format!("/catalog/v1/{other}/namespaces"))
+            .unwrap_err();
+        scoped.check_path("/management/v1/warehouse").unwrap_err();
+
+        let unscoped = ApiKeyDetails::try_from_key(key(None), chrono::Utc::now()).unwrap();
+        unscoped.check_path("/management/v1/warehouse").unwrap();
+    }
+}
@@ -1,7 +1,15 @@
+use axum::Router;
 use std::collections::HashSet;
-
-use super::{ProjectIdent, TableIdentUuid, WarehouseIdent};
+use std::str::FromStr;
+
+use super::tabular_idents::TabularIdentUuid;
+use super::token_issuer::{CATALOG_TOKEN_AUDIENCE, PROJECT_ID_CLAIM, WAREHOUSE_ID_CLAIM};
+use super::token_verification::{Aud, AuthDetails};
+use super::{
+    Catalog, NamespaceIdentUuid, ProjectIdent, SecretStore, State, TableIdentUuid, WarehouseIdent,
+};
 use crate::api::iceberg::v1::{NamespaceIdent, Result};
+use crate::api::ApiContext;
 use crate::request_metadata::RequestMetadata;
 
 #[derive(Debug, Clone)]
@@ -10,6 +18,47 @@ pub struct UserWarehouse {
     pub warehouse_id: Option<WarehouseIdent>,
 }
 
+impl UserWarehouse {
+    /// Project and warehouse embedded in a warehouse-scoped token or API key issued
+    /// by the catalog. Returns `None` for all other tokens.
+    #[must_use]
+    pub fn from_catalog_token(metadata: &RequestMetadata) -> Option<Self> {
+        let claims = match &metadata.auth_details {
+            Some(AuthDetails::CatalogToken(claims)) => claims,
+            Some(AuthDetails::ApiKey(details)) => &details.claims,
+            _ => return None,
+        };
+        if !matches!(&claims.aud, Aud::String(aud) if aud == CATALOG_TOKEN_AUDIENCE) {
+            return None;
+        }
+
+        let project_id = Self::project_from_token(metadata)?;
+        let warehouse_id = claims
+            .other
+            .get(WAREHOUSE_ID_CLAIM)
+            .and_then(serde_json::Value::as_str)
+            .and_then(|w| WarehouseIdent::from_str(w).ok())?;
+
+        Some(Self {
+            project_id: Some(project_id),
+            warehouse_id: Some(warehouse_id),
+        })
+    }
+
+    /// Project from the `project_id` claim of the token, if present.
+    #[must_use]
+    pub fn project_from_token(metadata: &RequestMetadata) -> Option<ProjectIdent> {
+        metadata
+            .auth_details
+            .as_ref()?
+            .claims()
+            .other
+            .get(PROJECT_ID_CLAIM)
+            .and_then(serde_json::Value::as_str)
+            .and_then(|p| ProjectIdent::from_str(p).ok())
+    }
+}
+
 #[async_trait::async_trait]
 
 pub trait AuthZHandler
@@ -95,13 +144,13 @@ where
         state: Self::State,
     ) -> Result<()>;
 
-    /// This should check if the user is allowed to rename the table.
-    /// For rename to work, also "check_create_table" must pass
-    /// for the destination namespace.
+    /// This should check if the user is allowed to rename the table
+    /// and to create it in the destination namespace.
     async fn check_rename_table(
         metadata: &RequestMetadata,
         warehouse_id: WarehouseIdent,
         source: Option<TableIdentUuid>,
+        destination: &NamespaceIdent,
         state: Self::State,
     ) -> Result<()>;
 
@@ -129,6 +178,39 @@ where
     ) -> Result<()>;
 
     // ---------------- Management API ----------------
+    /// `id_requested` is true if the caller chose the ID of the new project,
+    /// false if it was generated by the catalog.
+    async fn check_create_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        id_requested: bool,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_rename_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_delete_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_deactivate_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_activate_project(
+        metadata: &RequestMetadata,
+        project_id: ProjectIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
     async fn check_create_warehouse(
         metadata: &RequestMetadata,
         project_id: &ProjectIdent,
@@ -214,10 +296,13 @@ where
         _: Option<&NamespaceIdent>,
         _: Self::State,
     ) -> Result<()>;
+    /// This should check if the user is allowed to rename the view
+    /// and to create it in the destination namespace.
     async fn check_rename_view(
         _: &RequestMetadata,
         _: WarehouseIdent,
         _: Option<&TableIdentUuid>,
+        _: &NamespaceIdent,
         _: Self::State,
     ) -> Result<()>;
     async fn check_list_views(
@@ -233,6 +318,119 @@ where
         _view: Option<&TableIdentUuid>,
         _state: Self::State,
     ) -> Result<()>;
+
+    // ---------------- Visibility ----------------
+    // Called for every page of a listing after the listing itself was allowed.
+    // Items that are not returned are left out of the response.
+    /// Returns the tables and views of `namespace` the user may see.
+    async fn filter_visible_tabulars(
+        _metadata: &RequestMetadata,
+        _warehouse_id: WarehouseIdent,
+        _namespace: &NamespaceIdent,
+        tabulars: Vec<TabularIdentUuid>,
+        _state: Self::State,
+    ) -> Result<HashSet<TabularIdentUuid>> {
+        Ok(tabulars.into_iter().collect())
+    }
+
+    /// Returns the namespaces the user may see.
+    async fn filter_visible_namespaces(
+        _metadata: &RequestMetadata,
+        _warehouse_id: WarehouseIdent,
+        namespaces: Vec<NamespaceIdent>,
+        _state: Self::State,
+    ) -> Result<HashSet<NamespaceIdent>> {
+        Ok(namespaces.into_iter().collect())
+    }
+
+    // ---------------- Lifecycle ----------------
+    // Called when objects are created, moved or dropped, i.e. to maintain the object
+    // hierarchy in an external authorization system. Hooks for created or moved objects
+    // run right before the transaction is committed - an error aborts the operation. If
+    // the commit fails, they are undone by the hook for dropping or moving the object back.
+    // Hooks for dropped objects run after the commit, errors are only logged.
+    async fn on_project_created(
+        _metadata: &RequestMetadata,
+        _project_id: ProjectIdent,
+        _state: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_project_deleted(_project_id: ProjectIdent, _state: Self::State) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_warehouse_created(
+        _metadata: &RequestMetadata,
+        _project_id: ProjectIdent,
+        _warehouse_id: WarehouseIdent,
+        _state: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_warehouse_deleted(
+        _warehouse_id: WarehouseIdent,
+        _state: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_namespace_created(
+        _metadata: &RequestMetadata,
+        _warehouse_id: WarehouseIdent,
+        _namespace_id: NamespaceIdentUuid,
+        _namespace: &NamespaceIdent,
+        _state: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_namespace_dropped(
+        _warehouse_id: WarehouseIdent,
+        _namespace_id: NamespaceIdentUuid,
+        _state: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for tables and views.
+    async fn on_tabular_created(
+        _metadata: &RequestMetadata,
+        _warehouse_id: WarehouseIdent,
+        _namespace_id: NamespaceIdentUuid,
+        _tabular_id: TableIdentUuid,
+        _state: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for tables and views if they are renamed into another namespace.
+    async fn on_tabular_moved(
+        _warehouse_id: WarehouseIdent,
+        _tabular_id: TableIdentUuid,
+        _source: NamespaceIdentUuid,
+        _destination: NamespaceIdentUuid,
+        _state: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for tables and views.
+    async fn on_tabular_dropped(
+        _warehouse_id: WarehouseIdent,
+        _tabular_id: TableIdentUuid,
+        _state: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Additional management endpoints provided by this handler, i.e. to manage permissions.
+    /// They are merged into the management router.
+    fn management_router<C: Catalog, S: SecretStore>() -> Router<ApiContext<State<Self, C, S>>> {
+        Router::new()
+    }
 }
 
 /// Interface to provide Auth-related functions to the config gateway.
@@ -271,6 +469,8 @@ where
     /// extract it.
     /// If this AuthNHadler does not support enriching the token, or
     /// if no change to the original token is required, return Ok(None).
+    /// In this case the catalog issues its own warehouse-scoped token if
+    /// a token issuer is configured.
     async fn exchange_token_for_warehouse(
         state: A::State,
         previous_request_metadata: &RequestMetadata,
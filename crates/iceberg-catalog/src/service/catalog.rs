@@ -5,8 +5,8 @@ use iceberg_ext::configs::Location;
 use std::collections::{HashMap, HashSet};
 
 use super::{
-    storage::StorageProfile, NamespaceIdentUuid, ProjectIdent, TableIdentUuid, WarehouseIdent,
-    WarehouseStatus,
+    api_key::ApiKey, storage::StorageProfile, NamespaceIdentUuid, ProjectIdent, ProjectStatus,
+    TableIdentUuid, WarehouseIdent, WarehouseStatus,
 };
 pub use crate::api::iceberg::v1::{
     CreateNamespaceRequest, CreateNamespaceResponse, ListNamespacesQuery, ListNamespacesResponse,
@@ -15,6 +15,7 @@ pub use crate::api::iceberg::v1::{
 };
 use crate::api::iceberg::v1::{PaginatedTabulars, PaginationQuery};
 
+use crate::service::event_publisher::{OutboxEvent, Payload};
 use crate::service::health::HealthExt;
 
 #[async_trait::async_trait]
@@ -44,6 +45,65 @@ pub struct GetNamespaceResponse {
     pub namespace_id: NamespaceIdentUuid,
     pub warehouse_id: WarehouseIdent,
     pub properties: Option<std::collections::HashMap<String, String>>,
+    /// Storage profile override of the namespace.
+    /// If `None`, tabulars in this namespace use the inherited storage profile.
+    pub storage_profile: Option<StorageProfile>,
+    /// Storage secret ID of the namespace storage profile override.
+    pub storage_secret_id: Option<SecretIdent>,
+    /// Storage profile override of the nearest ancestor namespace that has one.
+    /// If `None`, the storage profile of the warehouse is inherited.
+    pub inherited_storage_profile: Option<StorageProfile>,
+    /// Storage secret ID of the inherited storage profile override.
+    pub inherited_storage_secret_id: Option<SecretIdent>,
+}
+
+impl GetNamespaceResponse {
+    /// Storage profile override that applies to tabulars in this namespace,
+    /// either its own or the one inherited from an ancestor namespace.
+    /// `None` if tabulars use the storage of the warehouse.
+    #[must_use]
+    pub fn storage_override(&self) -> Option<(&StorageProfile, Option<SecretIdent>)> {
+        match (&self.storage_profile, &self.inherited_storage_profile) {
+            (Some(storage_profile), _) => Some((storage_profile, self.storage_secret_id)),
+            (None, Some(storage_profile)) => {
+                Some((storage_profile, self.inherited_storage_secret_id))
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Storage profile and secret to use for tabulars in this namespace.
+    /// Falls back to the storage of the warehouse if neither the namespace
+    /// nor any of its ancestors has a storage profile override.
+    #[must_use]
+    pub fn effective_storage(
+        &self,
+        warehouse: &GetWarehouseResponse,
+    ) -> (StorageProfile, Option<SecretIdent>) {
+        match self.storage_override() {
+            Some((storage_profile, secret_id)) => (storage_profile.clone(), secret_id),
+            None => (
+                warehouse.storage_profile.clone(),
+                warehouse.storage_secret_id,
+            ),
+        }
+    }
+
+    /// Storage profile and secret tabulars in this namespace would use
+    /// if the namespace had no storage profile override of its own.
+    #[must_use]
+    pub fn inherited_storage(
+        &self,
+        warehouse: &GetWarehouseResponse,
+    ) -> (StorageProfile, Option<SecretIdent>) {
+        match &self.inherited_storage_profile {
+            Some(storage_profile) => (storage_profile.clone(), self.inherited_storage_secret_id),
+            None => (
+                warehouse.storage_profile.clone(),
+                warehouse.storage_secret_id,
+            ),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -78,6 +138,18 @@ pub struct GetStorageConfigResponse {
     pub storage_secret_ident: Option<SecretIdent>,
 }
 
+#[derive(Debug, Clone)]
+pub struct GetProjectResponse {
+    /// ID of the project.
+    pub project_id: ProjectIdent,
+    /// Name of the project.
+    pub name: String,
+    pub description: Option<String>,
+    /// Whether the project is active.
+    pub status: ProjectStatus,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetWarehouseResponse {
     /// ID of the warehouse.
@@ -129,6 +201,13 @@ where
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<GetNamespaceResponse>;
 
+    // Should only return a namespace if the warehouse is active.
+    async fn get_namespace_by_id<'a>(
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<GetNamespaceResponse>;
+
     /// Return Err only on unexpected errors, not if the namespace does not exist.
     /// If the namespace does not exist, return Ok(false).
     ///
@@ -157,6 +236,29 @@ where
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<()>;
 
+    /// Set or remove the storage profile override of a namespace.
+    ///
+    /// If `storage_profile` is `None`, tables in the namespace use the
+    /// inherited storage profile again.
+    async fn set_namespace_storage_profile<'a>(
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        storage_profile: Option<StorageProfile>,
+        storage_secret_id: Option<SecretIdent>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Return true if the namespace or any of its descendants contains tabulars,
+    /// including staged and deleted ones.
+    ///
+    /// Until the transaction ends, no tabulars can be created in or moved
+    /// into these namespaces.
+    async fn namespace_contains_tabulars<'a>(
+        warehouse_id: WarehouseIdent,
+        namespace_id: NamespaceIdentUuid,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<bool>;
+
     async fn create_table<'a>(
         namespace_id: NamespaceIdentUuid,
         table: &TableIdent,
@@ -262,8 +364,50 @@ where
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<WarehouseIdent>;
 
-    /// Return a list of all project ids in the catalog
-    async fn list_projects(catalog_state: Self::State) -> Result<HashSet<ProjectIdent>>;
+    // ---------------- Project Management API ----------------
+
+    /// Create a project.
+    async fn create_project<'a>(
+        project_id: ProjectIdent,
+        project_name: String,
+        description: Option<String>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Get a project, including inactive projects.
+    /// Return Ok(None) if the project does not exist.
+    async fn get_project<'a>(
+        project_id: ProjectIdent,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<Option<GetProjectResponse>>;
+
+    /// Return a list of all projects in the catalog
+    async fn list_projects(
+        // If None, return all projects
+        // If Some, return only the projects in the set
+        project_id_filter: Option<&HashSet<ProjectIdent>>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<GetProjectResponse>>;
+
+    /// Rename a project.
+    async fn rename_project<'a>(
+        project_id: ProjectIdent,
+        new_name: &str,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Delete a project. Fails if the project still contains warehouses.
+    async fn delete_project<'a>(
+        project_id: ProjectIdent,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Set the status of a project.
+    async fn set_project_status<'a>(
+        project_id: ProjectIdent,
+        status: ProjectStatus,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
 
     /// Return a list of all warehouse in a project
     async fn list_warehouses(
@@ -310,6 +454,43 @@ where
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<()>;
 
+    // ---------------- API Keys ----------------
+
+    /// Store a new API key. Only the hash of the key is stored.
+    async fn create_api_key(
+        name: &str,
+        owner: &str,
+        warehouse_id: Option<WarehouseIdent>,
+        key_hash: &[u8],
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        catalog_state: Self::State,
+    ) -> Result<ApiKey>;
+
+    /// Return all API keys of an owner, including revoked and expired keys.
+    async fn list_api_keys(owner: &str, catalog_state: Self::State) -> Result<Vec<ApiKey>>;
+
+    /// Return the API key with the given hash, even if it is revoked or expired.
+    /// Return Ok(None) if no such key exists.
+    async fn get_api_key_by_hash(
+        key_hash: &[u8],
+        catalog_state: Self::State,
+    ) -> Result<Option<ApiKey>>;
+
+    /// Set or remove the expiry of an API key of the owner.
+    async fn set_api_key_expiry(
+        api_key_id: uuid::Uuid,
+        owner: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        catalog_state: Self::State,
+    ) -> Result<ApiKey>;
+
+    /// Revoke an API key of the owner. Revoked keys are kept for auditing.
+    async fn revoke_api_key(
+        api_key_id: uuid::Uuid,
+        owner: &str,
+        catalog_state: Self::State,
+    ) -> Result<()>;
+
     /// Return Err only on unexpected errors, not if the table does not exist.
     /// If include_staged is true, also return staged tables.
     /// If the table does not exist, return Ok(None).
@@ -365,6 +546,59 @@ where
         destination: &TableIdent,
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'_>,
     ) -> Result<()>;
+
+    // ---------------- Event Outbox ----------------
+
+    /// Store events in the outbox. They are published once the transaction is committed.
+    async fn enqueue_events<'a>(
+        events: Vec<Payload>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Acquire the exclusive right to claim events until the transaction ends.
+    /// Returns false if it is held by another transaction.
+    async fn lock_event_outbox<'a>(
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<bool>;
+
+    /// Pending events that are due at `now`, ordered by their sequence.
+    /// Events are not due while an earlier pending event with the same ordering
+    /// key waits for its next attempt.
+    async fn list_due_events<'a>(
+        now: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<Vec<OutboxEvent>>;
+
+    /// Set the time of the next attempt of events, without counting an attempt.
+    /// Used to claim events while they are published and to release them.
+    async fn reschedule_events<'a>(
+        sequence_ids: &[i64],
+        next_attempt_at: chrono::DateTime<chrono::Utc>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    async fn mark_event_sent<'a>(
+        sequence_id: i64,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Record a failed attempt to publish an event. `sent_to` are the backends
+    /// that received the event so far. If `next_attempt_at` is `None`, the event
+    /// is given up and no longer blocks later events.
+    async fn mark_event_failed<'a>(
+        sequence_id: i64,
+        error: &str,
+        sent_to: &[String],
+        next_attempt_at: Option<chrono::DateTime<chrono::Utc>>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Delete events that were sent before `sent_before`.
+    async fn delete_sent_events<'a>(
+        sent_before: chrono::DateTime<chrono::Utc>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
 }
 
 #[derive(Debug, Clone, Default, Copy, PartialEq)]
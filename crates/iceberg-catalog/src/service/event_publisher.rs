@@ -1,64 +1,145 @@
 use crate::service::tabular_idents::TabularIdentUuid;
+use crate::service::{Catalog, NamespaceIdentUuid, Result, Transaction};
+use crate::WarehouseIdent;
 use async_trait::async_trait;
 use cloudevents::Event;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::sync::Arc;
 use uuid::Uuid;
 
-#[derive(Debug, Clone)]
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaBackend;
+mod outbox;
+pub use outbox::{OutboxEvent, OutboxRelay};
+mod webhook;
+pub use webhook::{WebhookBackend, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+/// Writes events to the outbox of the catalog and wakes the [`OutboxRelay`] once they
+/// are committed.
+#[derive(Debug, Clone, Default)]
 pub struct CloudEventsPublisher {
-    tx: tokio::sync::mpsc::Sender<Message>,
-    timeout: tokio::time::Duration,
+    notify: Arc<tokio::sync::Notify>,
 }
 
 impl CloudEventsPublisher {
     #[must_use]
-    pub fn new(tx: tokio::sync::mpsc::Sender<Message>) -> Self {
-        Self::new_with_timeout(tx, tokio::time::Duration::from_millis(50))
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    #[must_use]
-    pub fn new_with_timeout(
-        tx: tokio::sync::mpsc::Sender<Message>,
-        timeout: tokio::time::Duration,
-    ) -> Self {
-        Self { tx, timeout }
-    }
-
-    /// # Errors
+    /// Store an event in the outbox as part of `transaction`. It is only published
+    /// if the transaction is committed. Call [`Self::notify`] after the commit to
+    /// publish it without waiting for the next poll of the relay.
     ///
-    /// Returns an error if the event cannot be sent to the channel due to capacity / timeout.
-    pub async fn publish(
+    /// Events must be written after the change they describe, so that the order of
+    /// events of an entity matches the order of the changes.
+    ///
+    /// # Errors
+    /// Returns an error if the event cannot be written to the outbox.
+    pub async fn publish<C: Catalog>(
         &self,
         id: Uuid,
         typ: &str,
         data: serde_json::Value,
         metadata: EventMetadata,
-    ) -> anyhow::Result<()> {
-        self.tx
-            .send_timeout(
-                Message::Event(Payload {
-                    id,
-                    typ: typ.to_string(),
-                    data,
-                    metadata,
-                }),
-                self.timeout,
-            )
-            .await
-            .map_err(|e| {
-                tracing::warn!("Failed to emit event with id: '{}' due to: '{}'.", id, e);
-                e
-            })?;
-        Ok(())
+        transaction: <C::Transaction as Transaction<C::State>>::Transaction<'_>,
+    ) -> Result<()> {
+        C::enqueue_events(
+            vec![Payload {
+                id,
+                typ: typ.to_string(),
+                data,
+                metadata,
+            }],
+            transaction,
+        )
+        .await
     }
+
+    /// Wake the relay after a transaction containing events was committed.
+    pub fn notify(&self) {
+        self.notify.notify_one();
+    }
+
+    async fn notified(&self) {
+        self.notify.notified().await;
+    }
+}
+
+/// The entity an event is about.
+#[derive(Hash, PartialEq, Debug, Clone, Copy, Eq, Serialize, Deserialize)]
+pub enum EventEntity {
+    Table(Uuid),
+    View(Uuid),
+    Namespace(Uuid),
+    Warehouse(Uuid),
 }
 
-#[derive(Debug, Clone)]
+impl EventEntity {
+    #[must_use]
+    pub fn typ_str(&self) -> &'static str {
+        match self {
+            EventEntity::Table(_) => "Table",
+            EventEntity::View(_) => "View",
+            EventEntity::Namespace(_) => "Namespace",
+            EventEntity::Warehouse(_) => "Warehouse",
+        }
+    }
+
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        match self {
+            EventEntity::Table(id)
+            | EventEntity::View(id)
+            | EventEntity::Namespace(id)
+            | EventEntity::Warehouse(id) => *id,
+        }
+    }
+
+    /// The tabular of table and view events.
+    #[must_use]
+    pub fn tabular_id(&self) -> Option<TabularIdentUuid> {
+        match self {
+            EventEntity::Table(id) => Some(TabularIdentUuid::Table(*id)),
+            EventEntity::View(id) => Some(TabularIdentUuid::View(*id)),
+            EventEntity::Namespace(_) | EventEntity::Warehouse(_) => None,
+        }
+    }
+}
+
+impl From<TabularIdentUuid> for EventEntity {
+    fn from(ident: TabularIdentUuid) -> Self {
+        match ident {
+            TabularIdentUuid::Table(id) => EventEntity::Table(id),
+            TabularIdentUuid::View(id) => EventEntity::View(id),
+        }
+    }
+}
+
+impl From<NamespaceIdentUuid> for EventEntity {
+    fn from(ident: NamespaceIdentUuid) -> Self {
+        EventEntity::Namespace(*ident)
+    }
+}
+
+impl From<WarehouseIdent> for EventEntity {
+    fn from(ident: WarehouseIdent) -> Self {
+        EventEntity::Warehouse(*ident)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EventMetadata {
-    pub tabular_id: TabularIdentUuid,
+    // Events written before namespaces and warehouses had events only refer to tabulars
+    #[serde(alias = "tabular_id")]
+    pub entity: EventEntity,
     pub warehouse_id: Uuid,
+    /// Name of the table, view or warehouse, or the last level of the namespace.
     pub name: String,
+    /// Namespace of the tabular or the namespace itself. Empty for warehouse events.
     pub namespace: String,
     pub prefix: String,
     pub num_events: usize,
@@ -66,7 +147,7 @@ pub struct EventMetadata {
     pub trace_id: Uuid,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Payload {
     pub id: Uuid,
     pub typ: String,
@@ -74,93 +155,90 @@ pub struct Payload {
     pub metadata: EventMetadata,
 }
 
-#[derive(Debug)]
-pub enum Message {
-    Event(Payload),
-    Shutdown,
-}
-
-#[derive(Debug)]
-pub struct CloudEventsPublisherBackgroundTask {
-    pub source: tokio::sync::mpsc::Receiver<Message>,
-    pub sinks: Vec<Arc<dyn CloudEventBackend + Sync + Send>>,
-}
+impl Payload {
+    /// Events with the same ordering key are published in the order they were written.
+    #[must_use]
+    pub fn ordering_key(&self) -> Uuid {
+        self.metadata.entity.id()
+    }
 
-impl CloudEventsPublisherBackgroundTask {
     /// # Errors
-    /// Returns an error if the `Event` cannot be built from the data passed into this function
-    pub async fn publish(mut self) -> anyhow::Result<()> {
-        while let Some(Message::Event(Payload {
+    /// Returns an error if the `Event` cannot be built from the payload.
+    pub fn to_event(&self) -> anyhow::Result<Event> {
+        use cloudevents::{EventBuilder, EventBuilderV10};
+
+        let Payload {
             id,
             typ,
             data,
             metadata,
-        })) = self.source.recv().await
-        {
-            use cloudevents::{EventBuilder, EventBuilderV10};
-
-            let event_builder = EventBuilderV10::new()
-                .id(id.to_string())
-                .source(format!(
-                    "uri:iceberg-catalog-service:{}",
-                    hostname::get()
-                        .map(|os| os.to_string_lossy().to_string())
-                        .unwrap_or("hostname-unavailable".into())
-                ))
-                .ty(typ)
-                .data("application/json", data);
-
-            let EventMetadata {
-                tabular_id,
-                warehouse_id,
-                name,
-                namespace,
-                prefix,
-                num_events,
-                sequence_number,
-                trace_id,
-            } = metadata;
-            // TODO: this could be more elegant with a proc macro to give us IntoIter for EventMetadata
-            let event = event_builder
+        } = self;
+
+        let mut event_builder = EventBuilderV10::new()
+            .id(id.to_string())
+            .source(format!(
+                "uri:iceberg-catalog-service:{}",
+                hostname::get()
+                    .map(|os| os.to_string_lossy().to_string())
+                    .unwrap_or("hostname-unavailable".into())
+            ))
+            .ty(typ.clone())
+            .data("application/json", data.clone());
+
+        let EventMetadata {
+            entity,
+            warehouse_id,
+            name,
+            namespace,
+            prefix,
+            num_events,
+            sequence_number,
+            trace_id,
+        } = metadata;
+        // TODO: this could be more elegant with a proc macro to give us IntoIter for EventMetadata
+        event_builder = event_builder
+            .extension("entity-type", entity.typ_str())
+            .extension("entity-id", entity.id().to_string());
+        if let Some(tabular_id) = entity.tabular_id() {
+            event_builder = event_builder
                 .extension("tabular-type", tabular_id.typ_str())
-                .extension("tabular-id", tabular_id.to_string())
-                .extension("warehouse-id", warehouse_id.to_string())
-                .extension("name", name.to_string())
-                .extension("namespace", namespace.to_string())
-                .extension("prefix", prefix.to_string())
-                // TODO: decide what to do with these numbers, likely they are never anywhere close to
-                // saturating the respective int types, so probably a non-issue. Still we are converting
-                // the numbers to_string here to avoid usize -> i64 which is what EventBuilderV10
-                // uses to represent integers. The CloudEvents spec states i32 would be the correct int
-                // type.
-                .extension("num-events", num_events.to_string())
-                .extension("sequence-number", sequence_number.to_string())
-                // Implement distributed tracing: https://github.com/hansetag/iceberg-catalog/issues/63
-                .extension("trace-id", trace_id.to_string())
-                .build()?;
-
-            for sink in &self.sinks {
-                if let Err(e) = sink.publish(event.clone()).await {
-                    tracing::warn!(
-                        "Failed to emit event with id: '{}' on sink: '{}' due to: '{}'.",
-                        id,
-                        sink.name(),
-                        e
-                    );
-                }
-            }
+                .extension("tabular-id", tabular_id.to_string());
         }
-
-        Ok(())
+        if !namespace.is_empty() {
+            event_builder = event_builder.extension("namespace", namespace.to_string());
+        }
+        Ok(event_builder
+            .extension("warehouse-id", warehouse_id.to_string())
+            .extension("name", name.to_string())
+            .extension("prefix", prefix.to_string())
+            // TODO: decide what to do with these numbers, likely they are never anywhere close to
+            // saturating the respective int types, so probably a non-issue. Still we are converting
+            // the numbers to_string here to avoid usize -> i64 which is what EventBuilderV10
+            // uses to represent integers. The CloudEvents spec states i32 would be the correct int
+            // type.
+            .extension("num-events", num_events.to_string())
+            .extension("sequence-number", sequence_number.to_string())
+            // Implement distributed tracing: https://github.com/hansetag/iceberg-catalog/issues/63
+            .extension("trace-id", trace_id.to_string())
+            .build()?)
     }
 }
 
 #[async_trait]
 pub trait CloudEventBackend: Debug {
+    /// Publish an event. Failed events are retried by the [`OutboxRelay`], unless the
+    /// error is a [`PermanentError`].
     async fn publish(&self, event: Event) -> anyhow::Result<()>;
+    /// Unique name of the backend, used to track to which backends an event was published.
     fn name(&self) -> &str;
 }
 
+/// A failure that won't be resolved by retrying, for example because the
+/// receiver rejected the event. The event is not retried.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct PermanentError(pub String);
+
 #[cfg(feature = "nats")]
 #[derive(Debug)]
 pub struct NatsBackend {
@@ -198,3 +276,100 @@ impl CloudEventBackend for TracingPublisher {
         "tracing-publisher"
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cloudevents::AttributesReader;
+
+    fn payload(entity: EventEntity, namespace: &str) -> Payload {
+        Payload {
+            id: Uuid::now_v7(),
+            typ: "createNamespace".to_string(),
+            data: serde_json::json!({"a": 1}),
+            metadata: EventMetadata {
+                entity,
+                warehouse_id: Uuid::now_v7(),
+                name: "my_namespace".to_string(),
+                namespace: namespace.to_string(),
+                prefix: String::new(),
+                num_events: 1,
+                sequence_number: 0,
+                trace_id: Uuid::now_v7(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_namespace_event_extensions() {
+        let namespace_id = Uuid::now_v7();
+        let event = payload(EventEntity::Namespace(namespace_id), "my_namespace")
+            .to_event()
+            .unwrap();
+        assert_eq!(event.ty(), "createNamespace");
+        assert_eq!(
+            event.extension("entity-type").unwrap().to_string(),
+            "Namespace"
+        );
+        assert_eq!(
+            event.extension("entity-id").unwrap().to_string(),
+            namespace_id.to_string()
+        );
+        assert_eq!(
+            event.extension("namespace").unwrap().to_string(),
+            "my_namespace"
+        );
+        assert!(event.extension("tabular-id").is_none());
+    }
+
+    #[test]
+    fn test_table_event_keeps_tabular_extensions() {
+        let table_id = Uuid::now_v7();
+        let event = payload(EventEntity::Table(table_id), "my_namespace")
+            .to_event()
+            .unwrap();
+        assert_eq!(
+            event.extension("tabular-type").unwrap().to_string(),
+            "Table"
+        );
+        assert_eq!(
+            event.extension("tabular-id").unwrap().to_string(),
+            table_id.to_string()
+        );
+        assert_eq!(
+            event.extension("entity-id").unwrap().to_string(),
+            table_id.to_string()
+        );
+    }
+
+    #[test]
+    fn test_warehouse_event_has_no_namespace() {
+        let warehouse_id = Uuid::now_v7();
+        let payload = payload(EventEntity::Warehouse(warehouse_id), "");
+        assert_eq!(payload.ordering_key(), warehouse_id);
+        let event = payload.to_event().unwrap();
+        assert_eq!(
+            event.extension("entity-type").unwrap().to_string(),
+            "Warehouse"
+        );
+        assert!(event.extension("namespace").is_none());
+    }
+
+    #[test]
+    fn test_deserialize_metadata_of_tabular_event() {
+        // Metadata as stored in the outbox before events referred to other entities
+        let table_id = Uuid::now_v7();
+        let metadata: EventMetadata = serde_json::from_value(serde_json::json!({
+            "tabular_id": {"Table": table_id},
+            "warehouse_id": Uuid::now_v7(),
+            "name": "my_table",
+            "namespace": "my_namespace",
+            "prefix": "",
+            "num_events": 1,
+            "sequence_number": 0,
+            "trace_id": Uuid::now_v7(),
+        }))
+        .unwrap();
+        assert_eq!(metadata.entity, EventEntity::Table(table_id));
+    }
+}
@@ -0,0 +1,213 @@
+use super::CloudEventBackend;
+use crate::config::{KafkaAcks, KafkaConfig};
+use async_trait::async_trait;
+use cloudevents::{AttributesReader, Data, Event};
+use rdkafka::config::ClientConfig;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use std::time::Duration;
+
+/// Extension whose value is used as message key, so that all events of a table,
+/// view, namespace or warehouse end up in the same partition and stay ordered.
+const PARTITION_KEY_EXTENSION: &str = "entity-id";
+
+/// Publishes events to a Kafka topic using the binary content mode of the
+/// `CloudEvents` Kafka protocol binding.
+pub struct KafkaBackend {
+    producer: FutureProducer,
+    topic: String,
+    queue_timeout: Duration,
+}
+
+impl std::fmt::Debug for KafkaBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaBackend")
+            .field("topic", &self.topic)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KafkaBackend {
+    /// # Errors
+    /// Fails if the producer can't be created from the configuration.
+    pub fn try_new(config: &KafkaConfig) -> anyhow::Result<Self> {
+        let producer = client_config(config).create()?;
+        Ok(Self {
+            producer,
+            topic: config.topic.clone(),
+            queue_timeout: Duration::from_millis(config.delivery_timeout_ms),
+        })
+    }
+}
+
+#[async_trait]
+impl CloudEventBackend for KafkaBackend {
+    async fn publish(&self, event: Event) -> anyhow::Result<()> {
+        let message = BinaryMessage::try_from_event(&event)?;
+
+        let headers = message
+            .headers
+            .iter()
+            .fold(OwnedHeaders::new(), |headers, (key, value)| {
+                headers.insert(Header {
+                    key: key.as_str(),
+                    value: Some(value.as_slice()),
+                })
+            });
+        let mut record: FutureRecord<'_, str, [u8]> =
+            FutureRecord::to(&self.topic).headers(headers);
+        if let Some(key) = &message.key {
+            record = record.key(key.as_str());
+        }
+        if let Some(payload) = &message.payload {
+            record = record.payload(payload.as_slice());
+        }
+
+        self.producer
+            .send(record, self.queue_timeout)
+            .await
+            .map_err(|(e, _)| e)?;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "kafka-publisher"
+    }
+}
+
+fn client_config(config: &KafkaConfig) -> ClientConfig {
+    let mut client_config = ClientConfig::new();
+    client_config
+        .set("bootstrap.servers", &config.brokers)
+        .set("acks", config.acks.as_str())
+        .set("message.timeout.ms", config.delivery_timeout_ms.to_string());
+
+    // Retries must not reorder the events of a partition
+    if config.acks == KafkaAcks::All {
+        client_config.set("enable.idempotence", "true");
+    } else {
+        client_config.set("max.in.flight.requests.per.connection", "1");
+    }
+
+    let optional = [
+        (
+            "security.protocol",
+            config.security_protocol.map(|p| p.as_str().to_string()),
+        ),
+        ("sasl.mechanism", config.sasl_mechanism.clone()),
+        ("sasl.username", config.sasl_username.clone()),
+        ("sasl.password", config.sasl_password.clone()),
+        (
+            "ssl.ca.location",
+            config
+                .ssl_ca_location
+                .as_ref()
+                .map(|p| p.display().to_string()),
+        ),
+        (
+            "ssl.certificate.location",
+            config
+                .ssl_certificate_location
+                .as_ref()
+                .map(|p| p.display().to_string()),
+        ),
+        (
+            "ssl.key.location",
+            config
+                .ssl_key_location
+                .as_ref()
+                .map(|p| p.display().to_string()),
+        ),
+        ("ssl.key.password", config.ssl_key_password.clone()),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            client_config.set(key, value);
+        }
+    }
+
+    for (key, value) in &config.properties {
+        client_config.set(key.replace('_', "."), value);
+    }
+
+    client_config
+}
+
+/// A `CloudEvent` in binary content mode: attributes and extensions are `ce_` prefixed
+/// headers, the data is the payload.
+#[derive(Debug, PartialEq)]
+struct BinaryMessage {
+    key: Option<String>,
+    headers: Vec<(String, Vec<u8>)>,
+    payload: Option<Vec<u8>>,
+}
+
+impl BinaryMessage {
+    fn try_from_event(event: &Event) -> anyhow::Result<Self> {
+        let mut headers = Vec::new();
+        for (name, value) in event.iter_attributes() {
+            if name == "datacontenttype" {
+                continue;
+            }
+            headers.push((format!("ce_{name}"), value.to_string().into_bytes()));
+        }
+        for (name, value) in event.iter_extensions() {
+            headers.push((format!("ce_{name}"), value.to_string().into_bytes()));
+        }
+        if let Some(content_type) = event.datacontenttype() {
+            headers.push(("content-type".to_string(), content_type.as_bytes().to_vec()));
+        }
+
+        let payload = match event.data() {
+            Some(Data::Binary(data)) => Some(data.clone()),
+            Some(Data::String(data)) => Some(data.clone().into_bytes()),
+            Some(Data::Json(data)) => Some(serde_json::to_vec(data)?),
+            None => None,
+        };
+
+        Ok(Self {
+            key: event
+                .extension(PARTITION_KEY_EXTENSION)
+                .map(ToString::to_string),
+            headers,
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cloudevents::{EventBuilder, EventBuilderV10};
+
+    #[test]
+    fn test_binary_message() {
+        let entity_id = uuid::Uuid::now_v7().to_string();
+        let event = EventBuilderV10::new()
+            .id("1")
+            .source("uri:iceberg-catalog-service:test")
+            .ty("updateTable")
+            .data("application/json", serde_json::json!({"a": 1}))
+            .extension("entity-id", entity_id.clone())
+            .build()
+            .unwrap();
+
+        let message = BinaryMessage::try_from_event(&event).unwrap();
+        assert_eq!(message.key, Some(entity_id.clone()));
+        assert_eq!(message.payload, Some(br#"{"a":1}"#.to_vec()));
+
+        let header = |name: &str| {
+            message
+                .headers
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| String::from_utf8(value.clone()).unwrap())
+        };
+        assert_eq!(header("ce_specversion").as_deref(), Some("1.0"));
+        assert_eq!(header("ce_id").as_deref(), Some("1"));
+        assert_eq!(header("ce_type").as_deref(), Some("updateTable"));
+        assert_eq!(header("ce_entity-id"), Some(entity_id));
+        assert_eq!(header("content-type").as_deref(), Some("application/json"));
+        assert_eq!(header("ce_datacontenttype"), None);
+    }
+}
@@ -0,0 +1,458 @@
+use super::{CloudEventBackend, CloudEventsPublisher, Payload, PermanentError};
+use crate::service::{Catalog, Result, Transaction};
+use crate::CONFIG;
+use futures::future::Either;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum number of events claimed at once by the relay.
+const BATCH_SIZE: i64 = 100;
+
+/// An event stored in the outbox that was not published yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEvent {
+    /// Position of the event in the outbox, events are published in this order.
+    pub sequence_id: i64,
+    pub payload: Payload,
+    /// Number of failed attempts to publish the event.
+    pub attempts: i32,
+    pub next_attempt_at: chrono::DateTime<chrono::Utc>,
+    /// Names of the backends the event was already published to.
+    pub sent_to: Vec<String>,
+}
+
+/// Publishes the events of the outbox to all backends and marks them as sent.
+///
+/// Delivery is at-least-once: the relay claims a batch of due events in a short
+/// transaction and publishes them outside of it. Events are retried with exponential
+/// backoff for the backends that failed only, and later events with the same ordering
+/// key are held back until they succeed. Events are given up after `max_attempts` or if
+/// all failing backends returned a [`PermanentError`]. Only one relay claims events at
+/// a time, relays of other replicas wait until it releases the outbox.
+pub struct OutboxRelay<C: Catalog> {
+    catalog_state: C::State,
+    publisher: CloudEventsPublisher,
+    sinks: Vec<Arc<dyn CloudEventBackend + Sync + Send>>,
+    poll_interval: Duration,
+    max_backoff: chrono::Duration,
+    retention: chrono::Duration,
+    max_attempts: i32,
+    claim_timeout: chrono::Duration,
+}
+
+/// A failed attempt to publish an event to all backends.
+#[derive(Debug)]
+struct PublishFailure {
+    error: String,
+    /// Backends that received the event, including previous attempts.
+    sent_to: Vec<String>,
+    /// Whether retrying can't succeed.
+    permanent: bool,
+}
+
+impl<C: Catalog> std::fmt::Debug for OutboxRelay<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutboxRelay")
+            .field("sinks", &self.sinks)
+            .field("poll_interval", &self.poll_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Catalog> OutboxRelay<C> {
+    #[must_use]
+    pub fn new(
+        catalog_state: C::State,
+        publisher: CloudEventsPublisher,
+        sinks: Vec<Arc<dyn CloudEventBackend + Sync + Send>>,
+    ) -> Self {
+        Self {
+            catalog_state,
+            publisher,
+            sinks,
+            poll_interval: Duration::from_millis(CONFIG.event_outbox_poll_interval_ms),
+            max_backoff: seconds(CONFIG.event_outbox_max_backoff_seconds),
+            retention: seconds(CONFIG.event_outbox_retention_seconds),
+            max_attempts: CONFIG.event_outbox_max_attempts.max(1),
+            claim_timeout: seconds(CONFIG.event_outbox_claim_timeout_seconds),
+        }
+    }
+
+    /// Publish events until `shutdown` completes. Events are published when the
+    /// publisher is notified or the poll interval elapsed.
+    pub async fn run(self, shutdown: impl Future<Output = ()> + Send) {
+        let mut shutdown = std::pin::pin!(shutdown);
+        loop {
+            let wait = match self.relay_batch().await {
+                Ok(true) => Duration::ZERO,
+                Ok(false) => self.poll_interval,
+                Err(e) => {
+                    tracing::warn!("Failed to relay events of the outbox: {:?}", e.error);
+                    self.poll_interval
+                }
+            };
+
+            let wake = std::pin::pin!(tokio::time::timeout(wait, self.publisher.notified()));
+            if let Either::Left(_) = futures::future::select(shutdown.as_mut(), wake).await {
+                tracing::info!("Exiting outbox relay");
+                return;
+            }
+        }
+    }
+
+    /// Publish the next batch of due events.
+    /// Returns whether more events may be due.
+    async fn relay_batch(&self) -> Result<bool> {
+        let Some(events) = self.claim_batch().await? else {
+            return Ok(false);
+        };
+
+        let mut blocked = HashSet::new();
+        let mut skipped = vec![];
+        let mut completed = 0;
+        for event in &events {
+            let ordering_key = event.payload.ordering_key();
+            if blocked.contains(&ordering_key) {
+                skipped.push(event.sequence_id);
+                continue;
+            }
+
+            let result = self.publish(event).await;
+            let mut transaction = C::Transaction::begin_write(self.catalog_state.clone()).await?;
+            match result {
+                Ok(()) => {
+                    C::mark_event_sent(event.sequence_id, transaction.transaction()).await?;
+                    completed += 1;
+                }
+                Err(failure) => {
+                    let next_attempt_at = (!failure.permanent
+                        && event.attempts.saturating_add(1) < self.max_attempts)
+                        .then(|| chrono::Utc::now() + self.backoff(event.attempts));
+                    if let Some(next_attempt_at) = next_attempt_at {
+                        tracing::warn!(
+                            "Failed to publish event with id: '{}', retrying at {} due to: '{}'.",
+                            event.payload.id,
+                            next_attempt_at,
+                            failure.error
+                        );
+                        blocked.insert(ordering_key);
+                    } else {
+                        tracing::error!(
+                            "Failed to publish event with id: '{}' after {} attempts, giving up due to: '{}'.",
+                            event.payload.id,
+                            event.attempts.saturating_add(1),
+                            failure.error
+                        );
+                        completed += 1;
+                    }
+                    C::mark_event_failed(
+                        event.sequence_id,
+                        &failure.error,
+                        &failure.sent_to,
+                        next_attempt_at,
+                        transaction.transaction(),
+                    )
+                    .await?;
+                }
+            }
+            transaction.commit().await?;
+        }
+
+        // Held back by a failed event, they become due together with it
+        if !skipped.is_empty() {
+            let mut transaction = C::Transaction::begin_write(self.catalog_state.clone()).await?;
+            C::reschedule_events(&skipped, chrono::Utc::now(), transaction.transaction()).await?;
+            transaction.commit().await?;
+        }
+
+        Ok(completed > 0 && i64::try_from(events.len()).unwrap_or(i64::MAX) == BATCH_SIZE)
+    }
+
+    /// Claim the next batch of due events for `claim_timeout`, so that they are not
+    /// published by other relays meanwhile. Returns `None` if another relay is
+    /// claiming events.
+    async fn claim_batch(&self) -> Result<Option<Vec<OutboxEvent>>> {
+        let mut transaction = C::Transaction::begin_write(self.catalog_state.clone()).await?;
+        if !C::lock_event_outbox(transaction.transaction()).await? {
+            transaction.rollback().await?;
+            return Ok(None);
+        }
+
+        let now = chrono::Utc::now();
+        let events = C::list_due_events(now, BATCH_SIZE, transaction.transaction()).await?;
+        let sequence_ids = events.iter().map(|e| e.sequence_id).collect::<Vec<_>>();
+        C::reschedule_events(
+            &sequence_ids,
+            now + self.claim_timeout,
+            transaction.transaction(),
+        )
+        .await?;
+        C::delete_sent_events(now - self.retention, transaction.transaction()).await?;
+        transaction.commit().await?;
+
+        Ok(Some(events))
+    }
+
+    /// Publish an event to all backends that didn't receive it yet.
+    async fn publish(&self, event: &OutboxEvent) -> std::result::Result<(), PublishFailure> {
+        let mut sent_to = event.sent_to.clone();
+        let cloud_event = match event.payload.to_event() {
+            Ok(cloud_event) => cloud_event,
+            Err(e) => {
+                return Err(PublishFailure {
+                    error: format!("Failed to build event: {e}"),
+                    sent_to,
+                    permanent: true,
+                })
+            }
+        };
+
+        let mut errors = vec![];
+        let mut permanent = true;
+        for sink in &self.sinks {
+            if sent_to.iter().any(|name| name == sink.name()) {
+                continue;
+            }
+            match sink.publish(cloud_event.clone()).await {
+                Ok(()) => sent_to.push(sink.name().to_string()),
+                Err(e) => {
+                    permanent &= e.is::<PermanentError>();
+                    errors.push(format!("{}: {e}", sink.name()));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(PublishFailure {
+                error: errors.join("; "),
+                sent_to,
+                permanent,
+            })
+        }
+    }
+
+    /// Delay after the given number of previously failed attempts.
+    fn backoff(&self, attempts: i32) -> chrono::Duration {
+        let exponent = u32::try_from(attempts).unwrap_or(0).min(31);
+        chrono::Duration::seconds(1i64 << exponent).min(self.max_backoff)
+    }
+}
+
+fn seconds(seconds: u64) -> chrono::Duration {
+    // Capped to stay within the range of `chrono::Duration`
+    chrono::Duration::seconds(i64::try_from(seconds.min(u64::from(u32::MAX))).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::implementations::postgres::{Catalog as PostgresCatalog, CatalogState};
+    use crate::service::event_publisher::{EventEntity, EventMetadata};
+    use async_trait::async_trait;
+    use cloudevents::{AttributesReader, Event};
+    use std::sync::Mutex;
+
+    /// Fails events of the given types and records all others.
+    #[derive(Debug)]
+    struct RecordingBackend {
+        name: &'static str,
+        failing_types: Mutex<HashSet<String>>,
+        rejected_types: Mutex<HashSet<String>>,
+        events: Mutex<Vec<Event>>,
+    }
+
+    impl RecordingBackend {
+        fn new(name: &'static str) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                failing_types: Mutex::default(),
+                rejected_types: Mutex::default(),
+                events: Mutex::default(),
+            })
+        }
+
+        fn published_types(&self) -> Vec<String> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|e| e.ty().to_string())
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl CloudEventBackend for RecordingBackend {
+        async fn publish(&self, event: Event) -> anyhow::Result<()> {
+            if self.rejected_types.lock().unwrap().contains(event.ty()) {
+                return Err(PermanentError("rejected".to_string()).into());
+            }
+            if self.failing_types.lock().unwrap().contains(event.ty()) {
+                return Err(anyhow::anyhow!("unavailable"));
+            }
+            self.events.lock().unwrap().push(event);
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    fn payload(warehouse_id: uuid::Uuid, tabular_id: uuid::Uuid, typ: &str) -> Payload {
+        Payload {
+            id: uuid::Uuid::now_v7(),
+            typ: typ.to_string(),
+            data: serde_json::json!({"a": 1}),
+            metadata: EventMetadata {
+                entity: EventEntity::Table(tabular_id),
+                warehouse_id,
+                name: "my_table".to_string(),
+                namespace: "my_namespace".to_string(),
+                prefix: String::new(),
+                num_events: 1,
+                sequence_number: 0,
+                trace_id: uuid::Uuid::now_v7(),
+            },
+        }
+    }
+
+    async fn due_event_types(
+        state: CatalogState,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Vec<String> {
+        let mut transaction = <PostgresCatalog as Catalog>::Transaction::begin_read(state)
+            .await
+            .unwrap();
+        let due = PostgresCatalog::list_due_events(now, BATCH_SIZE, transaction.transaction())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+        due.into_iter().map(|e| e.payload.typ).collect()
+    }
+
+    #[sqlx::test]
+    async fn test_relay_preserves_order_per_tabular(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let warehouse_id = uuid::Uuid::now_v7();
+        let (table_1, table_2) = (uuid::Uuid::now_v7(), uuid::Uuid::now_v7());
+
+        let mut transaction = <PostgresCatalog as Catalog>::Transaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        PostgresCatalog::enqueue_events(
+            vec![
+                payload(warehouse_id, table_1, "dropTable"),
+                payload(warehouse_id, table_1, "updateTable"),
+                payload(warehouse_id, table_2, "updateTable"),
+            ],
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let backend = RecordingBackend::new("recording-backend");
+        backend
+            .failing_types
+            .lock()
+            .unwrap()
+            .insert("dropTable".to_string());
+        let relay = OutboxRelay::<PostgresCatalog>::new(
+            state.clone(),
+            CloudEventsPublisher::new(),
+            vec![backend.clone() as Arc<dyn CloudEventBackend + Sync + Send>],
+        );
+
+        // The failed event blocks the later event of the same table only
+        let before_relay = chrono::Utc::now();
+        relay.relay_batch().await.unwrap();
+        let published = backend
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.extension("tabular-id").unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(published, vec![table_2.to_string()]);
+
+        // Both events of the table wait for the backoff of the failed event
+        assert!(due_event_types(state.clone(), before_relay)
+            .await
+            .is_empty());
+        assert_eq!(
+            due_event_types(state, chrono::Utc::now() + chrono::Duration::hours(1)).await,
+            vec!["dropTable", "updateTable"]
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_relay_retries_failed_backends_only(pool: sqlx::PgPool) {
+        let state = CatalogState::from_pools(pool.clone(), pool.clone());
+        let warehouse_id = uuid::Uuid::now_v7();
+        let table = uuid::Uuid::now_v7();
+
+        let mut transaction = <PostgresCatalog as Catalog>::Transaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        PostgresCatalog::enqueue_events(
+            vec![
+                payload(warehouse_id, table, "createTable"),
+                payload(warehouse_id, table, "dropTable"),
+                payload(warehouse_id, table, "undropTable"),
+            ],
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let (healthy, flaky) = (
+            RecordingBackend::new("healthy"),
+            RecordingBackend::new("flaky"),
+        );
+        flaky
+            .failing_types
+            .lock()
+            .unwrap()
+            .insert("createTable".to_string());
+        flaky
+            .rejected_types
+            .lock()
+            .unwrap()
+            .insert("dropTable".to_string());
+        let mut relay = OutboxRelay::<PostgresCatalog>::new(
+            state.clone(),
+            CloudEventsPublisher::new(),
+            vec![
+                healthy.clone() as Arc<dyn CloudEventBackend + Sync + Send>,
+                flaky.clone() as Arc<dyn CloudEventBackend + Sync + Send>,
+            ],
+        );
+        relay.max_backoff = chrono::Duration::zero();
+
+        // The failed event blocks the later events of the table
+        relay.relay_batch().await.unwrap();
+        assert_eq!(healthy.published_types(), vec!["createTable"]);
+        assert!(flaky.published_types().is_empty());
+
+        // The retry publishes to the failed backend only. The rejected event is given up
+        // and doesn't block the following event.
+        flaky.failing_types.lock().unwrap().clear();
+        relay.relay_batch().await.unwrap();
+        assert_eq!(
+            healthy.published_types(),
+            vec!["createTable", "dropTable", "undropTable"]
+        );
+        assert_eq!(flaky.published_types(), vec!["createTable", "undropTable"]);
+        assert!(
+            due_event_types(state, chrono::Utc::now() + chrono::Duration::hours(1))
+                .await
+                .is_empty()
+        );
+    }
+}
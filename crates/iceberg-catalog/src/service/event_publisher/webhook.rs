@@ -0,0 +1,248 @@
+use super::{CloudEventBackend, PermanentError};
+use crate::config::WebhookConfig;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use cloudevents::{AttributesReader, Data, Event};
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use ring::hmac;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+/// Header containing the unix timestamp of a signed request.
+pub const TIMESTAMP_HEADER: &str = "x-iceberg-timestamp";
+/// Header containing `sha256=<hex>`, the HMAC-SHA256 of `<timestamp>.<body>`.
+pub const SIGNATURE_HEADER: &str = "x-iceberg-signature";
+
+/// Posts events to an HTTP endpoint using the binary content mode of the
+/// `CloudEvents` HTTP protocol binding.
+///
+/// Every publish is a single attempt, failed events are retried by the outbox relay.
+/// Each endpoint is a separate backend, so that an unavailable endpoint doesn't
+/// cause events to be sent to the others again.
+#[derive(Debug)]
+pub struct WebhookBackend {
+    client: reqwest::Client,
+    name: String,
+    config: WebhookConfig,
+}
+
+impl WebhookBackend {
+    /// # Errors
+    /// Fails if the HTTP client can't be created.
+    pub fn try_new(name: &str, config: WebhookConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().build()?,
+            name: format!("webhook-{name}"),
+            config,
+        })
+    }
+
+    fn accepts(&self, event_type: &str) -> bool {
+        self.config.event_types.is_empty()
+            || self.config.event_types.iter().any(|t| t == event_type)
+    }
+
+    async fn send(&self, request: &HttpRequest) -> anyhow::Result<()> {
+        let mut builder = self
+            .client
+            .post(self.config.url.clone())
+            .timeout(Duration::from_millis(self.config.timeout_ms))
+            .headers(request.headers.clone())
+            .body(request.body.clone());
+
+        if let Some(secret) = &self.config.secret {
+            // Signed per attempt, so that retries carry a fresh timestamp
+            let timestamp = chrono::Utc::now().timestamp();
+            builder = builder
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(SIGNATURE_HEADER, sign(secret, timestamp, &request.body));
+        }
+
+        let status = builder.send().await?.status();
+        if status.is_success() {
+            Ok(())
+        } else if status.is_server_error()
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::REQUEST_TIMEOUT
+        {
+            Err(anyhow!("Endpoint responded with {status}"))
+        } else {
+            Err(PermanentError(format!("Endpoint rejected the event with {status}")).into())
+        }
+    }
+}
+
+#[async_trait]
+impl CloudEventBackend for WebhookBackend {
+    async fn publish(&self, event: Event) -> anyhow::Result<()> {
+        if !self.accepts(event.ty()) {
+            return Ok(());
+        }
+        let request = HttpRequest::try_from_event(&event)?;
+        self.send(&request).await
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// `sha256=<hex>` of the HMAC-SHA256 of `<timestamp>.<body>`.
+fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let mut context = hmac::Context::with_key(&key);
+    context.update(timestamp.to_string().as_bytes());
+    context.update(b".");
+    context.update(body);
+
+    let mut signature = String::from("sha256=");
+    for byte in context.sign().as_ref() {
+        let _ = write!(signature, "{byte:02x}");
+    }
+    signature
+}
+
+/// A `CloudEvent` in binary content mode: attributes and extensions are `ce-` prefixed
+/// headers, the data is the body.
+#[derive(Debug)]
+struct HttpRequest {
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn try_from_event(event: &Event) -> anyhow::Result<Self> {
+        let mut headers = HeaderMap::new();
+        let attributes = event
+            .iter_attributes()
+            .filter(|(name, _)| *name != "datacontenttype")
+            .map(|(name, value)| (name, value.to_string()));
+        let extensions = event
+            .iter_extensions()
+            .map(|(name, value)| (name, value.to_string()));
+        for (name, value) in attributes.chain(extensions) {
+            headers.insert(
+                HeaderName::try_from(format!("ce-{name}"))?,
+                HeaderValue::try_from(encode_header_value(&value))?,
+            );
+        }
+        if let Some(content_type) = event.datacontenttype() {
+            headers.insert(CONTENT_TYPE, HeaderValue::try_from(content_type)?);
+        }
+
+        let body = match event.data() {
+            Some(Data::Binary(data)) => data.clone(),
+            Some(Data::String(data)) => data.clone().into_bytes(),
+            Some(Data::Json(data)) => serde_json::to_vec(data)?,
+            None => vec![],
+        };
+
+        Ok(Self { headers, body })
+    }
+}
+
+/// Percent-encodes space, `"`, `%` and all non-printable or non-ASCII characters,
+/// as required by the HTTP protocol binding.
+fn encode_header_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if matches!(byte, b'!'..=b'~') && byte != b'"' && byte != b'%' {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cloudevents::{EventBuilder, EventBuilderV10};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    fn event(typ: &str) -> Event {
+        EventBuilderV10::new()
+            .id("1")
+            .source("uri:iceberg-catalog-service:test")
+            .ty(typ)
+            .data("application/json", serde_json::json!({"a": 1}))
+            .extension("namespace", "sales.eu west")
+            .build()
+            .unwrap()
+    }
+
+    fn endpoint(url: &str, event_types: &[&str]) -> WebhookConfig {
+        serde_json::from_value(serde_json::json!({
+            "url": url,
+            "secret": "my-secret",
+            "event_types": event_types,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_http_request() {
+        let request = HttpRequest::try_from_event(&event("updateTable")).unwrap();
+        assert_eq!(request.body, br#"{"a":1}"#.to_vec());
+        assert_eq!(request.headers["ce-specversion"], "1.0");
+        assert_eq!(request.headers["ce-type"], "updateTable");
+        assert_eq!(request.headers["ce-namespace"], "sales.eu%20west");
+        assert_eq!(request.headers[CONTENT_TYPE], "application/json");
+        assert!(!request.headers.contains_key("ce-datacontenttype"));
+    }
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            sign("my-secret", 1_700_000_000, br#"{"a":1}"#),
+            "sha256=04c043166494c1b9c629de1b2d0e4a4f415271ed06ac92c90a372c7e21c83f3d"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_filters_and_signs() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let signatures = Arc::new(Mutex::new(vec![]));
+        let router = axum::Router::new().route(
+            "/hook",
+            axum::routing::post({
+                let attempts = attempts.clone();
+                let signatures = signatures.clone();
+                move |headers: HeaderMap| async move {
+                    signatures
+                        .lock()
+                        .unwrap()
+                        .push(headers.get(SIGNATURE_HEADER).cloned());
+                    // Fail the first attempt, reject the third
+                    match attempts.fetch_add(1, Ordering::SeqCst) {
+                        0 => StatusCode::SERVICE_UNAVAILABLE,
+                        2 => StatusCode::BAD_REQUEST,
+                        _ => StatusCode::NO_CONTENT,
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let drops = WebhookBackend::try_new("drops", endpoint(&url, &["dropTable"])).unwrap();
+        assert_eq!(drops.name(), "webhook-drops");
+        drops.publish(event("updateTable")).await.unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 0);
+
+        // A single attempt per publish, retries are left to the caller
+        let all = WebhookBackend::try_new("all", endpoint(&url, &[])).unwrap();
+        let err = all.publish(event("updateTable")).await.unwrap_err();
+        assert!(!err.is::<PermanentError>());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        all.publish(event("updateTable")).await.unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        let err = all.publish(event("updateTable")).await.unwrap_err();
+        assert!(err.is::<PermanentError>());
+        assert!(signatures.lock().unwrap().iter().all(Option::is_some));
+    }
+}
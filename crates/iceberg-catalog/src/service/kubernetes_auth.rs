@@ -0,0 +1,460 @@
+//! Authentication of Kubernetes service account tokens via the `TokenReview` API.
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use http::StatusCode;
+use iceberg_ext::catalog::rest::ErrorModel;
+use serde::Deserialize;
+use url::Url;
+
+use super::token_introspection::{cache_duration, now, token_hash, TokenHash};
+use super::token_verification::{Aud, Claims};
+use crate::config::KubernetesAuthConfig;
+
+/// Prefix of the username of service accounts, followed by `<namespace>:<name>`.
+const SERVICE_ACCOUNT_PREFIX: &str = "system:serviceaccount:";
+/// Issuer of legacy (non-projected) service account tokens.
+const LEGACY_ISSUER: &str = "kubernetes/serviceaccount";
+/// Claim present in projected service account tokens.
+const KUBERNETES_CLAIM: &str = "kubernetes.io";
+
+/// A Kubernetes service account, authenticated by its token.
+#[derive(Debug, Clone)]
+pub struct ServiceAccount {
+    pub namespace: String,
+    pub name: String,
+    pub uid: Option<String>,
+    /// `sub` is `system:serviceaccount:<namespace>:<name>`, `groups` contains the
+    /// groups reported by the API server, i.e. `system:serviceaccounts:<namespace>`.
+    pub claims: Claims,
+}
+
+/// Status of a `TokenReview`, from the
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/authentication-resources/token-review-v1/).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TokenReviewStatus {
+    #[serde(default)]
+    pub authenticated: bool,
+    pub user: Option<TokenReviewUser>,
+    pub audiences: Option<Vec<String>>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TokenReviewUser {
+    pub username: String,
+    pub uid: Option<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+/// Reviews tokens, usually by calling the `TokenReview` API of the cluster.
+#[async_trait::async_trait]
+pub trait TokenReviewer: std::fmt::Debug + Send + Sync + 'static {
+    /// # Errors
+    /// Fails if the review can't be performed, not if the token is invalid.
+    async fn review(
+        &self,
+        token: &str,
+        audiences: &[String],
+    ) -> Result<TokenReviewStatus, ErrorModel>;
+}
+
+/// Calls the `TokenReview` API of the Kubernetes API server.
+#[derive(Debug)]
+pub struct ApiServerTokenReviewer {
+    client: reqwest::Client,
+    url: Url,
+    token_path: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenReview {
+    status: TokenReviewStatus,
+}
+
+impl ApiServerTokenReviewer {
+    /// # Errors
+    /// Fails if the CA certificate can't be read or parsed.
+    pub fn new(config: &KubernetesAuthConfig) -> anyhow::Result<Self> {
+        let mut client = reqwest::Client::builder();
+        if let Some(ca_cert_path) = &config.ca_cert_path {
+            let pem = std::fs::read(ca_cert_path).with_context(|| {
+                format!(
+                    "Failed to read Kubernetes CA certificate from {}",
+                    ca_cert_path.display()
+                )
+            })?;
+            client = client.add_root_certificate(
+                reqwest::Certificate::from_pem(&pem)
+                    .context("Failed to parse Kubernetes CA certificate")?,
+            );
+        }
+
+        Ok(Self {
+            client: client.build()?,
+            url: config
+                .api_url
+                .join("apis/authentication.k8s.io/v1/tokenreviews")?,
+            token_path: config.token_path.clone(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenReviewer for ApiServerTokenReviewer {
+    async fn review(
+        &self,
+        token: &str,
+        audiences: &[String],
+    ) -> Result<TokenReviewStatus, ErrorModel> {
+        // The token of the catalog is rotated by the kubelet, so it is read on every review.
+        let own_token = tokio::fs::read_to_string(&self.token_path)
+            .await
+            .map_err(|e| {
+                ErrorModel::internal(
+                    "Failed to read the Kubernetes service account token of the catalog.",
+                    "KubernetesTokenUnavailable",
+                    Some(Box::new(e)),
+                )
+            })?;
+
+        let mut spec = serde_json::json!({ "token": token });
+        if !audiences.is_empty() {
+            spec["audiences"] = serde_json::json!(audiences);
+        }
+
+        self.client
+            .post(self.url.clone())
+            .bearer_auth(own_token.trim())
+            .json(&serde_json::json!({
+                "apiVersion": "authentication.k8s.io/v1",
+                "kind": "TokenReview",
+                "spec": spec,
+            }))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::BAD_GATEWAY.into())
+                    .message("Failed to review token at the Kubernetes API server.".to_string())
+                    .r#type("KubernetesTokenReviewFailed".to_string())
+                    .source(Some(Box::new(e)))
+                    .build()
+            })?
+            .json::<TokenReview>()
+            .await
+            .map(|review| review.status)
+            .map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::BAD_GATEWAY.into())
+                    .message("Failed to parse the token review response.".to_string())
+                    .r#type("KubernetesTokenReviewFailed".to_string())
+                    .source(Some(Box::new(e)))
+                    .build()
+            })
+    }
+}
+
+/// Authenticates Kubernetes service account tokens. Authenticated service accounts are
+/// cached by the hash of the token until the token expires, but at most for the configured TTL.
+#[derive(Clone)]
+pub struct KubernetesAuthenticator {
+    reviewer: Arc<dyn TokenReviewer>,
+    audiences: Vec<String>,
+    cache_ttl: Duration,
+    cache: moka::future::Cache<TokenHash, Arc<ServiceAccount>>,
+}
+
+/// Expires cached service accounts with their token, but at most after the configured TTL.
+struct ServiceAccountExpiry {
+    ttl: Duration,
+}
+
+impl moka::Expiry<TokenHash, Arc<ServiceAccount>> for ServiceAccountExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &TokenHash,
+        value: &Arc<ServiceAccount>,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(cache_duration(value.claims.exp, now(), self.ttl))
+    }
+}
+
+impl KubernetesAuthenticator {
+    #[must_use]
+    pub fn new(reviewer: Arc<dyn TokenReviewer>, config: &KubernetesAuthConfig) -> Self {
+        let cache_ttl = Duration::from_secs(config.cache_ttl_seconds);
+        Self {
+            reviewer,
+            audiences: config.audiences.clone(),
+            cache_ttl,
+            cache: moka::future::Cache::builder()
+                .max_capacity(config.cache_max_entries)
+                .expire_after(ServiceAccountExpiry { ttl: cache_ttl })
+                .build(),
+        }
+    }
+
+    /// Authenticator using the `TokenReview` API of the configured cluster.
+    ///
+    /// # Errors
+    /// Fails if the CA certificate can't be read or parsed.
+    pub fn from_config(config: &KubernetesAuthConfig) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            Arc::new(ApiServerTokenReviewer::new(config)?),
+            config,
+        ))
+    }
+
+    /// Whether the token looks like a Kubernetes service account token. The token is
+    /// not verified.
+    #[must_use]
+    pub fn is_service_account_token(token: &str) -> bool {
+        unverified_payload(token).is_some_and(|payload| {
+            payload.contains_key(KUBERNETES_CLAIM)
+                || payload.get("iss").and_then(serde_json::Value::as_str) == Some(LEGACY_ISSUER)
+        })
+    }
+
+    /// Review the token and return the service account it belongs to.
+    ///
+    /// # Errors
+    /// - Fails with 401 if the token is invalid or does not belong to a service account.
+    /// - Fails with 502 if the API server can't be reached.
+    pub async fn authenticate(&self, token: &str) -> Result<ServiceAccount, ErrorModel> {
+        let key = token_hash(token);
+        if let Some(service_account) = self.cache.get(&key).await {
+            return Ok(service_account.as_ref().clone());
+        }
+
+        let status = self.reviewer.review(token, &self.audiences).await?;
+        let payload = unverified_payload(token).unwrap_or_default();
+        let service_account = service_account_from_review(status, &payload, now(), self.cache_ttl)?;
+        self.cache
+            .insert(key, Arc::new(service_account.clone()))
+            .await;
+        Ok(service_account)
+    }
+}
+
+impl std::fmt::Debug for KubernetesAuthenticator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KubernetesAuthenticator")
+            .field("reviewer", &self.reviewer)
+            .field("audiences", &self.audiences)
+            .field("entries", &self.cache.entry_count())
+            .finish_non_exhaustive()
+    }
+}
+
+fn service_account_from_review(
+    status: TokenReviewStatus,
+    payload: &serde_json::Map<String, serde_json::Value>,
+    now: u64,
+    default_ttl: Duration,
+) -> Result<ServiceAccount, ErrorModel> {
+    if !status.authenticated {
+        tracing::debug!("Kubernetes rejected token: {:?}", status.error);
+        return Err(unauthorized("Kubernetes service account token is invalid."));
+    }
+    let user = status
+        .user
+        .ok_or_else(|| unauthorized("Token review does not contain a user."))?;
+    let (namespace, name) = user
+        .username
+        .strip_prefix(SERVICE_ACCOUNT_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or_else(|| unauthorized("Token does not belong to a service account."))?;
+
+    let now_usize = usize::try_from(now).unwrap_or(usize::MAX);
+    let exp = payload
+        .get("exp")
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|exp| usize::try_from(exp).ok())
+        // Legacy tokens don't expire, they are trusted for the cache TTL only
+        .unwrap_or_else(|| {
+            now_usize.saturating_add(usize::try_from(default_ttl.as_secs()).unwrap_or(usize::MAX))
+        });
+
+    Ok(ServiceAccount {
+        namespace: namespace.to_string(),
+        name: name.to_string(),
+        uid: user.uid.clone(),
+        claims: Claims {
+            sub: user.username.clone(),
+            iss: payload
+                .get("iss")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or(LEGACY_ISSUER)
+                .to_string(),
+            aud: Aud::Vec(status.audiences.unwrap_or_default()),
+            exp,
+            iat: payload
+                .get("iat")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|iat| usize::try_from(iat).ok())
+                .unwrap_or(now_usize),
+            other: serde_json::json!({
+                "groups": user.groups,
+                "kubernetes_namespace": namespace,
+                "kubernetes_service_account": name,
+            }),
+        },
+    })
+}
+
+fn unverified_payload(token: &str) -> Option<serde_json::Map<String, serde_json::Value>> {
+    token
+        .split('.')
+        .nth(1)
+        .and_then(|payload| URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok())
+        .and_then(|payload| serde_json::from_slice(&payload).ok())
+}
+
+fn unauthorized(message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .message(message)
+        .code(StatusCode::UNAUTHORIZED.into())
+        .r#type("UnauthorizedError")
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Local stand-in for the `TokenReview` API.
+    #[derive(Debug, Default)]
+    struct StaticTokenReviewer {
+        users: HashMap<String, TokenReviewUser>,
+        reviews: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl TokenReviewer for StaticTokenReviewer {
+        async fn review(
+            &self,
+            token: &str,
+            audiences: &[String],
+        ) -> Result<TokenReviewStatus, ErrorModel> {
+            self.reviews.fetch_add(1, Ordering::SeqCst);
+            Ok(match self.users.get(token) {
+                Some(user) => TokenReviewStatus {
+                    authenticated: true,
+                    user: Some(user.clone()),
+                    audiences: Some(audiences.to_vec()),
+                    error: None,
+                },
+                None => TokenReviewStatus {
+                    error: Some("invalid token".to_string()),
+                    ..TokenReviewStatus::default()
+                },
+            })
+        }
+    }
+
+    fn config() -> KubernetesAuthConfig {
+        serde_json::from_value(serde_json::json!({
+            "api_url": "https://kubernetes.default.svc",
+            "audiences": ["iceberg-catalog"],
+        }))
+        .unwrap()
+    }
+
+    fn token(payload: &serde_json::Value) -> String {
+        format!(
+            "eyJhbGciOiJSUzI1NiJ9.{}.signature",
+            URL_SAFE_NO_PAD.encode(payload.to_string())
+        )
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_service_account() {
+        let exp = now() + 600;
+        let token = token(&serde_json::json!({
+            "iss": "https://kubernetes.default.svc.cluster.local",
+            "sub": "system:serviceaccount:spark:driver",
+            "exp": exp,
+            "kubernetes.io": {"namespace": "spark"},
+        }));
+        let reviewer = Arc::new(StaticTokenReviewer {
+            users: HashMap::from([(
+                token.clone(),
+                TokenReviewUser {
+                    username: "system:serviceaccount:spark:driver".to_string(),
+                    uid: Some("1234".to_string()),
+                    groups: vec!["system:serviceaccounts:spark".to_string()],
+                },
+            )]),
+            ..StaticTokenReviewer::default()
+        });
+        let authenticator = KubernetesAuthenticator::new(reviewer.clone(), &config());
+
+        assert!(KubernetesAuthenticator::is_service_account_token(&token));
+        let service_account = authenticator.authenticate(&token).await.unwrap();
+        assert_eq!(service_account.namespace, "spark");
+        assert_eq!(service_account.name, "driver");
+        assert_eq!(
+            service_account.claims.sub,
+            "system:serviceaccount:spark:driver"
+        );
+        assert_eq!(
+            service_account.claims.other["groups"],
+            serde_json::json!(["system:serviceaccounts:spark"])
+        );
+        assert_eq!(service_account.claims.exp, usize::try_from(exp).unwrap());
+
+        // The second request is served from the cache
+        authenticator.authenticate(&token).await.unwrap();
+        assert_eq!(reviewer.reviews.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reject_invalid_token() {
+        let authenticator =
+            KubernetesAuthenticator::new(Arc::new(StaticTokenReviewer::default()), &config());
+        let token = token(&serde_json::json!({"iss": LEGACY_ISSUER}));
+        let err = authenticator.authenticate(&token).await.unwrap_err();
+        assert_eq!(err.code, 401);
+    }
+
+    #[test]
+    fn test_reject_non_service_account_user() {
+        let status = TokenReviewStatus {
+            authenticated: true,
+            user: Some(TokenReviewUser {
+                username: "alice".to_string(),
+                ..TokenReviewUser::default()
+            }),
+            ..TokenReviewStatus::default()
+        };
+        let err = service_account_from_review(
+            status,
+            &serde_json::Map::new(),
+            1000,
+            Duration::from_secs(60),
+        )
+        .unwrap_err();
+        assert_eq!(err.code, 401);
+    }
+
+    #[test]
+    fn test_is_service_account_token() {
+        assert!(KubernetesAuthenticator::is_service_account_token(&token(
+            &serde_json::json!({"iss": LEGACY_ISSUER})
+        )));
+        assert!(!KubernetesAuthenticator::is_service_account_token(&token(
+            &serde_json::json!({"iss": "https://idp.example.com"})
+        )));
+        assert!(!KubernetesAuthenticator::is_service_account_token("opaque"));
+    }
+}
@@ -1,21 +1,25 @@
+pub mod api_key;
 pub mod auth;
 mod catalog;
 pub mod config;
 pub mod contract_verification;
 pub mod event_publisher;
 pub mod health;
+pub mod kubernetes_auth;
 pub mod secrets;
 pub mod storage;
 pub mod tabular_idents;
+pub mod token_introspection;
+pub mod token_issuer;
 pub mod token_verification;
 
 pub use catalog::{
     Catalog, CommitTableResponse, CreateNamespaceRequest, CreateNamespaceResponse,
-    CreateTableRequest, CreateTableResponse, GetNamespaceResponse, GetStorageConfigResponse,
-    GetTableMetadataResponse, GetWarehouseResponse, ListFlags, ListNamespacesQuery,
-    ListNamespacesResponse, LoadTableResponse, NamespaceIdent, Result, TableCommit, TableIdent,
-    Transaction, UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
-    ViewMetadataWithLocation,
+    CreateTableRequest, CreateTableResponse, GetNamespaceResponse, GetProjectResponse,
+    GetStorageConfigResponse, GetTableMetadataResponse, GetWarehouseResponse, ListFlags,
+    ListNamespacesQuery, ListNamespacesResponse, LoadTableResponse, NamespaceIdent, Result,
+    TableCommit, TableIdent, Transaction, UpdateNamespacePropertiesRequest,
+    UpdateNamespacePropertiesResponse, ViewMetadataWithLocation,
 };
 use std::ops::Deref;
 
@@ -27,6 +31,7 @@ use std::str::FromStr;
 
 use crate::service::contract_verification::ContractVerifiers;
 use crate::service::event_publisher::CloudEventsPublisher;
+use crate::service::token_verification::Verifier;
 pub use secrets::{SecretIdent, SecretStore};
 
 use self::auth::AuthZHandler;
@@ -68,6 +73,8 @@ pub struct State<A: AuthZHandler, C: Catalog, S: SecretStore> {
     pub secrets: S,
     pub publisher: CloudEventsPublisher,
     pub contract_verifiers: ContractVerifiers,
+    /// Verifier of the OpenID provider, `None` if authentication is disabled.
+    pub token_verifier: Option<Verifier>,
 }
 
 impl<A: AuthZHandler, C: Catalog, S: SecretStore> ServiceState for State<A, C, S> {}
@@ -204,6 +211,35 @@ impl FromStr for ProjectIdent {
     }
 }
 
+/// Status of a project
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    strum_macros::Display,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(
+    feature = "sqlx",
+    sqlx(type_name = "project_status", rename_all = "kebab-case")
+)]
+pub enum ProjectStatus {
+    /// The project is active, warehouses can be created in it.
+    Active,
+    /// The project is inactive, no warehouses can be created in it.
+    Inactive,
+}
+
 /// Status of a warehouse
 #[derive(
     Debug,
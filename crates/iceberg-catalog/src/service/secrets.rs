@@ -3,6 +3,9 @@ use crate::service::health::{Health, HealthExt};
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Interface for Handling Secrets.
 #[async_trait]
@@ -23,7 +26,25 @@ where
         secret: S,
     ) -> Result<SecretIdent>;
 
-    /// Delete a secret
+    /// Get a specific version of a secret.
+    async fn get_secret_version<S: SecretInStorage + DeserializeOwned>(
+        &self,
+        secret_id: &SecretIdent,
+        version: u64,
+    ) -> Result<Secret<S>>;
+
+    /// Replace the value of an existing secret. The previous value is kept
+    /// as an older version. Returns the new version.
+    async fn update_secret<S: SecretInStorage + Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret_id: &SecretIdent,
+        secret: S,
+    ) -> Result<u64>;
+
+    /// List all versions of a secret, newest first.
+    async fn list_secret_versions(&self, secret_id: &SecretIdent) -> Result<Vec<SecretVersion>>;
+
+    /// Delete a secret including all its versions
     async fn delete_secret(&self, secret_id: &SecretIdent) -> Result<()>;
 }
 
@@ -31,6 +52,7 @@ where
 pub enum Secrets {
     Postgres(crate::implementations::postgres::SecretsState),
     KV2(crate::implementations::kv2::SecretsState),
+    File(crate::implementations::file::SecretsState),
 }
 
 #[async_trait]
@@ -42,6 +64,7 @@ impl SecretStore for Secrets {
         match self {
             Self::Postgres(state) => state.get_secret_by_id(secret_id).await,
             Self::KV2(state) => state.get_secret_by_id(secret_id).await,
+            Self::File(state) => state.get_secret_by_id(secret_id).await,
         }
     }
 
@@ -52,6 +75,42 @@ impl SecretStore for Secrets {
         match self {
             Self::Postgres(state) => state.create_secret(secret).await,
             Self::KV2(state) => state.create_secret(secret).await,
+            Self::File(state) => state.create_secret(secret).await,
+        }
+    }
+
+    async fn get_secret_version<S: SecretInStorage + DeserializeOwned>(
+        &self,
+        secret_id: &SecretIdent,
+        version: u64,
+    ) -> crate::api::Result<Secret<S>> {
+        match self {
+            Self::Postgres(state) => state.get_secret_version(secret_id, version).await,
+            Self::KV2(state) => state.get_secret_version(secret_id, version).await,
+            Self::File(state) => state.get_secret_version(secret_id, version).await,
+        }
+    }
+
+    async fn update_secret<S: SecretInStorage + Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret_id: &SecretIdent,
+        secret: S,
+    ) -> crate::api::Result<u64> {
+        match self {
+            Self::Postgres(state) => state.update_secret(secret_id, secret).await,
+            Self::KV2(state) => state.update_secret(secret_id, secret).await,
+            Self::File(state) => state.update_secret(secret_id, secret).await,
+        }
+    }
+
+    async fn list_secret_versions(
+        &self,
+        secret_id: &SecretIdent,
+    ) -> crate::api::Result<Vec<SecretVersion>> {
+        match self {
+            Self::Postgres(state) => state.list_secret_versions(secret_id).await,
+            Self::KV2(state) => state.list_secret_versions(secret_id).await,
+            Self::File(state) => state.list_secret_versions(secret_id).await,
         }
     }
 
@@ -59,6 +118,7 @@ impl SecretStore for Secrets {
         match self {
             Self::Postgres(state) => state.delete_secret(secret_id).await,
             Self::KV2(state) => state.delete_secret(secret_id).await,
+            Self::File(state) => state.delete_secret(secret_id).await,
         }
     }
 }
@@ -69,6 +129,7 @@ impl HealthExt for Secrets {
         match self {
             Self::Postgres(state) => state.health().await,
             Self::KV2(state) => state.health().await,
+            Self::File(state) => state.health().await,
         }
     }
 
@@ -76,6 +137,7 @@ impl HealthExt for Secrets {
         match self {
             Self::Postgres(state) => state.update_health().await,
             Self::KV2(state) => state.update_health().await,
+            Self::File(state) => state.update_health().await,
         }
     }
 }
@@ -92,6 +154,129 @@ impl From<crate::implementations::kv2::SecretsState> for Secrets {
     }
 }
 
+impl From<crate::implementations::file::SecretsState> for Secrets {
+    fn from(state: crate::implementations::file::SecretsState) -> Self {
+        Self::File(state)
+    }
+}
+
+/// Caches secrets of another [`SecretStore`] in memory.
+///
+/// Entries are invalidated when the secret is updated or deleted through this store.
+/// Changes made by other catalog instances become visible once the entry expires.
+#[derive(Clone)]
+pub struct CachedSecretStore<S: SecretStore> {
+    inner: S,
+    /// `None` if caching is disabled.
+    cache: Option<moka::future::Cache<SecretIdent, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl<S: SecretStore> CachedSecretStore<S> {
+    /// Caching is disabled if `time_to_live` or `max_entries` is zero.
+    #[must_use]
+    pub fn new(inner: S, time_to_live: Duration, max_entries: u64) -> Self {
+        let cache = (!time_to_live.is_zero() && max_entries > 0).then(|| {
+            moka::future::Cache::builder()
+                .time_to_live(time_to_live)
+                .max_capacity(max_entries)
+                .build()
+        });
+        Self { inner, cache }
+    }
+
+    #[must_use]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S: SecretStore> std::fmt::Debug for CachedSecretStore<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedSecretStore")
+            .field(
+                "entries",
+                &self.cache.as_ref().map(moka::future::Cache::entry_count),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<T: SecretStore> SecretStore for CachedSecretStore<T> {
+    async fn get_secret_by_id<S: SecretInStorage + DeserializeOwned>(
+        &self,
+        secret_id: &SecretIdent,
+    ) -> Result<Secret<S>> {
+        let Some(cache) = &self.cache else {
+            return self.inner.get_secret_by_id::<S>(secret_id).await;
+        };
+
+        if let Some(secret) = cache
+            .get(secret_id)
+            .await
+            .and_then(|cached| cached.downcast_ref::<Secret<S>>().cloned())
+        {
+            metrics::counter!("iceberg_catalog_secret_cache_hits_total").increment(1);
+            return Ok(secret);
+        }
+
+        metrics::counter!("iceberg_catalog_secret_cache_misses_total").increment(1);
+        let secret = self.inner.get_secret_by_id::<S>(secret_id).await?;
+        cache.insert(*secret_id, Arc::new(secret.clone())).await;
+        Ok(secret)
+    }
+
+    async fn create_secret<S: SecretInStorage + Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret: S,
+    ) -> Result<SecretIdent> {
+        self.inner.create_secret(secret).await
+    }
+
+    async fn get_secret_version<S: SecretInStorage + DeserializeOwned>(
+        &self,
+        secret_id: &SecretIdent,
+        version: u64,
+    ) -> Result<Secret<S>> {
+        self.inner.get_secret_version(secret_id, version).await
+    }
+
+    async fn update_secret<S: SecretInStorage + Send + Sync + Serialize + std::fmt::Debug>(
+        &self,
+        secret_id: &SecretIdent,
+        secret: S,
+    ) -> Result<u64> {
+        let version = self.inner.update_secret(secret_id, secret).await?;
+        if let Some(cache) = &self.cache {
+            cache.invalidate(secret_id).await;
+        }
+        Ok(version)
+    }
+
+    async fn list_secret_versions(&self, secret_id: &SecretIdent) -> Result<Vec<SecretVersion>> {
+        self.inner.list_secret_versions(secret_id).await
+    }
+
+    async fn delete_secret(&self, secret_id: &SecretIdent) -> Result<()> {
+        self.inner.delete_secret(secret_id).await?;
+        if let Some(cache) = &self.cache {
+            cache.invalidate(secret_id).await;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S: SecretStore> HealthExt for CachedSecretStore<S> {
+    async fn health(&self) -> Vec<Health> {
+        self.inner.health().await
+    }
+
+    async fn update_health(&self) {
+        self.inner.update_health().await;
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
 #[cfg_attr(feature = "sqlx", sqlx(transparent))]
@@ -134,9 +319,141 @@ impl std::fmt::Display for SecretIdent {
 pub struct Secret<T> {
     pub secret_id: SecretIdent,
     pub secret: T,
+    /// Version of the secret value, starting at 1.
+    pub version: u64,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Metadata of a single version of a secret. Never contains the secret itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SecretVersion {
+    /// Version number, starting at 1.
+    pub version: u64,
+    /// Time the version was created.
+    #[schema(value_type = String)]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Whether this is the version currently in use.
+    pub current: bool,
+}
+
 // Prohibits us to store unwanted types in the storage.
-pub trait SecretInStorage {}
+// Secrets must be cheap to clone and thread-safe, so that they can be cached.
+pub trait SecretInStorage: Clone + Send + Sync + 'static {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::implementations::file;
+    use crate::service::storage::{S3Credential, StorageCredential};
+
+    async fn cached_store() -> CachedSecretStore<file::SecretsState> {
+        store_with_ttl(Duration::from_secs(3600)).await
+    }
+
+    async fn store_with_ttl(time_to_live: Duration) -> CachedSecretStore<file::SecretsState> {
+        let directory = std::env::temp_dir().join(format!("secrets-{}", uuid::Uuid::now_v7()));
+        tokio::fs::create_dir_all(&directory).await.unwrap();
+        let inner = file::SecretsState::from_config(&crate::config::FileSecretsConfig {
+            directory,
+            allow_writes: true,
+            reload_interval_seconds: 3600,
+        })
+        .await
+        .unwrap();
+        CachedSecretStore::new(inner, time_to_live, 10)
+    }
+
+    fn credential(key: &str) -> StorageCredential {
+        S3Credential::AccessKey {
+            aws_access_key_id: key.to_string(),
+            aws_secret_access_key: "my secret key".to_string(),
+        }
+        .into()
+    }
+
+    #[tokio::test]
+    async fn test_cache_is_invalidated_on_update_and_delete() {
+        let store = cached_store().await;
+        let secret_id = store
+            .create_secret(credential("first access key"))
+            .await
+            .unwrap();
+
+        let read = store
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read.secret, credential("first access key"));
+        assert!(store.cache.as_ref().unwrap().contains_key(&secret_id));
+
+        store
+            .update_secret(&secret_id, credential("second access key"))
+            .await
+            .unwrap();
+        assert!(!store.cache.as_ref().unwrap().contains_key(&secret_id));
+        let read = store
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read.secret, credential("second access key"));
+        assert_eq!(read.version, 2);
+
+        store.delete_secret(&secret_id).await.unwrap();
+        assert!(store
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_secret_is_served_from_cache() {
+        let store = cached_store().await;
+        let secret_id = store
+            .create_secret(credential("my access key"))
+            .await
+            .unwrap();
+        store
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+
+        // Bypass the cache, the cached value is still served
+        store
+            .inner()
+            .update_secret(&secret_id, credential("other access key"))
+            .await
+            .unwrap();
+        let read = store
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read.secret, credential("my access key"));
+    }
+
+    #[tokio::test]
+    async fn test_zero_ttl_disables_cache() {
+        let store = store_with_ttl(Duration::ZERO).await;
+        assert!(store.cache.is_none());
+
+        let secret_id = store
+            .create_secret(credential("my access key"))
+            .await
+            .unwrap();
+        store
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        store
+            .inner()
+            .update_secret(&secret_id, credential("other access key"))
+            .await
+            .unwrap();
+        let read = store
+            .get_secret_by_id::<StorageCredential>(&secret_id)
+            .await
+            .unwrap();
+        assert_eq!(read.secret, credential("other access key"));
+    }
+}
@@ -3,6 +3,7 @@
 mod az;
 mod error;
 mod s3;
+mod validation;
 
 use super::{secrets::SecretInStorage, NamespaceIdentUuid, TableIdentUuid};
 use crate::api::{iceberg::v1::DataAccess, CatalogConfig};
@@ -16,8 +17,11 @@ use error::{
 use iceberg_ext::configs::table::TableProperties;
 use iceberg_ext::configs::Location;
 pub use s3::S3Location;
-pub use s3::{S3Credential, S3Flavor, S3Profile};
+pub use s3::{S3Credential, S3Flavor, S3Profile, S3ServerSideEncryption};
 use serde::{Deserialize, Serialize};
+pub use validation::{
+    StorageValidationCheck, StorageValidationReport, StorageValidationStatus, StorageValidationStep,
+};
 
 /// Storage profile for a warehouse.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, derive_more::From, utoipa::ToSchema)]
@@ -234,17 +238,83 @@ impl StorageProfile {
         credential: Option<&StorageCredential>,
         location: Option<&Location>,
     ) -> Result<(), ValidationError> {
-        let file_io = self.file_io(credential)?;
+        self.check_access(
+            credential,
+            location,
+            &mut StorageValidationReport::default(),
+        )
+        .await
+    }
 
+    /// Normalize the profile and validate physical access without persisting anything.
+    /// In contrast to [`StorageProfile::validate_access`], all steps are reported
+    /// individually instead of failing on the first error.
+    ///
+    /// Returns the normalized profile together with the report.
+    pub async fn dry_run_validation(
+        mut self,
+        credential: Option<&StorageCredential>,
+    ) -> (Self, StorageValidationReport) {
+        let mut report = StorageValidationReport::default();
+
+        if report
+            .record(StorageValidationStep::Normalize, self.normalize())
+            .is_ok()
+        {
+            // Errors are recorded in the report
+            let _ = self.check_access(credential, None, &mut report).await;
+        }
+
+        (self, report.finish())
+    }
+
+    async fn check_access(
+        &self,
+        credential: Option<&StorageCredential>,
+        location: Option<&Location>,
+        report: &mut StorageValidationReport,
+    ) -> Result<(), ValidationError> {
         let ns_id = NamespaceIdentUuid::default();
         let table_id = TableIdentUuid::default();
-        let ns_location = self.default_namespace_location(ns_id)?;
-        let test_location = location.map_or_else(
-            || self.default_tabular_location(&ns_location, table_id.into()),
-            std::borrow::ToOwned::to_owned,
-        );
+        let (file_io, test_location) = report.record(
+            StorageValidationStep::FileIo,
+            self.file_io(credential)
+                .map_err(ValidationError::from)
+                .and_then(|file_io| {
+                    let test_location = match location {
+                        Some(location) => location.clone(),
+                        None => self.default_tabular_location(
+                            &self.default_namespace_location(ns_id)?,
+                            table_id.into(),
+                        ),
+                    };
+                    Ok((file_io, test_location))
+                }),
+        )?;
+
+        let result = self
+            .validate_io(credential, &file_io, &test_location, report)
+            .await;
+
+        // Cleanup, also if a previous step failed after test files may have been written
+        let cleanup = crate::catalog::io::remove_all(&file_io, &test_location)
+            .await
+            .map_err(|e| ValidationError::IoOperationFailed(e, Box::new(self.clone())));
+        let cleanup = report.record(StorageValidationStep::Cleanup, cleanup);
+
+        result.and(cleanup)
+    }
+
+    /// Validate direct and vended-credentials access to `test_location`.
+    async fn validate_io(
+        &self,
+        credential: Option<&StorageCredential>,
+        file_io: &iceberg::io::FileIO,
+        test_location: &Location,
+        report: &mut StorageValidationReport,
+    ) -> Result<(), ValidationError> {
         // Validate direct read/write access
-        self.validate_read_write(&file_io, &test_location, false)
+        self.validate_read_write(file_io, test_location, false, report)
             .await?;
 
         // Test vended-credentials access
@@ -256,36 +326,54 @@ impl StorageProfile {
         };
 
         if test_vended_credentials {
-            let tbl_config = self
-                .generate_table_config(
-                    &DataAccess {
-                        remote_signing: false,
-                        vended_credentials: true,
-                    },
-                    credential,
-                    &test_location,
-                    StoragePermissions::ReadWriteDelete,
+            let result = self
+                .validate_vended_credentials(credential, test_location)
+                .await;
+            report.record(StorageValidationStep::VendedCredentials, result)?;
+        } else {
+            report.skip(
+                StorageValidationStep::VendedCredentials,
+                "Vended-credentials are not enabled for this storage profile.",
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn validate_vended_credentials(
+        &self,
+        credential: Option<&StorageCredential>,
+        test_location: &Location,
+    ) -> Result<(), ValidationError> {
+        let tbl_config = self
+            .generate_table_config(
+                &DataAccess {
+                    remote_signing: false,
+                    vended_credentials: true,
+                },
+                credential,
+                test_location,
+                StoragePermissions::ReadWriteDelete,
+            )
+            .await?;
+        match &self {
+            StorageProfile::S3(_) => {
+                let sts_file_io = s3::get_file_io_from_table_config(&tbl_config)?;
+                // Individual steps of the vended-credentials check are reported as a whole
+                self.validate_read_write(
+                    &sts_file_io,
+                    test_location,
+                    true,
+                    &mut StorageValidationReport::default(),
                 )
                 .await?;
-            match &self {
-                StorageProfile::S3(_) => {
-                    let sts_file_io = s3::get_file_io_from_table_config(&tbl_config)?;
-                    self.validate_read_write(&sts_file_io, &test_location, true)
-                        .await?;
-                }
-                StorageProfile::Azdls(_) => {
-                    az::validate_vended_credentials(&tbl_config, &test_location, self).await?;
-                }
-                #[cfg(test)]
-                StorageProfile::Test(_) => {}
             }
+            StorageProfile::Azdls(_) => {
+                az::validate_vended_credentials(&tbl_config, test_location, self).await?;
+            }
+            #[cfg(test)]
+            StorageProfile::Test(_) => {}
         }
-
-        // Cleanup
-        crate::catalog::io::remove_all(&file_io, &test_location)
-            .await
-            .map_err(|e| ValidationError::IoOperationFailed(e, Box::new(self.clone())))?;
-
         Ok(())
     }
 
@@ -294,6 +382,7 @@ impl StorageProfile {
         file_io: &iceberg::io::FileIO,
         test_location: &Location,
         is_vended_credentials: bool,
+        report: &mut StorageValidationReport,
     ) -> Result<(), ValidationError> {
         let compression_codec = CompressionCodec::Gzip;
 
@@ -311,24 +400,27 @@ impl StorageProfile {
         }
 
         // Test write
-        crate::catalog::io::write_metadata_file(
+        let result = crate::catalog::io::write_metadata_file(
             &test_file_write,
             "test",
             compression_codec,
             file_io,
         )
         .await
-        .map_err(|e| ValidationError::IoOperationFailed(e, Box::new(self.clone())))?;
+        .map_err(|e| ValidationError::IoOperationFailed(e, Box::new(self.clone())));
+        report.record(StorageValidationStep::Write, result)?;
 
         // Test read
-        let _ = crate::catalog::io::read_file(file_io, &test_file_write)
+        let result = crate::catalog::io::read_file(file_io, &test_file_write)
             .await
-            .map_err(|e| ValidationError::IoOperationFailed(e, Box::new(self.clone())))?;
+            .map_err(|e| ValidationError::IoOperationFailed(e, Box::new(self.clone())));
+        report.record(StorageValidationStep::Read, result)?;
 
         // Test delete
-        crate::catalog::io::delete_file(file_io, &test_file_write)
+        let result = crate::catalog::io::delete_file(file_io, &test_file_write)
             .await
-            .map_err(|e| ValidationError::IoOperationFailed(e, Box::new(self.clone())))?;
+            .map_err(|e| ValidationError::IoOperationFailed(e, Box::new(self.clone())));
+        report.record(StorageValidationStep::Delete, result)?;
 
         tracing::debug!(
             "Successfully wrote, read and deleted file at: {}",
@@ -522,6 +614,7 @@ mod tests {
             sts_role_arn: None,
             sts_enabled: false,
             flavor: S3Flavor::Aws,
+            server_side_encryption: None,
         });
 
         let target_location = "s3://my-bucket/subfolder/00000000-0000-0000-0000-000000000001/00000000-0000-0000-0000-000000000002";
@@ -582,6 +675,7 @@ mod tests {
                 sts_role_arn: None,
                 sts_enabled: false,
                 flavor: S3Flavor::Aws,
+                server_side_encryption: None,
             })
         );
     }
@@ -617,6 +711,7 @@ mod tests {
             sts_role_arn: None,
             sts_enabled: false,
             flavor: S3Flavor::Aws,
+            server_side_encryption: None,
         });
 
         let cases = vec![
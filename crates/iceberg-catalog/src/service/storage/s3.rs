@@ -49,6 +49,86 @@ pub struct S3Profile {
     /// Defaults to AWS
     #[serde(default)]
     pub flavor: S3Flavor,
+    /// Server side encryption for objects written to the bucket.
+    /// If not set, the default encryption of the bucket applies.
+    #[serde(default)]
+    pub server_side_encryption: Option<S3ServerSideEncryption>,
+}
+
+/// Server side encryption of S3 objects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+#[schema(rename_all = "kebab-case")]
+pub enum S3ServerSideEncryption {
+    /// SSE-S3: Encryption with Amazon S3 managed keys.
+    SseS3,
+    /// SSE-KMS: Encryption with AWS KMS keys.
+    #[serde(rename_all = "kebab-case")]
+    SseKms {
+        /// ARN or ID of the KMS key.
+        /// If not set, the AWS managed key `aws/s3` is used.
+        #[serde(default)]
+        kms_key_id: Option<String>,
+    },
+    /// DSSE-KMS: Dual-layer encryption with AWS KMS keys.
+    /// Not supported for metadata files written by the catalog yet.
+    #[serde(rename_all = "kebab-case")]
+    DsseKms {
+        /// ARN or ID of the KMS key.
+        /// If not set, the AWS managed key `aws/s3` is used.
+        #[serde(default)]
+        kms_key_id: Option<String>,
+    },
+}
+
+impl S3ServerSideEncryption {
+    /// Value of the `x-amz-server-side-encryption` header.
+    #[must_use]
+    pub fn header_value(&self) -> &'static str {
+        match self {
+            S3ServerSideEncryption::SseS3 => "AES256",
+            S3ServerSideEncryption::SseKms { .. } => "aws:kms",
+            S3ServerSideEncryption::DsseKms { .. } => "aws:kms:dsse",
+        }
+    }
+
+    /// Value of the `s3.sse.type` property understood by iceberg clients.
+    #[must_use]
+    pub fn iceberg_sse_type(&self) -> &'static str {
+        match self {
+            S3ServerSideEncryption::SseS3 => "s3",
+            S3ServerSideEncryption::SseKms { .. } => "kms",
+            S3ServerSideEncryption::DsseKms { .. } => "dsse-kms",
+        }
+    }
+
+    #[must_use]
+    pub fn kms_key_id(&self) -> Option<&str> {
+        match self {
+            S3ServerSideEncryption::SseS3 => None,
+            S3ServerSideEncryption::SseKms { kms_key_id }
+            | S3ServerSideEncryption::DsseKms { kms_key_id } => kms_key_id.as_deref(),
+        }
+    }
+
+    fn is_kms(&self) -> bool {
+        matches!(
+            self,
+            S3ServerSideEncryption::SseKms { .. } | S3ServerSideEncryption::DsseKms { .. }
+        )
+    }
+
+    fn normalize(&mut self) {
+        match self {
+            S3ServerSideEncryption::SseS3 => {}
+            S3ServerSideEncryption::SseKms { kms_key_id }
+            | S3ServerSideEncryption::DsseKms { kms_key_id } => {
+                if kms_key_id.as_ref().is_some_and(|k| k.trim().is_empty()) {
+                    *kms_key_id = None;
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -111,6 +191,18 @@ impl S3Profile {
                 "S3 Assume role ARN".to_string(),
             ));
         }
+        if let Some(sse) = &self.server_side_encryption {
+            // The FileIO of iceberg-rust only knows the `none`, `s3`, `kms` and `custom` types
+            if matches!(sse, S3ServerSideEncryption::DsseKms { .. }) {
+                return Err(FileIoError::UnsupportedAction(
+                    "S3 DSSE-KMS encryption".to_string(),
+                ));
+            }
+            builder = builder.with_prop(s3::SseType::KEY, sse.iceberg_sse_type());
+            if let Some(kms_key_id) = sse.kms_key_id() {
+                builder = builder.with_prop(s3::SseKey::KEY, kms_key_id);
+            }
+        }
         if let Some(credential) = credential {
             if let Some(session_token) = &credential.session_token() {
                 builder = builder.with_prop(iceberg::io::S3_SESSION_TOKEN, session_token);
@@ -141,6 +233,9 @@ impl S3Profile {
         self.normalize_endpoint()?;
         self.normalize_assume_role_arn();
         self.normalize_sts_role_arn();
+        if let Some(sse) = self.server_side_encryption.as_mut() {
+            sse.normalize();
+        }
 
         if self.sts_enabled && matches!(self.flavor, S3Flavor::Aws) && self.sts_role_arn.is_none() {
             return Err(ValidationError::InvalidProfile {
@@ -199,6 +294,7 @@ impl S3Profile {
             sts_role_arn: _,
             sts_enabled: _,
             flavor: _,
+            server_side_encryption: _,
         } = self;
 
         // assume_role_arn is not supported currently
@@ -293,6 +389,13 @@ impl S3Profile {
             config.insert(&s3::Endpoint(endpoint.clone()));
         }
 
+        if let Some(sse) = &self.server_side_encryption {
+            config.insert(&s3::SseType(sse.iceberg_sse_type().to_string()));
+            if let Some(kms_key_id) = sse.kms_key_id() {
+                config.insert(&s3::SseKey(kms_key_id.to_string()));
+            }
+        }
+
         if *vended_credentials {
             if self.sts_enabled {
                 let aws_sdk_sts::types::Credentials {
@@ -372,6 +475,7 @@ impl S3Profile {
             .policy(Self::get_aws_policy_string(
                 table_location,
                 storage_permissions,
+                self.server_side_encryption.as_ref(),
             )?);
         let assume_role_builder = if let Some(arn) = arn {
             assume_role_builder.role_arn(arn)
@@ -415,9 +519,20 @@ impl S3Profile {
         }
     }
 
+    fn permission_to_kms_actions(storage_permissions: StoragePermissions) -> &'static str {
+        match storage_permissions {
+            StoragePermissions::Read => "\"kms:Decrypt\"",
+            // Multipart uploads need to decrypt previously uploaded parts
+            StoragePermissions::ReadWrite | StoragePermissions::ReadWriteDelete => {
+                "\"kms:Decrypt\", \"kms:GenerateDataKey\""
+            }
+        }
+    }
+
     fn get_aws_policy_string(
         table_location: &Location,
         storage_permissions: StoragePermissions,
+        server_side_encryption: Option<&S3ServerSideEncryption>,
     ) -> Result<String, TableConfigError> {
         let table_location = S3Location::try_from(table_location.clone()).map_err(|e| {
             TableConfigError::Misconfiguration(
@@ -431,6 +546,30 @@ impl S3Profile {
         let key = table_location.key().join("/");
         let key = format!("{key}/");
 
+        // Objects encrypted with KMS keys can only be accessed with permissions on the key.
+        // Key IDs or aliases cannot be used as resource, so we fall back to all keys.
+        let kms_statement = match server_side_encryption {
+            Some(sse) if sse.is_kms() => {
+                let kms_resource = sse
+                    .kms_key_id()
+                    .filter(|k| k.starts_with("arn:"))
+                    .unwrap_or("*");
+                format!(
+                    r#",
+            {{
+                "Sid": "KmsAccess",
+                "Effect": "Allow",
+                "Action": [
+                    {}
+                ],
+                "Resource": "{kms_resource}"
+            }}"#,
+                    Self::permission_to_kms_actions(storage_permissions)
+                )
+            }
+            _ => String::new(),
+        };
+
         Ok(format!(
             r#"{{
         "Version": "2012-10-17",
@@ -456,7 +595,7 @@ impl S3Profile {
                         "s3:prefix": "{key}*"
                     }}
                 }}
-            }}
+            }}{kms_statement}
         ]
     }}"#,
             Self::permission_to_actions(storage_permissions),
@@ -546,6 +685,8 @@ pub(super) fn get_file_io_from_table_config(
         s3::AccessKeyId::KEY,
         s3::SecretAccessKey::KEY,
         s3::SessionToken::KEY,
+        s3::SseType::KEY,
+        s3::SseKey::KEY,
     ] {
         if let Some(value) = config.get_custom_prop(key) {
             builder = builder.with_prop(key, value);
@@ -803,6 +944,7 @@ mod test {
             sts_role_arn: None,
             sts_enabled: false,
             flavor: S3Flavor::Aws,
+            server_side_encryption: None,
         };
         let sp: StorageProfile = profile.clone().into();
 
@@ -842,6 +984,7 @@ mod test {
             sts_role_arn: None,
             sts_enabled: false,
             flavor: S3Flavor::Aws,
+            server_side_encryption: None,
         };
 
         let namespace_location = Location::from_str("s3://test-bucket/foo/").unwrap();
@@ -889,6 +1032,7 @@ mod test {
                 sts_role_arn: None,
                 flavor: S3Flavor::Minio,
                 sts_enabled: true,
+                server_side_encryption: None,
             };
             let mut profile: StorageProfile = profile.into();
 
@@ -924,6 +1068,7 @@ mod test {
                 sts_role_arn: Some(sts_role_arn),
                 flavor: S3Flavor::Aws,
                 sts_enabled: true,
+                server_side_encryption: None,
             }
             .into();
 
@@ -990,11 +1135,82 @@ mod test {
         let policy = S3Profile::get_aws_policy_string(
             &table_location.parse().unwrap(),
             StoragePermissions::ReadWriteDelete,
+            None,
         )
         .unwrap();
         let _ = serde_json::from_str::<serde_json::Value>(&policy).unwrap();
     }
 
+    #[test]
+    fn policy_string_with_kms_key_is_json() {
+        let table_location = "s3://bucket-name/path/to/table";
+        let kms_key_id = "arn:aws:kms:eu-central-1:123456789012:key/my-key";
+        let policy = S3Profile::get_aws_policy_string(
+            &table_location.parse().unwrap(),
+            StoragePermissions::Read,
+            Some(&S3ServerSideEncryption::SseKms {
+                kms_key_id: Some(kms_key_id.to_string()),
+            }),
+        )
+        .unwrap();
+        let policy = serde_json::from_str::<serde_json::Value>(&policy).unwrap();
+
+        let statements = policy["Statement"].as_array().unwrap();
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[2]["Resource"], kms_key_id);
+        assert_eq!(statements[2]["Action"], serde_json::json!(["kms:Decrypt"]));
+    }
+
+    #[test]
+    fn test_deserialize_server_side_encryption() {
+        let profile: S3Profile = serde_json::from_value(serde_json::json!({
+            "bucket": "test-bucket",
+            "region": "eu-central-1",
+            "sts-enabled": false,
+            "server-side-encryption": {
+                "type": "sse-kms",
+                "kms-key-id": "my-key"
+            }
+        }))
+        .unwrap();
+
+        let sse = profile.server_side_encryption.unwrap();
+        assert_eq!(sse.header_value(), "aws:kms");
+        assert_eq!(sse.iceberg_sse_type(), "kms");
+        assert_eq!(sse.kms_key_id(), Some("my-key"));
+    }
+
+    #[test]
+    fn test_file_io_with_server_side_encryption() {
+        let profile_with = |sse: S3ServerSideEncryption| S3Profile {
+            bucket: "test-bucket".to_string(),
+            key_prefix: None,
+            assume_role_arn: None,
+            endpoint: None,
+            region: "us-east-1".to_string(),
+            path_style_access: None,
+            sts_role_arn: None,
+            flavor: S3Flavor::Aws,
+            sts_enabled: false,
+            server_side_encryption: Some(sse),
+        };
+
+        profile_with(S3ServerSideEncryption::SseS3)
+            .file_io(None)
+            .unwrap();
+        profile_with(S3ServerSideEncryption::SseKms {
+            kms_key_id: Some("my-key".to_string()),
+        })
+        .file_io(None)
+        .unwrap();
+        let err = profile_with(S3ServerSideEncryption::DsseKms {
+            kms_key_id: Some("my-key".to_string()),
+        })
+        .file_io(None)
+        .unwrap_err();
+        assert!(matches!(err, FileIoError::UnsupportedAction(_)));
+    }
+
     #[test]
     fn test_parse_s3_location_invalid_proto() {
         S3Location::from_str("adls://test-bucket/foo/").unwrap_err();
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+
+/// A single step of the storage validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageValidationStep {
+    /// Normalize and statically validate the storage profile.
+    Normalize,
+    /// Create a FileIO from the storage profile and credential.
+    FileIo,
+    /// Write a test file with the storage credential.
+    Write,
+    /// Read the test file with the storage credential.
+    Read,
+    /// Delete the test file with the storage credential.
+    Delete,
+    /// Generate vended-credentials and use them to write, read and delete a test file.
+    VendedCredentials,
+    /// Remove all files written during validation.
+    Cleanup,
+}
+
+impl StorageValidationStep {
+    const ALL: [StorageValidationStep; 7] = [
+        StorageValidationStep::Normalize,
+        StorageValidationStep::FileIo,
+        StorageValidationStep::Write,
+        StorageValidationStep::Read,
+        StorageValidationStep::Delete,
+        StorageValidationStep::VendedCredentials,
+        StorageValidationStep::Cleanup,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageValidationStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct StorageValidationCheck {
+    /// The validation step this result belongs to.
+    pub step: StorageValidationStep,
+    /// Outcome of the step.
+    pub status: StorageValidationStatus,
+    /// Why the step failed or was skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Underlying errors that caused the failure, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+}
+
+/// Per-step outcome of a storage validation.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct StorageValidationReport {
+    /// Checks in the order of the validation steps.
+    pub checks: Vec<StorageValidationCheck>,
+}
+
+impl StorageValidationReport {
+    /// True if no check failed.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|c| c.status != StorageValidationStatus::Failed)
+    }
+
+    /// Record the outcome of `step` and pass the result through.
+    pub(super) fn record<T, E: std::error::Error>(
+        &mut self,
+        step: StorageValidationStep,
+        result: Result<T, E>,
+    ) -> Result<T, E> {
+        let check = match &result {
+            Ok(_) => StorageValidationCheck {
+                step,
+                status: StorageValidationStatus::Passed,
+                message: None,
+                causes: vec![],
+            },
+            Err(e) => StorageValidationCheck {
+                step,
+                status: StorageValidationStatus::Failed,
+                message: Some(e.to_string()),
+                causes: error_causes(e),
+            },
+        };
+        self.checks.push(check);
+        result
+    }
+
+    pub(super) fn skip(&mut self, step: StorageValidationStep, reason: &str) {
+        self.checks.push(StorageValidationCheck {
+            step,
+            status: StorageValidationStatus::Skipped,
+            message: Some(reason.to_string()),
+            causes: vec![],
+        });
+    }
+
+    /// Mark all steps that did not run as skipped.
+    /// Cleanup also runs after failed steps, so checks are sorted by step afterwards.
+    pub(super) fn finish(mut self) -> Self {
+        for step in StorageValidationStep::ALL {
+            if !self.checks.iter().any(|c| c.step == step) {
+                self.skip(step, "A previous check failed.");
+            }
+        }
+        self.checks.sort_by_key(|c| {
+            StorageValidationStep::ALL
+                .iter()
+                .position(|step| *step == c.step)
+        });
+        self
+    }
+}
+
+fn error_causes(e: &dyn std::error::Error) -> Vec<String> {
+    let mut causes = vec![];
+    let mut source = e.source();
+    while let Some(s) = source {
+        causes.push(s.to_string());
+        source = s.source();
+    }
+    causes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_failed_check_marks_remaining_steps_skipped() {
+        let mut report = StorageValidationReport::default();
+        report
+            .record(
+                StorageValidationStep::Normalize,
+                Ok::<(), std::fmt::Error>(()),
+            )
+            .unwrap();
+        report
+            .record(StorageValidationStep::FileIo, Err::<(), _>(std::fmt::Error))
+            .unwrap_err();
+        let report = report.finish();
+
+        assert!(!report.is_success());
+        assert_eq!(report.checks.len(), StorageValidationStep::ALL.len());
+        assert_eq!(report.checks[0].status, StorageValidationStatus::Passed);
+        assert_eq!(report.checks[1].status, StorageValidationStatus::Failed);
+        assert!(report.checks[2..]
+            .iter()
+            .all(|c| c.status == StorageValidationStatus::Skipped));
+    }
+
+    #[test]
+    fn test_cleanup_after_failed_check_keeps_step_order() {
+        let mut report = StorageValidationReport::default();
+        for step in [
+            StorageValidationStep::Normalize,
+            StorageValidationStep::FileIo,
+            StorageValidationStep::Write,
+        ] {
+            report.record(step, Ok::<(), std::fmt::Error>(())).unwrap();
+        }
+        report
+            .record(StorageValidationStep::Read, Err::<(), _>(std::fmt::Error))
+            .unwrap_err();
+        report
+            .record(
+                StorageValidationStep::Cleanup,
+                Ok::<(), std::fmt::Error>(()),
+            )
+            .unwrap();
+        let report = report.finish();
+
+        assert_eq!(
+            report.checks.iter().map(|c| c.step).collect::<Vec<_>>(),
+            StorageValidationStep::ALL
+        );
+        assert_eq!(report.checks[3].status, StorageValidationStatus::Failed);
+        assert_eq!(report.checks[4].status, StorageValidationStatus::Skipped);
+        assert_eq!(report.checks[5].status, StorageValidationStatus::Skipped);
+        assert_eq!(report.checks[6].status, StorageValidationStatus::Passed);
+    }
+}
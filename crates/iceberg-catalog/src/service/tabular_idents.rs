@@ -1,11 +1,12 @@
 use iceberg::TableIdent;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::ops::Deref;
 use uuid::Uuid;
 
 use super::TableIdentUuid;
 
-#[derive(Hash, PartialOrd, PartialEq, Debug, Clone, Copy, Eq)]
+#[derive(Hash, PartialOrd, PartialEq, Debug, Clone, Copy, Eq, Serialize, Deserialize)]
 pub enum TabularIdentUuid {
     Table(Uuid),
     View(Uuid),
@@ -0,0 +1,269 @@
+//! Verification of opaque access tokens via OAuth 2.0 Token Introspection (RFC 7662).
+use http::StatusCode;
+use iceberg_ext::catalog::rest::ErrorModel;
+use ring::digest;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use url::Url;
+
+use super::token_verification::{Aud, Claims};
+use crate::config::TokenIntrospectionConfig;
+
+pub(crate) type TokenHash = [u8; 32];
+
+/// Introspects opaque tokens at the provider. Active tokens are cached by the hash
+/// of the token until they expire, but at most for the configured TTL.
+#[derive(Clone)]
+pub struct Introspector {
+    client: reqwest::Client,
+    endpoint: Url,
+    client_id: String,
+    client_secret: String,
+    cache_ttl: Duration,
+    cache: moka::future::Cache<TokenHash, Arc<Claims>>,
+}
+
+/// Response of the introspection endpoint, from
+/// [RFC 7662 Section 2.2](https://datatracker.ietf.org/doc/html/rfc7662#section-2.2).
+#[derive(Debug, Deserialize)]
+struct IntrospectionResponse {
+    active: bool,
+    sub: Option<String>,
+    username: Option<String>,
+    client_id: Option<String>,
+    iss: Option<String>,
+    aud: Option<Aud>,
+    exp: Option<usize>,
+    iat: Option<usize>,
+    #[serde(flatten)]
+    other: serde_json::Value,
+}
+
+/// Expires cached claims with the token, but at most after the configured TTL.
+struct ClaimsExpiry {
+    ttl: Duration,
+}
+
+impl moka::Expiry<TokenHash, Arc<Claims>> for ClaimsExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &TokenHash,
+        value: &Arc<Claims>,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(cache_duration(value.exp, now(), self.ttl))
+    }
+}
+
+impl Introspector {
+    #[must_use]
+    pub fn new(config: &TokenIntrospectionConfig) -> Self {
+        let cache_ttl = Duration::from_secs(config.cache_ttl_seconds);
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: config.endpoint.clone(),
+            client_id: config.client_id.clone(),
+            client_secret: config.client_secret.clone(),
+            cache_ttl,
+            cache: moka::future::Cache::builder()
+                .max_capacity(config.cache_max_entries)
+                .expire_after(ClaimsExpiry { ttl: cache_ttl })
+                .build(),
+        }
+    }
+
+    /// Introspect the token and return its claims.
+    ///
+    /// # Errors
+    /// - Fails with 401 if the token is not active.
+    /// - Fails with 502 if the introspection endpoint can't be reached.
+    pub async fn introspect(&self, token: &str) -> Result<Claims, ErrorModel> {
+        let key = token_hash(token);
+        if let Some(claims) = self.cache.get(&key).await {
+            return Ok(claims.as_ref().clone());
+        }
+
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .form(&[("token", token), ("token_type_hint", "access_token")])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::BAD_GATEWAY.into())
+                    .message("Failed to introspect token.".to_string())
+                    .r#type("TokenIntrospectionFailed".to_string())
+                    .source(Some(Box::new(e)))
+                    .build()
+            })?
+            .json::<IntrospectionResponse>()
+            .await
+            .map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::BAD_GATEWAY.into())
+                    .message("Failed to parse token introspection response.".to_string())
+                    .r#type("TokenIntrospectionFailed".to_string())
+                    .source(Some(Box::new(e)))
+                    .build()
+            })?;
+
+        let claims = claims_from_response(response, &self.endpoint, now(), self.cache_ttl)?;
+        self.cache.insert(key, Arc::new(claims.clone())).await;
+        Ok(claims)
+    }
+}
+
+impl std::fmt::Debug for Introspector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Introspector")
+            .field("endpoint", &self.endpoint)
+            .field("client_id", &self.client_id)
+            .field("entries", &self.cache.entry_count())
+            .finish_non_exhaustive()
+    }
+}
+
+fn claims_from_response(
+    response: IntrospectionResponse,
+    endpoint: &Url,
+    now: u64,
+    default_ttl: Duration,
+) -> Result<Claims, ErrorModel> {
+    if !response.active {
+        return Err(unauthorized("Token is not active."));
+    }
+
+    let now_usize = usize::try_from(now).unwrap_or(usize::MAX);
+    let exp = response.exp.unwrap_or_else(|| {
+        // Without exp the token is trusted for the cache TTL only
+        now_usize.saturating_add(usize::try_from(default_ttl.as_secs()).unwrap_or(usize::MAX))
+    });
+    if exp <= now_usize {
+        return Err(unauthorized("Token is expired."));
+    }
+
+    let sub = response
+        .sub
+        .or(response.username)
+        .or(response.client_id)
+        .ok_or_else(|| unauthorized("Introspected token has no subject."))?;
+
+    Ok(Claims {
+        sub,
+        iss: response
+            .iss
+            .unwrap_or_else(|| endpoint.origin().ascii_serialization()),
+        aud: response.aud.unwrap_or(Aud::Vec(vec![])),
+        exp,
+        iat: response.iat.unwrap_or(now_usize),
+        other: response.other,
+    })
+}
+
+pub(crate) fn cache_duration(exp: usize, now: u64, ttl: Duration) -> Duration {
+    let exp = u64::try_from(exp).unwrap_or(u64::MAX);
+    ttl.min(Duration::from_secs(exp.saturating_sub(now)))
+}
+
+pub(crate) fn token_hash(token: &str) -> TokenHash {
+    let mut hash = [0; 32];
+    hash.copy_from_slice(digest::digest(&digest::SHA256, token.as_bytes()).as_ref());
+    hash
+}
+
+pub(crate) fn now() -> u64 {
+    u64::try_from(chrono::Utc::now().timestamp()).unwrap_or_default()
+}
+
+fn unauthorized(message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .message(message)
+        .code(StatusCode::UNAUTHORIZED.into())
+        .r#type("UnauthorizedError")
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn endpoint() -> Url {
+        "https://idp.example.com/oauth2/introspect".parse().unwrap()
+    }
+
+    fn response(value: serde_json::Value) -> IntrospectionResponse {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_claims_from_active_response() {
+        let claims = claims_from_response(
+            response(serde_json::json!({
+                "active": true,
+                "client_id": "spark",
+                "username": "alice",
+                "exp": 2000,
+                "groups": ["analysts"],
+            })),
+            &endpoint(),
+            1000,
+            Duration::from_secs(300),
+        )
+        .unwrap();
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.iss, "https://idp.example.com");
+        assert_eq!(claims.exp, 2000);
+        assert_eq!(claims.other["groups"], serde_json::json!(["analysts"]));
+    }
+
+    #[test]
+    fn test_claims_from_inactive_or_expired_response() {
+        let err = claims_from_response(
+            response(serde_json::json!({"active": false})),
+            &endpoint(),
+            1000,
+            Duration::from_secs(300),
+        )
+        .unwrap_err();
+        assert_eq!(err.code, 401);
+
+        let err = claims_from_response(
+            response(serde_json::json!({"active": true, "sub": "alice", "exp": 900})),
+            &endpoint(),
+            1000,
+            Duration::from_secs(300),
+        )
+        .unwrap_err();
+        assert_eq!(err.code, 401);
+    }
+
+    #[test]
+    fn test_claims_without_exp_use_ttl() {
+        let claims = claims_from_response(
+            response(serde_json::json!({"active": true, "sub": "alice"})),
+            &endpoint(),
+            1000,
+            Duration::from_secs(300),
+        )
+        .unwrap();
+        assert_eq!(claims.exp, 1300);
+    }
+
+    #[test]
+    fn test_cache_duration_bounded_by_exp() {
+        let ttl = Duration::from_secs(300);
+        assert_eq!(cache_duration(1100, 1000, ttl), Duration::from_secs(100));
+        assert_eq!(cache_duration(5000, 1000, ttl), ttl);
+        assert_eq!(cache_duration(900, 1000, ttl), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_token_hash_differs() {
+        assert_ne!(token_hash("a"), token_hash("b"));
+        assert_eq!(token_hash("a"), token_hash("a"));
+    }
+}
@@ -0,0 +1,434 @@
+//! Short-lived tokens signed by the catalog itself.
+//!
+//! Tokens are signed with Ed25519 keys that are derived from a configured secret and the
+//! current rotation period. All replicas sharing the secret derive the same keys without
+//! coordination. Keys of the previous, current and next period are published as JWKS.
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use http::StatusCode;
+use iceberg_ext::catalog::rest::ErrorModel;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use ring::hmac;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+use super::token_verification::AuthDetails;
+use crate::config::TokenIssuerConfig;
+use crate::request_metadata::RequestMetadata;
+use crate::{ProjectIdent, WarehouseIdent, CONFIG};
+
+/// Audience of tokens issued by the catalog.
+pub const CATALOG_TOKEN_AUDIENCE: &str = "iceberg-catalog";
+/// Claim containing the warehouse a catalog token is scoped to.
+pub const WAREHOUSE_ID_CLAIM: &str = "warehouse_id";
+/// Claim containing the project of the warehouse.
+pub const PROJECT_ID_CLAIM: &str = "project_id";
+/// Claim containing the issuer of the principal a catalog token was issued for.
+/// Only trusted in tokens signed by the catalog.
+pub const IDENTITY_ISSUER_CLAIM: &str = "identity_iss";
+
+const KID_PREFIX: &str = "iceberg-catalog-";
+/// DER prefix of a PKCS#8 v1 encoded Ed25519 private key, followed by the 32 byte seed.
+const ED25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+
+#[derive(Clone)]
+pub struct TokenIssuer {
+    secret: hmac::Key,
+    issuer: String,
+    ttl_seconds: u64,
+    key_rotation_seconds: u64,
+}
+
+impl TokenIssuer {
+    /// # Errors
+    /// Fails if the TTL is zero or exceeds the key rotation interval.
+    pub fn new(config: &TokenIssuerConfig, issuer: String) -> anyhow::Result<Self> {
+        // A token must stay verifiable until it expires, keys are only published for
+        // the current and the previous rotation period.
+        anyhow::ensure!(
+            config.ttl_seconds > 0 && config.ttl_seconds <= config.key_rotation_seconds,
+            "Token TTL must be positive and must not exceed the key rotation interval"
+        );
+        Ok(Self {
+            secret: hmac::Key::new(hmac::HMAC_SHA256, config.signing_secret.as_bytes()),
+            issuer,
+            ttl_seconds: config.ttl_seconds,
+            key_rotation_seconds: config.key_rotation_seconds,
+        })
+    }
+
+    /// Issuer using the catalog's base uri as `iss`.
+    ///
+    /// # Errors
+    /// Fails if the configuration is invalid, see [`TokenIssuer::new`].
+    pub fn from_config(config: &TokenIssuerConfig) -> anyhow::Result<Self> {
+        Self::new(config, CONFIG.base_uri_catalog().to_string())
+    }
+
+    #[must_use]
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Whether the token was signed by a catalog key, judging by its header.
+    #[must_use]
+    pub fn is_catalog_token(header: &Header) -> bool {
+        header
+            .kid
+            .as_deref()
+            .is_some_and(|kid| kid.starts_with(KID_PREFIX))
+    }
+
+    /// Issue a token scoped to `warehouse_id` for the principal of `auth_details`.
+    /// All other claims of the original token are kept, so that authorization
+    /// handlers see the same groups and attributes. The token never outlives
+    /// the original token.
+    ///
+    /// Returns the token and its lifetime in seconds.
+    ///
+    /// # Errors
+    /// Fails if the token can't be signed.
+    pub fn issue(
+        &self,
+        auth_details: &AuthDetails,
+        project_id: &ProjectIdent,
+        warehouse_id: WarehouseIdent,
+    ) -> Result<(String, u64), ErrorModel> {
+        self.issue_at(auth_details, project_id, warehouse_id, now())
+    }
+
+    /// Issue a token for the authenticated user of a request.
+    /// Returns `None` if the request is not authenticated with a JWT.
+    ///
+    /// # Errors
+    /// Fails if the token can't be signed.
+    pub fn issue_for_request(
+        &self,
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        warehouse_id: WarehouseIdent,
+    ) -> Result<Option<(String, u64)>, ErrorModel> {
+        match &metadata.auth_details {
+            Some(auth_details) => self.issue(auth_details, project_id, warehouse_id).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn issue_at(
+        &self,
+        auth_details: &AuthDetails,
+        project_id: &ProjectIdent,
+        warehouse_id: WarehouseIdent,
+        now: u64,
+    ) -> Result<(String, u64), ErrorModel> {
+        let claims = auth_details.claims();
+        let original_exp = u64::try_from(claims.exp).unwrap_or(u64::MAX);
+        let exp = original_exp.min(now.saturating_add(self.ttl_seconds));
+
+        let mut payload = match &claims.other {
+            serde_json::Value::Object(other) => other.clone(),
+            _ => serde_json::Map::new(),
+        };
+        payload.insert("sub".to_string(), claims.sub.clone().into());
+        payload.insert("iss".to_string(), self.issuer.clone().into());
+        payload.insert(
+            IDENTITY_ISSUER_CLAIM.to_string(),
+            auth_details.principal_id().issuer.into(),
+        );
+        payload.insert("aud".to_string(), CATALOG_TOKEN_AUDIENCE.into());
+        payload.insert("iat".to_string(), now.into());
+        payload.insert("exp".to_string(), exp.into());
+        payload.insert(PROJECT_ID_CLAIM.to_string(), project_id.to_string().into());
+        payload.insert(
+            WAREHOUSE_ID_CLAIM.to_string(),
+            warehouse_id.to_string().into(),
+        );
+
+        let period = self.period(now);
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(kid(period));
+
+        let token =
+            jsonwebtoken::encode(&header, &payload, &self.encoding_key(period)).map_err(|e| {
+                ErrorModel::internal(
+                    "Failed to sign catalog token.",
+                    "TokenSigningFailed",
+                    Some(Box::new(e)),
+                )
+            })?;
+
+        Ok((token, exp.saturating_sub(now)))
+    }
+
+    /// Verify a token signed by the catalog.
+    ///
+    /// # Errors
+    /// Fails with 401 if the key is unknown or expired, or if the token is invalid.
+    pub(crate) fn decode<O: DeserializeOwned>(
+        &self,
+        token: &str,
+        header: &Header,
+    ) -> Result<O, ErrorModel> {
+        let current = self.period(now());
+        let period = header
+            .kid
+            .as_deref()
+            .and_then(|kid| kid.strip_prefix(KID_PREFIX))
+            .and_then(|period| period.parse::<u64>().ok())
+            .filter(|period| {
+                period.saturating_add(1) >= current && *period <= current.saturating_add(1)
+            })
+            .ok_or_else(|| unauthorized("Unknown or expired catalog signing key."))?;
+
+        let decoding_key =
+            DecodingKey::from_ed_components(&self.public_key(period)?).map_err(|e| {
+                ErrorModel::internal(
+                    "Failed to create decoding key.",
+                    "InternalServerError",
+                    Some(Box::new(e)),
+                )
+            })?;
+
+        let mut validation = Validation::new(Algorithm::EdDSA);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[CATALOG_TOKEN_AUDIENCE]);
+
+        jsonwebtoken::decode(token, &decoding_key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| {
+                tracing::debug!("Failed to decode catalog token: {}", e);
+                let mut error = unauthorized("Failed to decode token.");
+                error.source = Some(Box::new(e));
+                error
+            })
+    }
+
+    /// Public keys of the previous, current and next rotation period as JWKS.
+    ///
+    /// # Errors
+    /// Fails if a key can't be derived.
+    pub fn jwks(&self) -> Result<serde_json::Value, ErrorModel> {
+        let current = self.period(now());
+        let keys = [current.saturating_sub(1), current, current + 1]
+            .into_iter()
+            .map(|period| {
+                Ok(serde_json::json!({
+                    "kty": "OKP",
+                    "crv": "Ed25519",
+                    "use": "sig",
+                    "alg": "EdDSA",
+                    "kid": kid(period),
+                    "x": self.public_key(period)?,
+                }))
+            })
+            .collect::<Result<Vec<_>, ErrorModel>>()?;
+        Ok(serde_json::json!({ "keys": keys }))
+    }
+
+    fn period(&self, now: u64) -> u64 {
+        now / self.key_rotation_seconds
+    }
+
+    fn seed(&self, period: u64) -> hmac::Tag {
+        hmac::sign(&self.secret, format!("{KID_PREFIX}{period}").as_bytes())
+    }
+
+    fn encoding_key(&self, period: u64) -> EncodingKey {
+        let mut pkcs8 = ED25519_PKCS8_PREFIX.to_vec();
+        pkcs8.extend_from_slice(self.seed(period).as_ref());
+        EncodingKey::from_ed_der(&pkcs8)
+    }
+
+    /// Base64url encoded public key of the given period.
+    fn public_key(&self, period: u64) -> Result<String, ErrorModel> {
+        let key_pair =
+            Ed25519KeyPair::from_seed_unchecked(self.seed(period).as_ref()).map_err(|e| {
+                ErrorModel::internal(
+                    format!("Failed to derive catalog signing key: {e}"),
+                    "InternalServerError",
+                    None,
+                )
+            })?;
+        Ok(URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref()))
+    }
+}
+
+impl Debug for TokenIssuer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenIssuer")
+            .field("issuer", &self.issuer)
+            .field("ttl_seconds", &self.ttl_seconds)
+            .field("key_rotation_seconds", &self.key_rotation_seconds)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Warehouse-scoped credentials may only access the catalog API of their
+/// warehouse and the config endpoint.
+///
+/// # Errors
+/// Fails with 403 if the path is outside of the warehouse.
+pub(crate) fn check_warehouse_path(
+    warehouse_id: WarehouseIdent,
+    path: &str,
+) -> Result<(), ErrorModel> {
+    let allowed = path
+        .strip_prefix("/catalog/v1/")
+        .and_then(|rest| rest.split('/').next())
+        .is_some_and(|segment| segment == "config" || segment == warehouse_id.to_string().as_str());
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(ErrorModel::forbidden(
+            "Token is not valid for this resource",
+            "WarehouseScopeMismatch",
+            None,
+        ))
+    }
+}
+
+fn kid(period: u64) -> String {
+    format!("{KID_PREFIX}{period}")
+}
+
+fn now() -> u64 {
+    u64::try_from(chrono::Utc::now().timestamp()).unwrap_or_default()
+}
+
+fn unauthorized(message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .message(message)
+        .code(StatusCode::UNAUTHORIZED.into())
+        .r#type("UnauthorizedError")
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::service::token_verification::{Aud, Claims, PrincipalId};
+
+    fn issuer() -> TokenIssuer {
+        TokenIssuer::new(
+            &TokenIssuerConfig {
+                signing_secret: "secret".to_string(),
+                ttl_seconds: 3600,
+                key_rotation_seconds: 86400,
+            },
+            "https://catalog.example.com/catalog".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ttl_must_not_exceed_key_rotation() {
+        let config = |ttl_seconds, key_rotation_seconds| TokenIssuerConfig {
+            signing_secret: "secret".to_string(),
+            ttl_seconds,
+            key_rotation_seconds,
+        };
+        let issuer = "https://catalog.example.com/catalog";
+        assert!(TokenIssuer::new(&config(3600, 3600), issuer.to_string()).is_ok());
+        assert!(TokenIssuer::new(&config(0, 3600), issuer.to_string()).is_err());
+        assert!(TokenIssuer::new(&config(7200, 3600), issuer.to_string()).is_err());
+    }
+
+    fn claims(exp: usize) -> AuthDetails {
+        AuthDetails::JWT(Claims {
+            sub: "user-1".to_string(),
+            iss: "https://idp.example.com".to_string(),
+            aud: Aud::String("account".to_string()),
+            exp,
+            iat: 0,
+            other: serde_json::json!({"groups": ["analysts"]}),
+        })
+    }
+
+    #[test]
+    fn test_issue_and_decode() {
+        let issuer = issuer();
+        let project_id = ProjectIdent::from(uuid::Uuid::now_v7());
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+
+        let (token, expires_in) = issuer
+            .issue(&claims(usize::MAX), &project_id, warehouse_id)
+            .unwrap();
+        assert_eq!(expires_in, 3600);
+
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert!(TokenIssuer::is_catalog_token(&header));
+
+        let decoded: Claims = issuer.decode(&token, &header).unwrap();
+        assert_eq!(decoded.sub, "user-1");
+        assert_eq!(decoded.iss, "https://catalog.example.com/catalog");
+        assert_eq!(
+            AuthDetails::CatalogToken(decoded.clone()).principal_id(),
+            PrincipalId::new("https://idp.example.com", "user-1")
+        );
+        assert_eq!(
+            decoded.other[WAREHOUSE_ID_CLAIM],
+            serde_json::json!(warehouse_id.to_string())
+        );
+        assert_eq!(
+            decoded.other[PROJECT_ID_CLAIM],
+            serde_json::json!(project_id.to_string())
+        );
+        assert_eq!(decoded.other["groups"], serde_json::json!(["analysts"]));
+    }
+
+    #[test]
+    fn test_token_does_not_outlive_original() {
+        let issuer = issuer();
+        let now = 1_000_000;
+        let (_, expires_in) = issuer
+            .issue_at(
+                &claims(usize::try_from(now + 60).unwrap()),
+                &ProjectIdent::from(uuid::Uuid::now_v7()),
+                WarehouseIdent::from(uuid::Uuid::now_v7()),
+                now,
+            )
+            .unwrap();
+        assert_eq!(expires_in, 60);
+    }
+
+    #[test]
+    fn test_decode_rejects_other_secret() {
+        let (token, _) = issuer()
+            .issue(
+                &claims(usize::MAX),
+                &ProjectIdent::from(uuid::Uuid::now_v7()),
+                WarehouseIdent::from(uuid::Uuid::now_v7()),
+            )
+            .unwrap();
+        let other = TokenIssuer::new(
+            &TokenIssuerConfig {
+                signing_secret: "other".to_string(),
+                ttl_seconds: 3600,
+                key_rotation_seconds: 86400,
+            },
+            "https://catalog.example.com/catalog".to_string(),
+        )
+        .unwrap();
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        let err = other.decode::<Claims>(&token, &header).unwrap_err();
+        assert_eq!(err.code, 401);
+    }
+
+    #[test]
+    fn test_jwks_contains_signing_key() {
+        let issuer = issuer();
+        let jwks = issuer.jwks().unwrap();
+        let kids = jwks["keys"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|k| k["kid"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(kids.len(), 3);
+        assert!(kids.contains(&kid(issuer.period(now()))));
+    }
+}
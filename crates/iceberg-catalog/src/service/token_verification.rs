@@ -13,16 +13,140 @@ use jwks_client_rs::source::WebSource;
 use jwks_client_rs::{JsonWebKey, JwksClient};
 
 use crate::request_metadata::RequestMetadata;
+use crate::service::api_key::{is_api_key, ApiKeyAuthenticator, ApiKeyDetails};
+use crate::service::kubernetes_auth::{KubernetesAuthenticator, ServiceAccount};
+use crate::service::token_introspection::Introspector;
+use crate::service::token_issuer::{
+    check_warehouse_path, TokenIssuer, IDENTITY_ISSUER_CLAIM, PROJECT_ID_CLAIM, WAREHOUSE_ID_CLAIM,
+};
+use crate::{OpenIdProviderConfig, WarehouseIdent};
 use axum::Extension;
-use serde::de::DeserializeOwned;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use serde::Deserialize;
 use std::fmt::Debug;
 use std::str::FromStr;
+use std::sync::Arc;
 use url::Url;
 
 #[derive(Debug, Clone)]
 pub enum AuthDetails {
+    /// A JWT of a trusted OpenID provider.
     JWT(Claims),
+    /// A warehouse-scoped token issued by the catalog itself.
+    CatalogToken(Claims),
+    /// An opaque token, verified via token introspection.
+    Introspection(Claims),
+    /// An API key issued by the catalog.
+    ApiKey(ApiKeyDetails),
+    /// A Kubernetes service account token, verified via the `TokenReview` API.
+    KubernetesServiceAccount(ServiceAccount),
+}
+
+impl AuthDetails {
+    /// Claims of the token, independent of how it was verified.
+    #[must_use]
+    pub fn claims(&self) -> &Claims {
+        match self {
+            AuthDetails::JWT(claims)
+            | AuthDetails::CatalogToken(claims)
+            | AuthDetails::Introspection(claims) => claims,
+            AuthDetails::ApiKey(details) => &details.claims,
+            AuthDetails::KubernetesServiceAccount(service_account) => &service_account.claims,
+        }
+    }
+
+    /// Identity of the authenticated principal. Tokens and API keys issued by the
+    /// catalog act as the principal they were issued for.
+    #[must_use]
+    pub fn principal_id(&self) -> PrincipalId {
+        let claims = self.claims();
+        let issuer = match self {
+            AuthDetails::CatalogToken(claims) => claims
+                .other
+                .get(IDENTITY_ISSUER_CLAIM)
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or(&claims.iss),
+            AuthDetails::ApiKey(details) => &details.owner.issuer,
+            _ => &claims.iss,
+        };
+        PrincipalId::new(issuer, &claims.sub)
+    }
+
+    /// Warehouse-scoped tokens and API keys issued by the catalog may only access
+    /// the catalog API of their warehouse and the config endpoint.
+    ///
+    /// # Errors
+    /// Fails with 403 if the path is outside of the scope of the token.
+    pub fn check_path(&self, path: &str) -> Result<(), ErrorModel> {
+        match self {
+            AuthDetails::ApiKey(details) => details.check_path(path),
+            AuthDetails::CatalogToken(claims) => {
+                check_warehouse_path(catalog_token_warehouse(claims)?, path)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Warehouse a token issued by the catalog is scoped to.
+///
+/// # Errors
+/// Fails with 403 if the token has no valid `warehouse_id` claim.
+pub(crate) fn catalog_token_warehouse(claims: &Claims) -> Result<WarehouseIdent, ErrorModel> {
+    claims
+        .other
+        .get(WAREHOUSE_ID_CLAIM)
+        .and_then(serde_json::Value::as_str)
+        .and_then(|w| WarehouseIdent::from_str(w).ok())
+        .ok_or_else(|| {
+            ErrorModel::forbidden(
+                "Catalog token is not scoped to a warehouse",
+                "WarehouseScopeMismatch",
+                None,
+            )
+        })
+}
+
+/// Identity of a principal. Subjects are only unique per issuer, so principals
+/// are identified by both, formatted as `<issuer>|<subject>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrincipalId {
+    pub issuer: String,
+    pub subject: String,
+}
+
+impl PrincipalId {
+    #[must_use]
+    pub fn new(issuer: &str, subject: &str) -> Self {
+        Self {
+            issuer: issuer.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for PrincipalId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.issuer, self.subject)
+    }
+}
+
+impl FromStr for PrincipalId {
+    type Err = ErrorModel;
+
+    // Issuers are URLs and can't contain `|`, subjects can.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_once('|')
+            .map(|(issuer, subject)| Self::new(issuer, subject))
+            .ok_or_else(|| {
+                ErrorModel::bad_request(
+                    format!("Principal '{s}' must have the format '<issuer>|<subject>'"),
+                    "InvalidPrincipal",
+                    None,
+                )
+            })
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -47,13 +171,16 @@ pub(crate) async fn auth_middleware_fn(
     State(verifier): State<Verifier>,
     authorization: Option<TypedHeader<Authorization<Bearer>>>,
     Extension(mut metadata): Extension<RequestMetadata>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
     if let Some(authorization) = authorization {
-        match verifier.decode::<Claims>(authorization.token()).await {
-            Ok(val) => {
-                metadata.auth_details = Some(AuthDetails::JWT(val));
+        match verifier.authenticate(authorization.token()).await {
+            Ok(auth_details) => {
+                if let Err(err) = auth_details.check_path(request.uri().path()) {
+                    return IcebergErrorResponse::from(err).into_response();
+                }
+                metadata.auth_details = Some(auth_details);
             }
             Err(err) => {
                 tracing::debug!("Failed to verify token: {:?}", err);
@@ -71,107 +198,257 @@ pub(crate) async fn auth_middleware_fn(
         .into_response();
     }
 
+    // The extractor hands out a clone, put the enriched metadata back for the handlers
+    request.extensions_mut().insert(metadata);
     next.run(request).await
 }
 
+/// Verifies tokens of the trusted OpenID providers and, if enabled, of the catalog itself.
 #[derive(Clone)]
 pub struct Verifier {
+    providers: Arc<Vec<Provider>>,
+    token_issuer: Option<TokenIssuer>,
+    introspector: Option<Introspector>,
+    api_keys: Option<Arc<dyn ApiKeyAuthenticator>>,
+    kubernetes: Option<KubernetesAuthenticator>,
+}
+
+/// A trusted OpenID provider, identified by its issuer.
+struct Provider {
     client: JwksClient<WebSource>,
     issuer: String,
+    token_endpoint: Option<Url>,
+    config: OpenIdProviderConfig,
+}
+
+/// The unverified payload of a token, used to select the provider.
+#[derive(Deserialize)]
+struct UnverifiedIssuer {
+    iss: String,
 }
 
 impl Verifier {
     const WELL_KNOWN_CONFIG: &'static str = ".well-known/openid-configuration";
 
-    /// Create a new verifier with the given openid configuration url and audience.
+    /// Create a new verifier for a single provider with the given openid configuration url.
     ///
     /// # Errors
     ///
     /// This function can fail if the openid configuration cannot be fetched or parsed.
     /// This function can also fail if the `WebSource` cannot be built from the jwks uri in the
     /// fetched openid configuration
-    pub async fn new(mut url: Url) -> anyhow::Result<Self> {
+    pub async fn new(url: Url) -> anyhow::Result<Self> {
+        Self::from_providers(vec![OpenIdProviderConfig::new(url)]).await
+    }
+
+    /// Create a new verifier trusting all given providers.
+    ///
+    /// # Errors
+    /// Fails if the openid configuration of any provider cannot be fetched or parsed,
+    /// or if two providers have the same issuer.
+    pub async fn from_providers(configs: Vec<OpenIdProviderConfig>) -> anyhow::Result<Self> {
+        let mut providers: Vec<Provider> = Vec::with_capacity(configs.len());
+        for config in configs {
+            let provider = Provider::discover(config).await?;
+            if providers.iter().any(|p| p.issuer == provider.issuer) {
+                return Err(anyhow::anyhow!(
+                    "Issuer {} is configured more than once",
+                    provider.issuer
+                ));
+            }
+            if provider.config.audiences.is_empty() {
+                tracing::warn!(
+                    "Audience of tokens issued by {} is not validated.",
+                    provider.issuer
+                );
+            }
+            providers.push(provider);
+        }
+
+        Ok(Self {
+            providers: Arc::new(providers),
+            token_issuer: None,
+            introspector: None,
+            api_keys: None,
+            kubernetes: None,
+        })
+    }
+
+    /// Additionally accept tokens signed by the catalog's own issuer.
+    #[must_use]
+    pub fn with_token_issuer(mut self, token_issuer: TokenIssuer) -> Self {
+        self.token_issuer = Some(token_issuer);
+        self
+    }
+
+    /// Introspect tokens that are not valid JWTs.
+    #[must_use]
+    pub fn with_introspector(mut self, introspector: Introspector) -> Self {
+        self.introspector = Some(introspector);
+        self
+    }
+
+    /// Accept API keys issued by the catalog.
+    #[must_use]
+    pub fn with_api_keys(mut self, api_keys: Arc<dyn ApiKeyAuthenticator>) -> Self {
+        self.api_keys = Some(api_keys);
+        self
+    }
+
+    /// Accept Kubernetes service account tokens.
+    #[must_use]
+    pub fn with_kubernetes(mut self, kubernetes: KubernetesAuthenticator) -> Self {
+        self.kubernetes = Some(kubernetes);
+        self
+    }
+
+    /// Issuer of catalog tokens, if enabled.
+    #[must_use]
+    pub fn token_issuer(&self) -> Option<&TokenIssuer> {
+        self.token_issuer.as_ref()
+    }
+
+    /// Token endpoint of the first provider that advertises one.
+    #[must_use]
+    pub fn token_endpoint(&self) -> Option<&Url> {
+        self.providers
+            .iter()
+            .find_map(|provider| provider.token_endpoint.as_ref())
+    }
+
+    /// Verify a token issued by a trusted provider or the catalog. Tokens that
+    /// are not JWTs are introspected if introspection is enabled, JWTs are never
+    /// introspected. API keys are looked up in the catalog and Kubernetes service
+    /// account tokens are reviewed by the cluster if enabled.
+    ///
+    /// # Errors
+    /// Fails with 401 if the token is invalid.
+    pub async fn authenticate(&self, token: &str) -> Result<AuthDetails, ErrorModel> {
+        if let Some(api_keys) = self.api_keys.as_ref() {
+            if is_api_key(token) {
+                return api_keys.authenticate(token).await.map(AuthDetails::ApiKey);
+            }
+        }
+
+        let header = match jsonwebtoken::decode_header(token) {
+            Ok(header) => header,
+            Err(e) => {
+                return if let Some(introspector) = &self.introspector {
+                    tracing::debug!("Token is not a JWT, introspecting: {}", e);
+                    introspector
+                        .introspect(token)
+                        .await
+                        .map(AuthDetails::Introspection)
+                } else {
+                    Err(ErrorModel::builder()
+                        .message("Failed to decode auth token header.")
+                        .code(StatusCode::UNAUTHORIZED.into())
+                        .r#type("UnauthorizedError")
+                        .source(Some(Box::new(e)))
+                        .build())
+                };
+            }
+        };
+
+        match self.decode(token, &header).await {
+            Ok(auth_details) => Ok(auth_details),
+            Err(e) => {
+                if let Some(kubernetes) = &self.kubernetes {
+                    if KubernetesAuthenticator::is_service_account_token(token) {
+                        return kubernetes
+                            .authenticate(token)
+                            .await
+                            .map(AuthDetails::KubernetesServiceAccount);
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn decode(&self, token: &str, header: &Header) -> Result<AuthDetails, ErrorModel> {
+        if let Some(token_issuer) = self.token_issuer.as_ref() {
+            if TokenIssuer::is_catalog_token(header) {
+                return token_issuer
+                    .decode(token, header)
+                    .map(AuthDetails::CatalogToken);
+            }
+        }
+
+        let issuer = unverified_issuer(token)?;
+        let provider = self
+            .providers
+            .iter()
+            .find(|provider| provider.issuer == issuer)
+            .ok_or_else(|| {
+                tracing::debug!("Token issued by untrusted issuer {issuer}");
+                unauthorized("Token is not issued by a trusted issuer.")
+            })?;
+
+        let mut claims = provider.decode(token, header).await?;
+        map_claims(&provider.config, &mut claims);
+
+        serde_json::from_value(serde_json::Value::Object(claims))
+            .map(AuthDetails::JWT)
+            .map_err(|e| {
+                let mut error = unauthorized("Token does not contain the required claims.");
+                error.source = Some(Box::new(e));
+                error
+            })
+    }
+}
+
+impl Provider {
+    async fn discover(config: OpenIdProviderConfig) -> anyhow::Result<Self> {
+        let mut url = config.provider_uri.clone();
         if !url.path().ends_with('/') {
             url.set_path(&format!("{}/", url.path()));
         }
-        let config = reqwest::get(url.join(Self::WELL_KNOWN_CONFIG)?)
+        let well_known = reqwest::get(url.join(Verifier::WELL_KNOWN_CONFIG)?)
             .await
-            .context("Failed to fetch openid configuration")?
+            .with_context(|| format!("Failed to fetch openid configuration of {url}"))?
             .json::<WellKnownConfig>()
             .await
-            .context("Failed to parse openid configuration")?;
-        let source = WebSource::builder().build(config.jwks_uri)?;
+            .with_context(|| format!("Failed to parse openid configuration of {url}"))?;
+        let source = WebSource::builder().build(well_known.jwks_uri)?;
         let client = JwksClient::builder().build(source);
         Ok(Self {
             client,
-            issuer: config.issuer,
+            issuer: well_known.issuer,
+            token_endpoint: well_known.token_endpoint,
+            config,
         })
     }
 
     // this function is mostly lifted out of jwks_client_rs which is incompatible with azure jwks.
-    async fn decode<O: DeserializeOwned>(&self, token: &str) -> Result<O, ErrorModel> {
-        let header: Header = jsonwebtoken::decode_header(token).map_err(|e| {
-            ErrorModel::builder()
-                .message("Failed to decode auth token header.")
-                .code(StatusCode::UNAUTHORIZED.into())
-                .r#type("UnauthorizedError")
-                .source(Some(Box::new(e)))
-                .build()
-        })?;
-
-        if let Some(kid) = header.kid.as_ref() {
-            let key: JsonWebKey = self
-                .client
-                .get_opt(kid)
-                .await
-                .map_err(|e| Self::internal_error(e, "Failed to fetch key from jwks endpoint."))?
-                .ok_or_else(|| {
-                    ErrorModel::builder()
-                        .message("Unknown kid")
-                        .r#type("UnauthorizedError")
-                        .code(StatusCode::UNAUTHORIZED.into())
-                        .build()
-                })?;
-
-            let validation = self.setup_validation(&header, &key)?;
-            let decoding_key = Self::setup_decoding_key(key)?;
+    async fn decode(
+        &self,
+        token: &str,
+        header: &Header,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, ErrorModel> {
+        let kid = header
+            .kid
+            .as_ref()
+            .ok_or_else(|| unauthorized("Token header does not contain a key id."))?;
 
-            return Ok(jsonwebtoken::decode(token, &decoding_key, &validation)
-                .map_err(|e| {
-                    tracing::debug!("Failed to decode token: {}", e);
-                    ErrorModel::builder()
-                        .message("Failed to decode token.")
-                        .code(StatusCode::UNAUTHORIZED.into())
-                        .r#type("UnauthorizedError")
-                        .source(Some(Box::new(e)))
-                        .build()
-                })?
-                .claims);
-        }
+        let key: JsonWebKey = self
+            .client
+            .get_opt(kid)
+            .await
+            .map_err(|e| internal_error(e, "Failed to fetch key from jwks endpoint."))?
+            .ok_or_else(|| unauthorized("Unknown kid"))?;
 
-        Err(ErrorModel::builder()
-            .message("Token header does not contain a key id.")
-            .code(StatusCode::UNAUTHORIZED.into())
-            .r#type("UnauthorizedError")
-            .build())
-    }
+        let validation = self.setup_validation(header, &key)?;
+        let decoding_key = setup_decoding_key(key)?;
 
-    fn setup_decoding_key(key: JsonWebKey) -> Result<DecodingKey, ErrorModel> {
-        let decoding_key = match key {
-            JsonWebKey::Rsa(jwk) => DecodingKey::from_rsa_components(jwk.modulus(), jwk.exponent())
-                .map_err(|e| {
-                    Self::internal_error(
-                        e,
-                        "Failed to create rsa decoding key from key components.",
-                    )
-                })?,
-            JsonWebKey::Ec(jwk) => {
-                DecodingKey::from_ec_components(jwk.x(), jwk.y()).map_err(|e| {
-                    Self::internal_error(e, "Failed to create ec decoding key from key components.")
-                })?
-            }
-        };
-        Ok(decoding_key)
+        Ok(jsonwebtoken::decode(token, &decoding_key, &validation)
+            .map_err(|e| {
+                tracing::debug!("Failed to decode token: {}", e);
+                let mut error = unauthorized("Failed to decode token.");
+                error.source = Some(Box::new(e));
+                error
+            })?
+            .claims)
     }
 
     fn setup_validation(
@@ -181,7 +458,7 @@ impl Verifier {
     ) -> Result<Validation, ErrorModel> {
         let mut validation = if let Some(alg) = key.alg() {
             Validation::new(Algorithm::from_str(alg).map_err(|e| {
-                Self::internal_error(
+                internal_error(
                     e,
                     "Failed to parse algorithm from key obtained from the jwks endpoint.",
                 )
@@ -192,30 +469,94 @@ impl Verifier {
             Validation::new(header.alg)
         };
 
-        // TODO: aud validation in multi-tenant setups
-        validation.validate_aud = false;
-
+        if self.config.audiences.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(self.config.audiences.as_slice());
+        }
+        validation.leeway = self.config.clock_skew_seconds;
         validation.set_issuer(&[&self.issuer]);
 
         Ok(validation)
     }
+}
 
-    fn internal_error(
-        e: impl std::error::Error + Sync + Send + 'static,
-        message: &str,
-    ) -> ErrorModel {
-        ErrorModel::builder()
-            .message(message)
-            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
-            .r#type("InternalServerError")
-            .source(Some(Box::new(e)))
-            .build()
+/// Copy the configured claims to the names the catalog expects. If a configured
+/// claim is missing, the claim of the same name is removed instead of being trusted.
+fn map_claims(
+    config: &OpenIdProviderConfig,
+    claims: &mut serde_json::Map<String, serde_json::Value>,
+) {
+    let mappings = [
+        (Some(&config.subject_claim), "sub"),
+        (config.groups_claim.as_ref(), "groups"),
+        (config.project_claim.as_ref(), PROJECT_ID_CLAIM),
+    ];
+    for (source, target) in mappings {
+        let Some(source) = source.filter(|source| source.as_str() != target) else {
+            continue;
+        };
+        match claims.get(source).cloned() {
+            Some(value) => claims.insert(target.to_string(), value),
+            None => claims.remove(target),
+        };
     }
 }
 
+/// Read the `iss` claim without verifying the token. Only used to select the provider
+/// that verifies the token.
+fn unverified_issuer(token: &str) -> Result<String, ErrorModel> {
+    token
+        .split('.')
+        .nth(1)
+        .and_then(|payload| URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok())
+        .and_then(|payload| serde_json::from_slice::<UnverifiedIssuer>(&payload).ok())
+        .map(|payload| payload.iss)
+        .ok_or_else(|| unauthorized("Token does not contain an issuer."))
+}
+
+fn setup_decoding_key(key: JsonWebKey) -> Result<DecodingKey, ErrorModel> {
+    let decoding_key = match key {
+        JsonWebKey::Rsa(jwk) => DecodingKey::from_rsa_components(jwk.modulus(), jwk.exponent())
+            .map_err(|e| {
+                internal_error(e, "Failed to create rsa decoding key from key components.")
+            })?,
+        JsonWebKey::Ec(jwk) => DecodingKey::from_ec_components(jwk.x(), jwk.y()).map_err(|e| {
+            internal_error(e, "Failed to create ec decoding key from key components.")
+        })?,
+    };
+    Ok(decoding_key)
+}
+
+fn unauthorized(message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .message(message)
+        .code(StatusCode::UNAUTHORIZED.into())
+        .r#type("UnauthorizedError")
+        .build()
+}
+
+fn internal_error(e: impl std::error::Error + Sync + Send + 'static, message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .message(message)
+        .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+        .r#type("InternalServerError")
+        .source(Some(Box::new(e)))
+        .build()
+}
+
 impl Debug for Verifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Verifier").finish()
+        f.debug_struct("Verifier")
+            .field(
+                "issuers",
+                &self.providers.iter().map(|p| &p.issuer).collect::<Vec<_>>(),
+            )
+            .field("token_issuer", &self.token_issuer)
+            .field("introspector", &self.introspector)
+            .field("api_keys", &self.api_keys)
+            .field("kubernetes", &self.kubernetes)
+            .finish()
     }
 }
 
@@ -225,11 +566,137 @@ pub struct WellKnownConfig {
     pub other: serde_json::Value,
     pub jwks_uri: Url,
     pub issuer: String,
+    pub token_endpoint: Option<Url>,
 }
 
 #[cfg(test)]
 mod test {
+    use super::{map_claims, unverified_issuer, AuthDetails, PrincipalId};
     use crate::service::token_verification::Claims;
+    use crate::{OpenIdProviderConfig, WarehouseIdent};
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_unverified_issuer() {
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"iss":"https://idp.example.com","sub":"1"}"#);
+        let token = format!("e30.{payload}.c2ln");
+        assert_eq!(
+            unverified_issuer(&token).unwrap(),
+            "https://idp.example.com"
+        );
+        assert_eq!(unverified_issuer("not-a-token").unwrap_err().code, 401);
+    }
+
+    #[test]
+    fn test_map_claims() {
+        let mut config = OpenIdProviderConfig::new("https://idp.example.com".parse().unwrap());
+        config.subject_claim = "oid".to_string();
+        config.groups_claim = Some("roles".to_string());
+        config.project_claim = Some("tenant".to_string());
+
+        let mut claims = serde_json::json!({
+            "sub": "app-1",
+            "oid": "object-1",
+            "roles": ["writer"],
+            "tenant": "01f2fdfc-81fc-444d-8368-5b6701566e35",
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        map_claims(&config, &mut claims);
+
+        assert_eq!(claims["sub"], "object-1");
+        assert_eq!(claims["groups"], serde_json::json!(["writer"]));
+        assert_eq!(claims["project_id"], "01f2fdfc-81fc-444d-8368-5b6701566e35");
+    }
+
+    #[test]
+    fn test_map_claims_removes_missing() {
+        let mut config = OpenIdProviderConfig::new("https://idp.example.com".parse().unwrap());
+        config.groups_claim = Some("roles".to_string());
+        config.project_claim = Some("tenant".to_string());
+
+        let mut claims = serde_json::json!({
+            "sub": "user-1",
+            "groups": ["a"],
+            "project_id": "01f2fdfc-81fc-444d-8368-5b6701566e35",
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        map_claims(&config, &mut claims);
+
+        assert_eq!(claims["sub"], "user-1");
+        assert!(!claims.contains_key("groups"));
+        assert!(!claims.contains_key("project_id"));
+    }
+
+    #[test]
+    fn test_principal_id() {
+        let principal = PrincipalId::from_str("https://idp.example.com|auth0|1").unwrap();
+        assert_eq!(principal.issuer, "https://idp.example.com");
+        assert_eq!(principal.subject, "auth0|1");
+        assert_eq!(principal.to_string(), "https://idp.example.com|auth0|1");
+        assert!(PrincipalId::from_str("alice").is_err());
+    }
+
+    #[test]
+    fn test_principal_id_of_catalog_token() {
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "sub": "alice",
+            "iat": 22,
+            "aud": "iceberg-catalog",
+            "iss": "https://catalog.example.com/catalog",
+            "exp": 9022,
+            "identity_iss": "https://idp.example.com",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            AuthDetails::CatalogToken(claims.clone()).principal_id(),
+            PrincipalId::new("https://idp.example.com", "alice")
+        );
+        // Only trusted in tokens signed by the catalog
+        assert_eq!(
+            AuthDetails::JWT(claims).principal_id(),
+            PrincipalId::new("https://catalog.example.com/catalog", "alice")
+        );
+    }
+
+    #[test]
+    fn test_catalog_token_check_path() {
+        let warehouse_id = uuid::Uuid::now_v7();
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "sub": "alice",
+            "iat": 22,
+            "aud": "iceberg-catalog",
+            "iss": "https://catalog.example.com/catalog",
+            "exp": 9022,
+            "warehouse_id": warehouse_id.to_string(),
+        }))
+        .unwrap();
+
+        let scoped = AuthDetails::CatalogToken(claims.clone());
+        scoped.check_path("/catalog/v1/config").unwrap();
+        scoped
+            .check_path(&format!("/catalog/v1/{warehouse_id}/namespaces"))
+            .unwrap();
+        let other = uuid::Uuid::now_v7();
+        assert_eq!(
+            scoped
+                .check_path(&format!("/catalog/v1/{other}/namespaces"))
+                .unwrap_err()
+                .code,
+            403
+        );
+        scoped.check_path("/management/v1/api-key").unwrap_err();
+
+        AuthDetails::JWT(claims)
+            .check_path("/management/v1/api-key")
+            .unwrap();
+    }
 
     #[test]
     fn test_aud_with_array() {
@@ -87,6 +87,32 @@ impl Location {
 
         self.to_string().starts_with(other_folder.as_str())
     }
+
+    /// All locations of which this location is a sublocation, from the root
+    /// to the location itself. Returned locations have no trailing slash.
+    ///
+    /// `s3://bucket/foo/bar` yields `s3://bucket`, `s3://bucket/foo` and `s3://bucket/foo/bar`.
+    #[must_use]
+    pub fn partial_locations(&self) -> Vec<Location> {
+        let mut location = self.clone();
+        location.without_trailing_slash();
+        let full = location.as_str();
+        let path = location.0.path();
+        let root = &full[..full.len() - path.len()];
+
+        let mut partial = root.to_string();
+        let mut locations = vec![partial.clone()];
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            partial.push('/');
+            partial.push_str(segment);
+            locations.push(partial.clone());
+        }
+
+        locations
+            .into_iter()
+            .filter_map(|l| url::Url::parse(&l).ok().map(Location))
+            .collect()
+    }
 }
 
 impl ConfigProperty for Location {
@@ -176,4 +202,33 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_partial_locations() {
+        let cases = vec![
+            ("s3://bucket", vec!["s3://bucket"]),
+            ("s3://bucket/", vec!["s3://bucket"]),
+            (
+                "s3://bucket/foo/bar/",
+                vec!["s3://bucket", "s3://bucket/foo", "s3://bucket/foo/bar"],
+            ),
+            (
+                "abfss://filesystem@account.dfs.core.windows.net/foo",
+                vec![
+                    "abfss://filesystem@account.dfs.core.windows.net",
+                    "abfss://filesystem@account.dfs.core.windows.net/foo",
+                ],
+            ),
+        ];
+
+        for (location, expected) in cases {
+            let location = Location::from_str(location).unwrap();
+            let partial_locations = location.partial_locations();
+            let partial_locations = partial_locations
+                .iter()
+                .map(Location::as_str)
+                .collect::<Vec<_>>();
+            assert_eq!(partial_locations, expected, "Location: {location}");
+        }
+    }
 }
@@ -61,6 +61,8 @@ pub mod s3 {
             RemoteSigningEnabled, bool, "s3.remote-signing-enabled", "s3_remote_signing_enabled";
             Signer, String, "s3.signer", "s3_signer";
             SignerUri, String, "s3.signer.uri", "s3_signer_uri";
+            SseType, String, "s3.sse.type", "s3_sse_type";
+            SseKey, String, "s3.sse.key", "s3_sse_key";
          }
     );
 }
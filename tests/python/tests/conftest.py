@@ -84,6 +84,21 @@ class Server:
     management_url: str
     access_token: str
 
+    def create_project(self, name: str) -> uuid.UUID:
+        """Create a project in this server"""
+        response = requests.post(
+            self.project_url,
+            json={"project-name": name},
+            headers={"Authorization": f"Bearer {self.access_token}"},
+        )
+        if not response.ok:
+            raise ValueError(
+                f"Failed to create project ({response.status_code}): {response.text}"
+            )
+
+        project_id = response.json()["project-id"]
+        return uuid.UUID(project_id)
+
     def create_warehouse(
         self, name: str, project_id: uuid.UUID, sts_enabled: bool
     ) -> uuid.UUID:
@@ -114,6 +129,10 @@ class Server:
     def warehouse_url(self) -> str:
         return urllib.parse.urljoin(self.management_url, "v1/warehouse")
 
+    @property
+    def project_url(self) -> str:
+        return urllib.parse.urljoin(self.management_url, "v1/project")
+
 
 @dataclasses.dataclass
 class Warehouse:
@@ -185,8 +204,8 @@ def server(access_token) -> Server:
 @pytest.fixture(scope="session", params=STS)
 def warehouse(server: Server, request) -> Warehouse:
     sts_enabled = request.param
-    project_id = uuid.uuid4()
     test_id = uuid.uuid4()
+    project_id = server.create_project(f"project-{test_id}")
     warehouse_name = f"warehouse-{test_id}"
     warehouse_id = server.create_warehouse(
         warehouse_name, project_id=project_id, sts_enabled=sts_enabled